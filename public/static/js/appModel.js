@@ -1,15 +1,64 @@
 "use strict";
 
-define(["knockout", "localStorage", "messageModel", "mapModel"], function(ko, storage, message, map) {
+define(["knockout", "reqwest", "localStorage", "messageModel", "mapModel", "permalink", "objectGroups"], function(
+	ko,
+	reqwest,
+	storage,
+	message,
+	map,
+	permalink,
+	objectGroups
+) {
+	// Longer permalinks stored on server and replaced with short identifier
+	const MAX_LINK_LENGTH = 2000;
+
+	// Collect vertices of inspected objects, only objects requested in inspection mode have point indices
+	const collectVertices = function(features) {
+		const result = [];
+
+		for (const feature of features) {
+			const parts = feature.lines || feature.polygons;
+
+			parts.forEach(function(points, part) {
+				for (const point of points) {
+					if (point.index !== undefined) {
+						result.push({
+							id: feature.id,
+							part: part,
+							index: point.index,
+							lat: point.lat,
+							lon: point.lon,
+							duplicate: point.duplicate,
+						});
+					}
+				}
+			});
+		}
+
+		return result;
+	};
+
 	return function() {
 		const self = this;
 
 		this.isConnectionVisible = ko.observable(false);
 		this.isObjectsVisible = ko.observable(false);
+		this.isExportVisible = ko.observable(false);
+		this.isPermalinkVisible = ko.observable(false);
+		this.isFormatTilesVisible = ko.observable(false);
+		this.isDiffVisible = ko.observable(false);
+		this.isSqlVisible = ko.observable(false);
+		this.permalink = ko.observable("");
+		this.objectsRequests = ko.observableArray();
+		// Parameter and variable values last used to show objects of every format
+		this.formatValues = ko.observable({});
 		this.isClearVisible = ko.observable(false);
 		this.isObjectsEnabled = ko.observable(false);
 		this.isClearEnabled = ko.observable(false);
 		this.features = ko.observableArray();
+		this.vertices = ko.observableArray();
+		this.isLoginVisible = ko.observable(false);
+		this.userName = ko.observable(null);
 		this.messages = ko.observableArray();
 
 		this.clearMessages = function() {
@@ -22,14 +71,37 @@ define(["knockout", "localStorage", "messageModel", "mapModel"], function(ko, st
 			});
 
 			self.features(featureNames);
+			self.vertices(collectVertices(features));
+		};
+
+		// Objects of format tiles have no geometry details, so no vertices shown for them
+		this.featuresIdentified = function(features) {
+			const featureNames = features.map(function(feature) {
+				return feature.format + ": " + feature.names + " (" + feature.id + ")";
+			});
+
+			self.features(
+				featureNames.filter(function(name, index) {
+					return featureNames.indexOf(name) === index;
+				})
+			);
+			self.vertices([]);
 		};
 
-		this.map = map.create(this.featuresSelected);
+		this.map = map.create(this.featuresSelected, this.featuresIdentified);
+
+		this.isUserVisible = ko.pureComputed(function() {
+			return this.userName() !== null;
+		}, this);
 
 		this.isObjectsDisabled = ko.pureComputed(function() {
 			return !this.isObjectsEnabled();
 		}, this);
 
+		this.isExportDisabled = ko.pureComputed(function() {
+			return !this.isObjectsEnabled() || this.map.mapObjects().length === 0;
+		}, this);
+
 		this.isClearDisabled = ko.pureComputed(function() {
 			return !this.isClearEnabled();
 		}, this);
@@ -39,6 +111,11 @@ define(["knockout", "localStorage", "messageModel", "mapModel"], function(ko, st
 
 			self.isConnectionVisible(!oldState);
 			self.isObjectsVisible(false);
+			self.isExportVisible(false);
+			self.isPermalinkVisible(false);
+			self.isFormatTilesVisible(false);
+			self.isDiffVisible(false);
+			self.isSqlVisible(false);
 		};
 
 		this.hideConnection = function() {
@@ -50,7 +127,13 @@ define(["knockout", "localStorage", "messageModel", "mapModel"], function(ko, st
 				const oldState = self.isObjectsVisible();
 
 				self.isConnectionVisible(false);
+				self.isExportVisible(false);
+				self.isPermalinkVisible(false);
+				self.isFormatTilesVisible(false);
+				self.isDiffVisible(false);
+				self.isSqlVisible(false);
 				self.isObjectsVisible(!oldState);
+				self.map.requestedView(null);
 			}
 		};
 
@@ -62,6 +145,192 @@ define(["knockout", "localStorage", "messageModel", "mapModel"], function(ko, st
 			self.isObjectsVisible(false);
 		};
 
+		this.showExport = function() {
+			if (!self.isExportDisabled()) {
+				const oldState = self.isExportVisible();
+
+				self.isConnectionVisible(false);
+				self.isObjectsVisible(false);
+				self.isPermalinkVisible(false);
+				self.isFormatTilesVisible(false);
+				self.isDiffVisible(false);
+				self.isSqlVisible(false);
+				self.isExportVisible(!oldState);
+			}
+		};
+
+		this.hideExport = function() {
+			self.isExportVisible(false);
+		};
+
+		// Objects shown on the map grouped by format, parameters and variables. Objects of SQL console are not shared.
+		this.collectSharedObjects = function() {
+			return objectGroups.group(self.map.mapObjects());
+		};
+
+		this.showPermalink = function() {
+			const connection = storage.getConnectionSettings();
+
+			if (self.isExportDisabled() || !connection) {
+				return;
+			}
+
+			if (self.isPermalinkVisible()) {
+				self.isPermalinkVisible(false);
+
+				return;
+			}
+
+			const state = {
+				host: connection.host,
+				port: connection.port,
+				database: connection.database,
+				role: connection.role,
+				objects: self.collectSharedObjects(),
+				view: self.map.view(),
+			};
+			const hash = permalink.encode(state);
+			const showLink = function(hash) {
+				self.permalink(permalink.link(hash));
+				self.isConnectionVisible(false);
+				self.isObjectsVisible(false);
+				self.isExportVisible(false);
+				self.isFormatTilesVisible(false);
+				self.isDiffVisible(false);
+				self.isSqlVisible(false);
+				self.isPermalinkVisible(true);
+				window.history.replaceState(null, "", "#" + hash);
+			};
+
+			if (permalink.link(hash).length <= MAX_LINK_LENGTH) {
+				showLink(hash);
+
+				return;
+			}
+
+			reqwest({
+				url: "/api/v1/share",
+				method: "post",
+				data: JSON.stringify(state),
+				type: "json",
+				contentType: "application/json",
+			}).then(function(responce) {
+				if (responce.success) {
+					showLink(permalink.shareHash(responce.result));
+				} else {
+					self.pushMessage(message.error(responce.message, "Failed to share objects"));
+				}
+			});
+		};
+
+		this.hidePermalink = function() {
+			self.isPermalinkVisible(false);
+		};
+
+		this.showFormatTiles = function() {
+			if (self.isObjectsEnabled()) {
+				const oldState = self.isFormatTilesVisible();
+
+				self.isConnectionVisible(false);
+				self.isObjectsVisible(false);
+				self.isExportVisible(false);
+				self.isPermalinkVisible(false);
+				self.isDiffVisible(false);
+				self.isSqlVisible(false);
+				self.isFormatTilesVisible(!oldState);
+			}
+		};
+
+		this.hideFormatTiles = function() {
+			self.isFormatTilesVisible(false);
+		};
+
+		this.showDiff = function() {
+			if (self.isObjectsEnabled()) {
+				const oldState = self.isDiffVisible();
+
+				self.isConnectionVisible(false);
+				self.isObjectsVisible(false);
+				self.isExportVisible(false);
+				self.isPermalinkVisible(false);
+				self.isFormatTilesVisible(false);
+				self.isSqlVisible(false);
+				self.isDiffVisible(!oldState);
+			}
+		};
+
+		this.hideDiff = function() {
+			self.isDiffVisible(false);
+		};
+
+		this.showSql = function() {
+			if (self.isObjectsEnabled()) {
+				const oldState = self.isSqlVisible();
+
+				self.isConnectionVisible(false);
+				self.isObjectsVisible(false);
+				self.isExportVisible(false);
+				self.isPermalinkVisible(false);
+				self.isFormatTilesVisible(false);
+				self.isDiffVisible(false);
+				self.isSqlVisible(!oldState);
+			}
+		};
+
+		this.hideSql = function() {
+			self.isSqlVisible(false);
+		};
+
+		// Password is not part of permalink, current password kept if connection is the same.
+		this.applyPermalink = function(state) {
+			const connection = storage.getConnectionSettings();
+			const isSameConnection =
+				connection !== null &&
+				connection.host === state.host &&
+				connection.port === state.port &&
+				connection.database === state.database &&
+				connection.role === state.role;
+
+			if (!isSameConnection) {
+				storage.setConnectionSettings(state.host, state.port, state.database, state.role, "");
+				self.pushMessage(
+					message.warn(
+						"Connection changed to " + state.host + "/" + state.database + ", enter password if required.",
+						"Permalink"
+					)
+				);
+			}
+
+			self.map.requestedView(state.view);
+			self.objectsRequests(state.objects);
+		};
+
+		this.loadPermalink = function() {
+			const state = permalink.decode(window.location.hash);
+
+			if (state === null) {
+				return;
+			}
+
+			if (!state.share) {
+				self.applyPermalink(state);
+
+				return;
+			}
+
+			reqwest({
+				url: "/api/v1/share/" + encodeURIComponent(state.share),
+				method: "get",
+				type: "json",
+			}).then(function(responce) {
+				if (responce.success) {
+					self.applyPermalink(responce.result);
+				} else {
+					self.pushMessage(message.error(responce.message, "Permalink"));
+				}
+			});
+		};
+
 		this.clearShapes = function() {
 			if (self.isClearEnabled()) {
 				self.map.clearShapes();
@@ -72,6 +341,42 @@ define(["knockout", "localStorage", "messageModel", "mapModel"], function(ko, st
 			self.messages.push(message);
 		};
 
+		// Formats depend on user permissions, so page reloaded after user changed
+		this.loginCallback = function(user) {
+			window.location.reload();
+		};
+
+		this.logout = function() {
+			reqwest({
+				url: "/api/v1/auth/logout",
+				method: "post",
+				type: "json",
+				contentType: "application/json",
+			}).always(function() {
+				window.location.reload();
+			});
+		};
+
+		this.loadUser = function() {
+			reqwest({
+				url: "/api/v1/user",
+				method: "post",
+				type: "json",
+				contentType: "application/json",
+			})
+				.then(function(responce) {
+					if (responce.success && responce.result !== null) {
+						self.userName(responce.result.login);
+					}
+				})
+				.fail(function(request) {
+					if (request.status === 401) {
+						self.isLoginVisible(true);
+						self.isConnectionVisible(false);
+					}
+				});
+		};
+
 		const connectionSettings = storage.getConnectionSettings();
 
 		if (connectionSettings === null) {
@@ -85,5 +390,8 @@ define(["knockout", "localStorage", "messageModel", "mapModel"], function(ko, st
 			self.isObjectsEnabled(true);
 			self.isClearEnabled(true);
 		});
+
+		this.loadUser();
+		this.loadPermalink();
 	};
 });
@@ -2,23 +2,321 @@
 
 define(["knockout", "openLayers"], function(ko, ol) {
 	const KEY_INDEX = "index";
+	const KEY_OBJECT = "object";
+	const INVALID_STYLE = new ol.style.Style({
+		stroke: new ol.style.Stroke({ color: [219, 40, 40, 0.6], width: 9 }),
+	});
+	const ARROW_SPACING = 80;
+	const DIRECTION_FORMAT = "format";
+	const DIRECTION_ALL = "all";
+	const DIRECTION_NONE = "none";
+	const DIRECTION_NEXT = {
+		[DIRECTION_FORMAT]: DIRECTION_ALL,
+		[DIRECTION_ALL]: DIRECTION_NONE,
+		[DIRECTION_NONE]: DIRECTION_FORMAT,
+	};
+	const DIRECTION_TITLES = {
+		[DIRECTION_FORMAT]: "Line direction: as configured in format",
+		[DIRECTION_ALL]: "Line direction: show for all lines",
+		[DIRECTION_NONE]: "Line direction: hidden",
+	};
+
+	const TILES_OSM_TITLE = "OpenStreetMap";
+	const KEY_FORMAT = "format";
+	const MVT_CONTENT_TYPE = "application/vnd.mapbox-vector-tile";
+	const FORMAT_TILE_STYLE = new ol.style.Style({
+		stroke: new ol.style.Stroke({ color: [163, 51, 200, 0.8], width: 2 }),
+		fill: new ol.style.Fill({ color: [163, 51, 200, 0.1] }),
+	});
+
+	// Local base map layer, vector layers stored as Mapbox Vector Tiles
+	const createTileLayer = function(layer) {
+		const url = "/tiles/" + encodeURIComponent(layer.name) + "/{z}/{x}/{y}";
+
+		if (layer.layer_type === "vector") {
+			return new ol.layer.VectorTile({
+				source: new ol.source.VectorTile({ format: new ol.format.MVT(), url: url }),
+				visible: false,
+			});
+		}
+
+		return new ol.layer.Tile({ source: new ol.source.XYZ({ url: url }), visible: false });
+	};
+
+	// Tiles requested with connection in request body, failed tiles left empty and reported once
+	const createFormatTileLoader = function(formatTile) {
+		let isErrorReported = false;
 
-	const createToggleControl = function(callback) {
+		const reportError = function(text) {
+			if (!isErrorReported) {
+				isErrorReported = true;
+				formatTile.errorCallback(text);
+			}
+		};
+
+		return function(tile, url) {
+			tile.setLoader(function() {
+				const request = new XMLHttpRequest();
+
+				request.open("GET", url);
+				request.responseType = "arraybuffer";
+				request.setRequestHeader("X-Database-Password", formatTile.connection.password || "");
+				request.onload = function() {
+					const contentType = request.getResponseHeader("Content-Type") || "";
+
+					if (request.status === 200 && contentType.indexOf(MVT_CONTENT_TYPE) === 0) {
+						const format = tile.getFormat();
+
+						tile.setProjection(format.readProjection(request.response));
+						tile.setFeatures(format.readFeatures(request.response));
+						tile.setExtent(format.getLastExtent());
+
+						return;
+					}
+
+					let text = new TextDecoder().decode(request.response);
+
+					try {
+						text = JSON.parse(text).message;
+					} catch (error) {
+						// Plain text error
+					}
+
+					tile.setFeatures([]);
+					reportError(text);
+				};
+				request.onerror = function() {
+					tile.setFeatures([]);
+					reportError("Request failed");
+				};
+				request.send();
+			});
+		};
+	};
+
+	// Connection of format tiles in query string, password sent in header
+	const createFormatTileQuery = function(formatTile) {
+		const connection = formatTile.connection;
+		const fields = {
+			host: connection.host,
+			port: connection.port,
+			database: connection.database,
+			role: connection.role,
+		};
+
+		const parameters = formatTile.values.parameters;
+		const variables = formatTile.values.variables;
+
+		for (const name of Object.keys(parameters)) {
+			fields["parameter." + name] = parameters[name];
+		}
+
+		for (const name of Object.keys(variables)) {
+			fields["variable." + name] = variables[name];
+		}
+
+		return Object.keys(fields)
+			.map(function(name) {
+				return encodeURIComponent(name) + "=" + encodeURIComponent(fields[name]);
+			})
+			.join("&");
+	};
+
+	// Objects of whole format as vector tiles, hidden below minimal zoom of the format
+	const createFormatTileLayer = function(formatTile) {
+		const source = new ol.source.VectorTile({
+			format: new ol.format.MVT(),
+			url:
+				"/api/v1/tiles/" +
+				encodeURIComponent(formatTile.format) +
+				"/{z}/{x}/{y}.mvt?" +
+				createFormatTileQuery(formatTile),
+			tileLoadFunction: createFormatTileLoader(formatTile),
+		});
+		const layer = new ol.layer.VectorTile({ source: source, style: FORMAT_TILE_STYLE });
+
+		if (formatTile.minZoom > 0) {
+			layer.setMaxResolution(source.getTileGrid().getResolution(formatTile.minZoom) * 1.01);
+		}
+
+		layer.set(KEY_FORMAT, formatTile.format);
+
+		return layer;
+	};
+
+	const createToggleControl = function(className, title, label, callback) {
 		const button = document.createElement("button");
-		button.title = "Toggle world map";
-		button.innerHTML = "W";
+		button.title = title;
+		button.innerHTML = label;
 		button.addEventListener("click", callback, false);
 		button.addEventListener("touchstart", callback, false);
 
 		const element = document.createElement("div");
-		element.className = "toggle-world-map ol-unselectable ol-control";
+		element.className = className + " ol-unselectable ol-control";
 		element.appendChild(button);
 
 		return new ol.control.Control({ element: element });
 	};
 
-	const createSelectInteration = function(map, callback) {
-		const interactSelect = new ol.interaction.Select();
+	// Create arrows along the line, arrows placed on segment middle not closer than ARROW_SPACING pixels
+	const createArrowStyles = function(coordinates, resolution, color) {
+		const spacing = ARROW_SPACING * resolution;
+		const result = [];
+		let distance = spacing;
+
+		for (let index = 1; index < coordinates.length; index++) {
+			const start = coordinates[index - 1];
+			const end = coordinates[index];
+			const dx = end[0] - start[0];
+			const dy = end[1] - start[1];
+			const length = Math.sqrt(dx * dx + dy * dy);
+
+			distance += length;
+
+			if (length === 0 || distance < spacing) {
+				continue;
+			}
+
+			distance = 0;
+			result.push(
+				new ol.style.Style({
+					geometry: new ol.geom.Point([start[0] + dx / 2, start[1] + dy / 2]),
+					image: new ol.style.RegularShape({
+						fill: new ol.style.Fill({ color: color }),
+						points: 3,
+						radius: 6,
+						rotation: Math.PI / 2 - Math.atan2(dy, dx),
+						rotateWithView: true,
+					}),
+				})
+			);
+		}
+
+		return result;
+	};
+
+	// Create markers for the first (circle) and the last (square) line vertex
+	const createEndpointStyles = function(coordinates) {
+		if (coordinates.length === 0) {
+			return [];
+		}
+
+		const stroke = new ol.style.Stroke({ color: "#fff", width: 1.5 });
+		const start = new ol.style.Style({
+			geometry: new ol.geom.Point(coordinates[0]),
+			image: new ol.style.Circle({
+				fill: new ol.style.Fill({ color: "#21ba45" }),
+				stroke: stroke,
+				radius: 5,
+			}),
+		});
+		const end = new ol.style.Style({
+			geometry: new ol.geom.Point(coordinates[coordinates.length - 1]),
+			image: new ol.style.RegularShape({
+				fill: new ol.style.Fill({ color: "#db2828" }),
+				stroke: stroke,
+				points: 4,
+				radius: 6,
+				angle: Math.PI / 4,
+			}),
+		});
+
+		return [start, end];
+	};
+
+	// Multiply alpha channel of the color by the given opacity
+	const applyOpacity = function(color, opacity) {
+		const result = ol.color.asArray(color).slice();
+
+		if (opacity !== undefined) {
+			result[3] = result[3] * opacity;
+		}
+
+		return result;
+	};
+
+	// Create text style from the object field configured as label
+	const createLabel = function(mapObject, labelField) {
+		let label;
+
+		if (labelField === undefined) {
+			return undefined;
+		} else if (labelField === "id") {
+			label = "" + mapObject.id;
+		} else if (labelField === "names") {
+			label = mapObject.names.join(", ");
+		} else if (mapObject.attributes && labelField in mapObject.attributes) {
+			label = mapObject.attributes[labelField];
+		} else {
+			return undefined;
+		}
+
+		return new ol.style.Text({
+			text: label,
+			overflow: true,
+			fill: new ol.style.Fill({ color: "#333" }),
+			stroke: new ol.style.Stroke({ color: "#fff", width: 3 }),
+		});
+	};
+
+	// Project single point from latitude/longitude to WEB Mercator.
+	const pointToCoordinate = function(point) {
+		return ol.proj.transform([point.lon, point.lat], "EPSG:4326", "EPSG:3857");
+	};
+
+	// Unproject center of the view from WEB Mercator to latitude/longitude.
+	const viewToMapView = function(view) {
+		const center = ol.proj.transform(view.getCenter(), "EPSG:3857", "EPSG:4326");
+
+		return { lat: center[1], lon: center[0], zoom: view.getZoom() };
+	};
+
+	// Create markers with sequence number for every vertex of object requested in inspection mode
+	const createVertexStyles = function(mapObject) {
+		const parts = mapObject.lines || mapObject.polygons;
+		const result = [];
+
+		for (const points of parts) {
+			for (const point of points) {
+				if (point.index === undefined) {
+					continue;
+				}
+
+				const color = point.duplicate ? "#db2828" : "#2185d0";
+
+				result.push(
+					new ol.style.Style({
+						geometry: new ol.geom.Point(pointToCoordinate(point)),
+						image: new ol.style.Circle({
+							fill: new ol.style.Fill({ color: color }),
+							stroke: new ol.style.Stroke({ color: "#fff", width: 1 }),
+							radius: 4,
+						}),
+						text: new ol.style.Text({
+							text: "" + point.index,
+							offsetY: point.duplicate ? 12 : -12,
+							fill: new ol.style.Fill({ color: color }),
+							stroke: new ol.style.Stroke({ color: "#fff", width: 3 }),
+						}),
+					})
+				);
+			}
+		}
+
+		return result;
+	};
+
+	const getSelectedStyle = function(feature) {
+		const style = new ol.style.Style({
+			stroke: new ol.style.Stroke({ color: "#3399cc", width: 3 }),
+			fill: new ol.style.Fill({ color: [255, 255, 255, 0.4] }),
+		});
+
+		return [style].concat(createVertexStyles(feature.get(KEY_OBJECT)));
+	};
+
+	const createSelectInteration = function(map, layer, callback) {
+		const interactSelect = new ol.interaction.Select({ layers: [layer], style: getSelectedStyle });
 		interactSelect.on("select", function(event) {
 			const selectedFeatures = interactSelect.getFeatures();
 			const selectedArray = selectedFeatures.getArray();
@@ -26,7 +324,7 @@ define(["knockout", "openLayers"], function(ko, ol) {
 				return feature.get(KEY_INDEX);
 			});
 
-			callback(selectedIds);
+			callback(selectedIds, event.mapBrowserEvent.pixel);
 		});
 
 		map.addInteraction(interactSelect);
@@ -35,8 +333,14 @@ define(["knockout", "openLayers"], function(ko, ol) {
 	const OpenLayersMap = function(element, params) {
 		this.objectFeatures = {};
 		this.objectStyles = {};
+		this.objectDirections = {};
+		this.objectInvalid = {};
+		this.directionMode = DIRECTION_FORMAT;
 
 		params.mapObjects.subscribe(this.updateGeometry.bind(this));
+		params.requestedView.subscribe(this.applyView.bind(this));
+		params.tileLayers.subscribe(this.updateTileLayers.bind(this));
+		params.formatTiles.subscribe(this.updateFormatTiles.bind(this));
 
 		// Initialize layers - background - OSM, foreground - vector
 		const sourceOsm = new ol.source.OSM();
@@ -53,7 +357,21 @@ define(["knockout", "openLayers"], function(ko, ol) {
 				attribution: true,
 				zoom: true,
 			})
-			.extend([new ol.control.ScaleLine(), createToggleControl(this.toggleTiles.bind(this))]);
+			.extend([
+				new ol.control.ScaleLine(),
+				createToggleControl(
+					"toggle-world-map",
+					"World map: " + TILES_OSM_TITLE,
+					"W",
+					this.toggleTiles.bind(this)
+				),
+				createToggleControl(
+					"toggle-direction",
+					DIRECTION_TITLES[DIRECTION_FORMAT],
+					"D",
+					this.toggleDirection.bind(this)
+				),
+			]);
 
 		// Create default view
 		const view = new ol.View({
@@ -69,12 +387,33 @@ define(["knockout", "openLayers"], function(ko, ol) {
 			view: view,
 		});
 
-		// Add callback handler for object selection.
+		// Add callback handler for object selection, objects of format tiles identified when no object selected.
 		if (params.selectionCallback) {
-			createSelectInteration(map, params.selectionCallback);
+			createSelectInteration(
+				map,
+				layerVector,
+				function(selectedIds, pixel) {
+					const identified = selectedIds.length === 0 ? this.identifyFormatTiles(pixel) : [];
+
+					if (identified.length > 0) {
+						params.identifyCallback(identified);
+					} else {
+						params.selectionCallback(selectedIds);
+					}
+				}.bind(this)
+			);
 		}
 
-		this.tileLayer = layerTile;
+		// Track current view for permalinks
+		map.on("moveend", function() {
+			params.view(viewToMapView(view));
+		});
+
+		this.requestedView = params.requestedView;
+		// Background layers switched by toggle, last one - OSM, index past the end - no background
+		this.tileLayers = [{ title: TILES_OSM_TITLE, layer: layerTile }];
+		this.tileIndex = 0;
+		this.formatTileLayers = [];
 		this.sourceVector = sourceVector;
 		this.map = map;
 	};
@@ -95,19 +434,31 @@ define(["knockout", "openLayers"], function(ko, ol) {
 				const points = mapObject.lines.map(this.lineToCoordinate.bind(this));
 
 				geometry = new ol.geom.MultiLineString(points, "XY");
-				style = this.getLineStyle();
+				style = this.getLineStyle(mapObject);
+
+				this.objectDirections[index] = {
+					arrows: !!(mapObject.style && mapObject.style.arrows),
+					endpoints: !!(mapObject.style && mapObject.style.endpoints),
+				};
 			} else if (mapObject.type === "MultiPolygon") {
 				const points = mapObject.polygons.map(this.polygonToCoordinate.bind(this));
 
 				geometry = new ol.geom.MultiPolygon(points, "XY");
-				style = this.getPolygonStyle();
+				style = this.getPolygonStyle(mapObject);
 			}
 
 			const feature = new ol.Feature({ geometry, name });
 			feature.set(KEY_INDEX, index);
+			feature.set(KEY_OBJECT, mapObject);
 
 			this.objectFeatures[index] = feature;
 			this.objectStyles[index] = style;
+			// Skipped checks are reported but don't mark object invalid
+			this.objectInvalid[index] =
+				mapObject.issues !== undefined &&
+				mapObject.issues.some(function(issue) {
+					return issue.kind !== "CheckSkipped";
+				});
 			this.sourceVector.addFeature(feature);
 		}
 
@@ -119,11 +470,15 @@ define(["knockout", "openLayers"], function(ko, ol) {
 				this.sourceVector.removeFeature(feature);
 				delete this.objectFeatures[index];
 				delete this.objectStyles[index];
+				delete this.objectDirections[index];
+				delete this.objectInvalid[index];
 			}
 		}
 
-		// Zoom to fit all features if features present on the map
-		if (this.sourceVector.getFeatures().length > 0) {
+		// Zoom to fit all features if features present on the map, view restored from permalink kept
+		if (this.requestedView()) {
+			this.applyView(this.requestedView());
+		} else if (this.sourceVector.getFeatures().length > 0) {
 			const extent = this.sourceVector.getExtent();
 			const view = this.map.getView();
 
@@ -134,36 +489,153 @@ define(["knockout", "openLayers"], function(ko, ol) {
 	};
 
 	// Polygons represents places
-	OpenLayersMap.prototype.getPolygonStyle = function() {
+	OpenLayersMap.prototype.getPolygonStyle = function(mapObject) {
+		const objectStyle = mapObject.style || {};
 		const color_r = 0 + Math.floor(64.0 * Math.random());
 		const color_g = 128 + Math.floor(64.0 - 128.0 * Math.random());
 		const color_b = 255 - Math.floor(64.0 * Math.random());
-		const stroke = new ol.style.Stroke({ color: [color_r, color_g, color_b], width: 1 });
-		const fill = new ol.style.Fill({ color: [color_r, color_g, color_b, 0.1] });
+		const strokeColor = objectStyle.stroke_color || [color_r, color_g, color_b];
+		const fillColor = objectStyle.fill_color || [color_r, color_g, color_b, 0.1];
+		const stroke = new ol.style.Stroke({
+			color: applyOpacity(strokeColor, objectStyle.opacity),
+			width: objectStyle.stroke_width || 1,
+			lineDash: objectStyle.dash,
+		});
+		const fill = new ol.style.Fill({ color: applyOpacity(fillColor, objectStyle.opacity) });
+		const text = createLabel(mapObject, objectStyle.label_field);
 
-		return new ol.style.Style({ stroke: stroke, fill: fill });
+		return new ol.style.Style({ stroke: stroke, fill: fill, text: text });
 	};
 
 	// Lines represents roads and links
-	OpenLayersMap.prototype.getLineStyle = function() {
+	OpenLayersMap.prototype.getLineStyle = function(mapObject) {
+		const objectStyle = mapObject.style || {};
 		const color_r = 255 - Math.floor(64.0 * Math.random());
 		const color_g = 128 + Math.floor(64.0 - 128.0 * Math.random());
 		const color_b = 0 + Math.floor(64.0 * Math.random());
-		const stroke = new ol.style.Stroke({ color: [color_r, color_g, color_b], width: 2 });
+		const strokeColor = objectStyle.stroke_color || [color_r, color_g, color_b];
+		const stroke = new ol.style.Stroke({
+			color: applyOpacity(strokeColor, objectStyle.opacity),
+			width: objectStyle.stroke_width || 2,
+			lineDash: objectStyle.dash,
+		});
+		const text = createLabel(mapObject, objectStyle.label_field);
+
+		return new ol.style.Style({ stroke: stroke, text: text });
+	};
+
+	OpenLayersMap.prototype.applyView = function(mapView) {
+		if (mapView) {
+			const view = this.map.getView();
+
+			view.setCenter(pointToCoordinate(mapView));
+			view.setZoom(mapView.zoom);
+		}
+	};
+
+	OpenLayersMap.prototype.updateTileLayers = function(layers) {
+		const osm = this.tileLayers[this.tileLayers.length - 1];
+		const localLayers = layers.map(function(layer) {
+			return { title: layer.title, layer: createTileLayer(layer) };
+		});
+
+		for (const [index, tileLayer] of localLayers.entries()) {
+			this.map.getLayers().insertAt(index, tileLayer.layer);
+		}
 
-		return new ol.style.Style({ stroke: stroke });
+		// Local layers preferred, first one shown instead of OSM
+		this.tileLayers = localLayers.concat([osm]);
+		this.showTileLayer(0);
 	};
 
-	OpenLayersMap.prototype.toggleTiles = function(feature) {
-		const tileVisible = !this.tileLayer.getVisible();
+	OpenLayersMap.prototype.updateFormatTiles = function(formatTiles) {
+		const layers = this.map.getLayers();
 
-		this.tileLayer.setVisible(tileVisible);
+		for (const layer of this.formatTileLayers) {
+			layers.remove(layer);
+		}
+
+		// Format tiles placed between base map and objects
+		this.formatTileLayers = formatTiles.map(createFormatTileLayer);
+
+		for (const layer of this.formatTileLayers) {
+			layers.insertAt(layers.getLength() - 1, layer);
+		}
+	};
+
+	OpenLayersMap.prototype.identifyFormatTiles = function(pixel) {
+		const formatTileLayers = this.formatTileLayers;
+		const result = [];
+
+		this.map.forEachFeatureAtPixel(
+			pixel,
+			function(feature, layer) {
+				result.push({
+					format: layer.get(KEY_FORMAT),
+					id: feature.get("id"),
+					names: feature.get("names"),
+				});
+			},
+			{
+				layerFilter: function(layer) {
+					return formatTileLayers.indexOf(layer) !== -1;
+				},
+			}
+		);
+
+		return result;
+	};
+
+	OpenLayersMap.prototype.showTileLayer = function(tileIndex) {
+		this.tileIndex = tileIndex;
+
+		for (const [index, tileLayer] of this.tileLayers.entries()) {
+			tileLayer.layer.setVisible(index === tileIndex);
+		}
+	};
+
+	OpenLayersMap.prototype.toggleTiles = function(event) {
+		const tileIndex = (this.tileIndex + 1) % (this.tileLayers.length + 1);
+		const tileLayer = this.tileLayers[tileIndex];
+
+		this.showTileLayer(tileIndex);
+		event.currentTarget.title = "World map: " + (tileLayer ? tileLayer.title : "hidden");
+	};
+
+	OpenLayersMap.prototype.toggleDirection = function(event) {
+		this.directionMode = DIRECTION_NEXT[this.directionMode];
+		event.currentTarget.title = DIRECTION_TITLES[this.directionMode];
+		this.sourceVector.changed();
 	};
 
-	OpenLayersMap.prototype.getFeatureStyle = function(feature) {
+	OpenLayersMap.prototype.getFeatureStyle = function(feature, resolution) {
 		const id = feature.get(KEY_INDEX);
+		const style = this.objectStyles[id];
+		const direction = this.objectDirections[id];
+		const result = this.objectInvalid[id] ? [INVALID_STYLE, style] : [style];
+
+		if (direction === undefined || this.directionMode === DIRECTION_NONE) {
+			return result;
+		}
+
+		const showAll = this.directionMode === DIRECTION_ALL;
+		const showArrows = showAll || direction.arrows;
+		const showEndpoints = showAll || direction.endpoints;
+		const color = style.getStroke().getColor();
+
+		for (const line of feature.getGeometry().getLineStrings()) {
+			const coordinates = line.getCoordinates();
 
-		return this.objectStyles[id];
+			if (showArrows) {
+				result.push(...createArrowStyles(coordinates, resolution, color));
+			}
+
+			if (showEndpoints) {
+				result.push(...createEndpointStyles(coordinates));
+			}
+		}
+
+		return result;
 	};
 
 	// Project polygon from latitude/longitude to WEB Mercator.
@@ -177,9 +649,7 @@ define(["knockout", "openLayers"], function(ko, ol) {
 	};
 
 	// Project single point from latitude/longitude to WEB Mercator.
-	OpenLayersMap.prototype.pointToCoordinate = function(point) {
-		return ol.proj.transform([point.lon, point.lat], "EPSG:4326", "EPSG:3857");
-	};
+	OpenLayersMap.prototype.pointToCoordinate = pointToCoordinate;
 
 	// OpenLayers binding
 	ko.bindingHandlers.asMap = {
@@ -1,6 +1,11 @@
 "use strict";
 
 define(["knockout"], function(ko) {
+	ko.components.register("ko-login", {
+		viewModel: { require: "components/Login" },
+		template: { require: "text!components/Login.html" },
+	});
+
 	ko.components.register("ko-connection-settings", {
 		viewModel: { require: "components/ConnectionSettings" },
 		template: { require: "text!components/ConnectionSettings.html" },
@@ -11,11 +16,41 @@ define(["knockout"], function(ko) {
 		template: { require: "text!components/Objects.html" },
 	});
 
+	ko.components.register("ko-export", {
+		viewModel: { require: "components/Export" },
+		template: { require: "text!components/Export.html" },
+	});
+
+	ko.components.register("ko-permalink", {
+		viewModel: { require: "components/Permalink" },
+		template: { require: "text!components/Permalink.html" },
+	});
+
+	ko.components.register("ko-diff", {
+		viewModel: { require: "components/Diff" },
+		template: { require: "text!components/Diff.html" },
+	});
+
+	ko.components.register("ko-sql", {
+		viewModel: { require: "components/Sql" },
+		template: { require: "text!components/Sql.html" },
+	});
+
+	ko.components.register("ko-format-tiles", {
+		viewModel: { require: "components/FormatTiles" },
+		template: { require: "text!components/FormatTiles.html" },
+	});
+
 	ko.components.register("ko-selected-names", {
 		viewModel: { require: "components/SelectedNames" },
 		template: { require: "text!components/SelectedNames.html" },
 	});
 
+	ko.components.register("ko-vertices", {
+		viewModel: { require: "components/Vertices" },
+		template: { require: "text!components/Vertices.html" },
+	});
+
 	ko.components.register("ko-messages", {
 		viewModel: { require: "components/Messages" },
 		template: { require: "text!components/Messages.html" },
@@ -0,0 +1,217 @@
+"use strict";
+
+define(["knockout", "reqwest", "messageModel", "localStorage", "integerParser", "geometryDecoder"], function(
+	ko,
+	reqwest,
+	message,
+	storage,
+	parser,
+	decoder
+) {
+	// Versions of changed objects shown with contrasting styles, unchanged objects with format style
+	const BEFORE_STYLE = {
+		stroke_color: [219, 40, 40],
+		fill_color: [219, 40, 40, 0.1],
+		stroke_width: 3,
+		dash: [8, 6],
+	};
+	const AFTER_STYLE = {
+		stroke_color: [33, 186, 69],
+		fill_color: [33, 186, 69, 0.1],
+		stroke_width: 3,
+	};
+	const STATUS_TITLES = {
+		added: "Added",
+		removed: "Removed",
+		unchanged: "Unchanged",
+		changed: "Changed",
+	};
+
+	const connectionName = function(connection) {
+		return connection.role + "@" + connection.host + ":" + (connection.port || 5432) + "/" + connection.database;
+	};
+
+	const changesText = function(diff) {
+		const changes = [];
+
+		if (diff.names_changed) {
+			changes.push("names");
+		}
+
+		if (diff.geometry_changed) {
+			changes.push("geometry");
+		}
+
+		return changes.join(", ");
+	};
+
+	return function(params) {
+		const self = this;
+
+		this.showCallback = params.showCallback;
+		this.closeCallback = params.closeCallback;
+		this.messageCallback = params.messageCallback;
+		this.formatValues = params.formatValues;
+
+		this.objects = ko.observable("");
+		this.availableFormats = ko.observableArray();
+		this.selectedFormat = ko.observable();
+		this.availableTargets = ko.observableArray();
+		this.selectedTarget = ko.observable();
+		this.isObjectsValid = ko.observable(true);
+		this.isLoading = ko.observable(false);
+		this.results = ko.observableArray();
+
+		this.isObjectsInvalid = ko.pureComputed(function() {
+			return !this.isObjectsValid();
+		}, this);
+
+		this.isResultsVisible = ko.pureComputed(function() {
+			return this.results().length > 0;
+		}, this);
+
+		// Any recent connection except the current one can be compared with it
+		this.updateTargets = function() {
+			const connection = storage.getConnectionSettings();
+			const targets = storage.getRecentConnections().filter(function(target) {
+				return !connection || connectionName(target) !== connectionName(connection);
+			});
+
+			self.availableTargets(
+				targets.map(function(target) {
+					return { name: connectionName(target), connection: target };
+				})
+			);
+		};
+
+		this.toMapObject = function(object, format, values, suffix, style) {
+			const decoded = decoder.decode(object);
+
+			decoded.format = format;
+			decoded.parameters = values.parameters;
+			decoded.variables = values.variables;
+			decoded.names = decoded.names.map(function(name) {
+				return name + " (" + suffix + ")";
+			});
+
+			if (style) {
+				decoded.style = Object.assign({}, decoded.style, style);
+			}
+
+			return decoded;
+		};
+
+		this.processResponce = function(responce, format, values) {
+			self.isLoading(false);
+
+			if (!responce.success) {
+				self.messageCallback(message.error(responce.message, "Comparison failed"));
+
+				return;
+			}
+
+			const objects = [];
+
+			for (const diff of responce.result) {
+				if (diff.status === "unchanged") {
+					objects.push(self.toMapObject(diff.after, format, values, "unchanged", null));
+					continue;
+				}
+
+				if (diff.before) {
+					objects.push(self.toMapObject(diff.before, format, values, "before", BEFORE_STYLE));
+				}
+
+				if (diff.after) {
+					objects.push(self.toMapObject(diff.after, format, values, "after", AFTER_STYLE));
+				}
+			}
+
+			self.results(
+				responce.result.map(function(diff) {
+					return {
+						id: diff.id,
+						status: STATUS_TITLES[diff.status],
+						changes: changesText(diff),
+						distance: diff.distance === undefined ? "" : diff.distance.toFixed(2) + " m",
+						isChanged: diff.status !== "unchanged",
+					};
+				})
+			);
+			self.showCallback(objects);
+		};
+
+		this.compare = function() {
+			const connection = storage.getConnectionSettings();
+			const target = self.selectedTarget();
+
+			if (!connection || !target || !self.validate()) {
+				return;
+			}
+
+			const format = self.selectedFormat();
+			// Both versions loaded with parameter and variable values last used to show objects of the format
+			const values = self.formatValues()[format] || { parameters: {}, variables: {} };
+
+			reqwest({
+				url: "/api/v1/diff",
+				method: "post",
+				data: JSON.stringify({
+					host: connection.host,
+					port: connection.port,
+					database: connection.database,
+					role: connection.role,
+					password: connection.password,
+					format: format,
+					ids: parser.parse(self.objects()),
+					parameters: values.parameters,
+					variables: values.variables,
+					target: {
+						host: target.connection.host,
+						port: target.connection.port || 5432,
+						database: target.connection.database,
+						role: target.connection.role,
+						password: target.connection.password,
+					},
+				}),
+				type: "json",
+				contentType: "application/json",
+			})
+				.then(function(responce) {
+					self.processResponce(responce, format, values);
+				})
+				.fail(function() {
+					self.messageCallback(message.error("Request failed", "Comparison failed"));
+					self.isLoading(false);
+				});
+
+			self.results([]);
+			self.isLoading(true);
+		};
+
+		this.validate = function() {
+			const valid = parser.validate(self.objects());
+
+			self.isObjectsValid(valid);
+
+			return valid;
+		};
+
+		this.hide = function() {
+			self.closeCallback();
+		};
+
+		storage.addRecentListener(this.updateTargets);
+		storage.addConnectionListener(this.updateTargets);
+		this.updateTargets();
+
+		reqwest({
+			url: "/api/v1/format",
+			method: "post",
+			type: "json",
+			contentType: "application/json",
+		}).then(function(responce) {
+			self.availableFormats(responce.result);
+		});
+	};
+});
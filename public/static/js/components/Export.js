@@ -0,0 +1,156 @@
+"use strict";
+
+define(["knockout", "messageModel", "localStorage", "objectGroups"], function(ko, message, storage, objectGroups) {
+	const EXPORT_FORMATS = [
+		{ name: "GeoJSON", value: "geojson" },
+		{ name: "KML", value: "kml" },
+		{ name: "GPX (lines only)", value: "gpx" },
+		{ name: "Shapefile (zip)", value: "shapefile" },
+		{ name: "WKT", value: "wkt" },
+		{ name: "CSV", value: "csv" },
+	];
+
+	const groupTitle = function(group) {
+		const values = Object.assign({}, group.parameters, group.variables);
+		const pairs = Object.keys(values).map(function(name) {
+			return name + "=" + values[name];
+		});
+
+		return pairs.length > 0 ? group.format + " (" + pairs.join(", ") + ")" : group.format;
+	};
+
+	const fileName = function(request) {
+		const disposition = request.getResponseHeader("Content-Disposition") || "";
+		const match = /filename="([^"]+)"/.exec(disposition);
+
+		return match ? match[1] : "objects";
+	};
+
+	const save = function(blob, name) {
+		const url = URL.createObjectURL(blob);
+		const link = document.createElement("a");
+
+		link.href = url;
+		link.download = name;
+		document.body.appendChild(link);
+		link.click();
+		document.body.removeChild(link);
+		URL.revokeObjectURL(url);
+	};
+
+	return function(params) {
+		const self = this;
+
+		this.objects = params.objects;
+		this.closeCallback = params.closeCallback;
+		this.messageCallback = params.messageCallback;
+
+		this.availableExportFormats = EXPORT_FORMATS;
+		this.selectedExportFormat = ko.observable(EXPORT_FORMATS[0].value);
+		this.pendingRequests = ko.observable(0);
+
+		this.isLoading = ko.pureComputed(function() {
+			return this.pendingRequests() > 0;
+		}, this);
+
+		this.summary = ko.pureComputed(function() {
+			// Objects of SQL console have no format and can't be exported
+			const groups = objectGroups.group(this.objects());
+
+			if (groups.length === 0) {
+				return "No objects shown on the map.";
+			}
+
+			return groups
+				.map(function(group) {
+					return groupTitle(group) + ": " + group.ids.length;
+				})
+				.join(", ");
+		}, this);
+
+		this.finishRequest = function() {
+			self.pendingRequests(self.pendingRequests() - 1);
+
+			if (self.pendingRequests() === 0) {
+				self.closeCallback();
+			}
+		};
+
+		// Errors returned as JSON, files as any other content type.
+		this.processResponce = function(request) {
+			const contentType = request.getResponseHeader("Content-Type") || "";
+
+			if (request.status === 200 && contentType.indexOf("application/json") !== 0) {
+				save(request.response, fileName(request));
+				self.finishRequest();
+
+				return;
+			}
+
+			const reader = new FileReader();
+
+			reader.onload = function() {
+				let text = reader.result;
+
+				try {
+					text = JSON.parse(reader.result).message;
+				} catch (error) {
+					// Plain text error
+				}
+
+				self.messageCallback(message.error(text, "Export failed"));
+				self.finishRequest();
+			};
+			reader.readAsText(request.response);
+		};
+
+		this.exportGroup = function(group, connection) {
+			const request = new XMLHttpRequest();
+
+			request.open("POST", "/api/v1/export");
+			request.responseType = "blob";
+			request.setRequestHeader("Content-Type", "application/json");
+			request.onload = function() {
+				self.processResponce(request);
+			};
+			request.onerror = function() {
+				self.messageCallback(message.error("Request failed", "Export failed"));
+				self.finishRequest();
+			};
+			request.send(
+				JSON.stringify({
+					host: connection.host,
+					port: connection.port,
+					database: connection.database,
+					role: connection.role,
+					password: connection.password,
+					format: group.format,
+					ids: group.ids,
+					parameters: group.parameters,
+					variables: group.variables,
+					export_format: self.selectedExportFormat(),
+				})
+			);
+		};
+
+		// One file downloaded for every format, parameters and variables of shown objects.
+		this.export = function() {
+			const connection = storage.getConnectionSettings();
+			const groups = objectGroups.group(self.objects());
+
+			if (!connection || groups.length === 0 || self.isLoading()) {
+				return;
+			}
+
+			self.pendingRequests(groups.length);
+
+			for (const group of groups) {
+				self.exportGroup(group, connection);
+			}
+		};
+
+		this.hide = function() {
+			self.closeCallback();
+		};
+	};
+});
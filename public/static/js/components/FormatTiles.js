@@ -0,0 +1,77 @@
+"use strict";
+
+define(["knockout", "reqwest", "messageModel", "localStorage"], function(ko, reqwest, message, storage) {
+	return function(params) {
+		const self = this;
+
+		this.formatTiles = params.formatTiles;
+		this.closeCallback = params.closeCallback;
+		this.messageCallback = params.messageCallback;
+		this.formatValues = params.formatValues;
+
+		this.availableFormats = ko.observableArray();
+		this.selectedFormats = ko.observableArray();
+		this.isLoading = ko.observable(true);
+
+		this.isEmpty = ko.pureComputed(function() {
+			return !this.isLoading() && this.availableFormats().length === 0;
+		}, this);
+
+		this.minZoomText = function(format) {
+			return format.min_zoom > 0 ? "from zoom " + format.min_zoom : "all zooms";
+		};
+
+		// Tiles loaded with connection selected at the moment formats shown and parameter and variable values last used
+		// to show objects of the format.
+		this.show = function() {
+			const connection = storage.getConnectionSettings();
+
+			if (!connection) {
+				return;
+			}
+
+			const selectedFormats = self.availableFormats().filter(function(format) {
+				return self.selectedFormats.indexOf(format.format) !== -1;
+			});
+			const formatTiles = selectedFormats.map(function(format) {
+				return {
+					format: format.format,
+					minZoom: format.min_zoom,
+					connection: connection,
+					values: self.formatValues()[format.format] || { parameters: {}, variables: {} },
+					errorCallback: function(text) {
+						self.messageCallback(message.error(text, "Failed to load tiles of " + format.format));
+					},
+				};
+			});
+
+			self.formatTiles(formatTiles);
+			self.closeCallback();
+		};
+
+		this.hideAll = function() {
+			self.selectedFormats([]);
+			self.formatTiles([]);
+			self.closeCallback();
+		};
+
+		this.hide = function() {
+			self.closeCallback();
+		};
+
+		reqwest({
+			url: "/api/v1/tiles",
+			method: "post",
+			type: "json",
+			contentType: "application/json",
+		})
+			.then(function(responce) {
+				if (responce.success) {
+					self.availableFormats(responce.result);
+				}
+			})
+			.always(function() {
+				self.isLoading(false);
+			});
+	};
+});
@@ -0,0 +1,54 @@
+"use strict";
+
+define(["knockout", "reqwest"], function(ko, reqwest) {
+	return function(params) {
+		const self = this;
+
+		this.loginCallback = params.loginCallback;
+
+		this.login = ko.observable("");
+		this.password = ko.observable("");
+		this.errorMessage = ko.observable("");
+		this.isLoading = ko.observable(false);
+
+		this.isErrorVisible = ko.pureComputed(function() {
+			return this.errorMessage().length > 0;
+		}, this);
+
+		this.processResponce = function(responce) {
+			self.isLoading(false);
+
+			if (responce.success) {
+				self.errorMessage("");
+				self.password("");
+				self.loginCallback(responce.result);
+			} else {
+				self.errorMessage(responce.message);
+			}
+		};
+
+		this.processFail = function() {
+			self.isLoading(false);
+			self.errorMessage("Failed to sign in");
+		};
+
+		this.signIn = function() {
+			const data = {
+				login: self.login(),
+				password: self.password(),
+			};
+
+			reqwest({
+				url: "/api/v1/auth/login",
+				method: "post",
+				data: JSON.stringify(data),
+				type: "json",
+				contentType: "application/json",
+			})
+				.then(self.processResponce)
+				.fail(self.processFail);
+
+			self.isLoading(true);
+		};
+	};
+});
@@ -1,11 +1,12 @@
 "use strict";
 
-define(["knockout", "reqwest", "messageModel", "localStorage", "integerParser"], function(
+define(["knockout", "reqwest", "messageModel", "localStorage", "integerParser", "geometryDecoder"], function(
 	ko,
 	reqwest,
 	message,
 	storage,
-	parser
+	parser,
+	decoder
 ) {
 	const Objects = function(params) {
 		const self = this;
@@ -13,13 +14,98 @@ define(["knockout", "reqwest", "messageModel", "localStorage", "integerParser"],
 		this.showCallback = params.showCallback;
 		this.closeCallback = params.closeCallback;
 		this.messageCallback = params.messageCallback;
+		this.requests = params.requests;
+		this.formatValues = params.formatValues;
+		this.isFormatsLoaded = ko.observable(false);
+		this.isParametersLoaded = ko.observable(false);
+		this.isVariablesLoaded = ko.observable(false);
 
 		this.objects = ko.observable("");
 		this.availableFormats = ko.observableArray();
 		this.selectedFormat = ko.observable();
+		// Parameter definitions of formats and entered values, values kept while other format is selected.
+		this.formatParameters = ko.observable({});
+		this.parameterFields = {};
+		// Variables used by formats, variable without selected value gets value of connection or default value.
+		this.formatVariables = ko.observable({});
+		this.variableFields = {};
+		this.inspect = ko.observable(false);
+		this.validateGeometry = ko.observable(false);
+		this.refresh = ko.observable(false);
+		this.availableEncodings = decoder.encodings;
+		this.selectedEncoding = ko.observable(decoder.encodings[0]);
 		this.isObjectsValid = ko.observable(true);
 		this.isLoading = ko.observable(false);
 
+		this.parameters = ko.pureComputed(function() {
+			const format = this.selectedFormat();
+			const definitions = this.formatParameters()[format] || [];
+
+			if (!(format in this.parameterFields)) {
+				this.parameterFields[format] = definitions.map(function(definition) {
+					const defaultValue = "default" in definition ? String(definition.default) : "";
+
+					return {
+						id: "parameter-" + definition.name,
+						label: definition.title || definition.name,
+						type: definition.type,
+						inputType: { int: "number", date: "date" }[definition.type] || "text",
+						values: definition.values || [],
+						name: definition.name,
+						defaultValue: defaultValue,
+						value: ko.observable(defaultValue),
+					};
+				});
+			}
+
+			return this.parameterFields[format];
+		}, this);
+
+		// Values of parameters entered by user, parameters left empty get default values on server.
+		this.parameterValues = function() {
+			const result = {};
+
+			for (const parameter of self.parameters()) {
+				const value = String(parameter.value() || "").trim();
+
+				if (value !== "") {
+					result[parameter.name] = value;
+				}
+			}
+
+			return result;
+		};
+
+		this.variables = ko.pureComputed(function() {
+			const format = this.selectedFormat();
+			const definitions = this.formatVariables()[format] || {};
+
+			if (!(format in this.variableFields)) {
+				this.variableFields[format] = Object.keys(definitions).map(function(name) {
+					return {
+						id: "variable-" + name,
+						name: name,
+						values: definitions[name].values,
+						value: ko.observable(),
+					};
+				});
+			}
+
+			return this.variableFields[format];
+		}, this);
+
+		this.variableValues = function() {
+			const result = {};
+
+			for (const variable of self.variables()) {
+				if (variable.value()) {
+					result[variable.name] = variable.value();
+				}
+			}
+
+			return result;
+		};
+
 		this.isObjectsInvalid = ko.pureComputed(function() {
 			return !this.isObjectsValid();
 		}, this);
@@ -32,7 +118,7 @@ define(["knockout", "reqwest", "messageModel", "localStorage", "integerParser"],
 			return valid;
 		};
 
-		this.processResponce = function(responce, expectedIds) {
+		this.processResponce = function(responce, expectedIds, format, parameters, variables) {
 			if (responce.success) {
 				const actualIds = {};
 
@@ -40,6 +126,14 @@ define(["knockout", "reqwest", "messageModel", "localStorage", "integerParser"],
 					const id = place.id;
 
 					actualIds[id] = true;
+
+					if (place.issues) {
+						const issues = place.issues.map(function(issue) {
+							return issue.message;
+						});
+
+						self.messageCallback(message.warn(issues.join("; "), "Object " + id + " has invalid geometry"));
+					}
 				}
 
 				for (const id of expectedIds) {
@@ -48,7 +142,18 @@ define(["knockout", "reqwest", "messageModel", "localStorage", "integerParser"],
 					}
 				}
 
-				self.showCallback(responce.result);
+				// Source format, parameters and variables kept with every object to export objects shown on the map
+				const objects = responce.result.map(function(object) {
+					const decoded = decoder.decode(object);
+
+					decoded.format = format;
+					decoded.parameters = parameters;
+					decoded.variables = variables;
+
+					return decoded;
+				});
+
+				self.showCallback(objects);
 				self.closeCallback();
 			} else {
 				this.messageCallback(message.error(responce.message, "Error occurred"));
@@ -75,6 +180,12 @@ define(["knockout", "reqwest", "messageModel", "localStorage", "integerParser"],
 					password: connection.password,
 					format: self.selectedFormat(),
 					ids: ids,
+					parameters: self.parameterValues(),
+					variables: self.variableValues(),
+					inspect: self.inspect(),
+					validate: self.validateGeometry(),
+					refresh: self.refresh(),
+					encoding: self.selectedEncoding(),
 				};
 
 				reqwest({
@@ -85,14 +196,104 @@ define(["knockout", "reqwest", "messageModel", "localStorage", "integerParser"],
 					contentType: "application/json",
 				})
 					.then(function(responce) {
-						self.processResponce(responce, ids);
+						self.processResponce(responce, ids, data.format, data.parameters, data.variables);
 					})
 					.fail(self.processFail);
 
+				self.keepFormatValues(data.format, data.parameters, data.variables);
 				self.isLoading(true);
 			}
 		};
 
+		// Values used to show objects of the format also used for format tiles and comparison
+		this.keepFormatValues = function(format, parameters, variables) {
+			const values = Object.assign({}, self.formatValues());
+
+			values[format] = { parameters: parameters, variables: variables };
+			self.formatValues(values);
+		};
+
+		// Sets values of parameters and variables of selected format, missing parameters get default values.
+		this.applyFormatValues = function(parameters, variables) {
+			for (const parameter of self.parameters()) {
+				const name = parameter.name;
+				const value = name in parameters ? String(parameters[name]) : parameter.defaultValue;
+
+				parameter.value(value);
+			}
+
+			for (const variable of self.variables()) {
+				variable.value(variables[variable.name]);
+			}
+		};
+
+		// Removes objects from server cache, all cached objects of the format removed if no ids entered.
+		this.invalidateCache = function() {
+			const connection = storage.getConnectionSettings();
+
+			if (!connection) {
+				return;
+			}
+
+			const data = {
+				host: connection.host,
+				port: connection.port,
+				database: connection.database,
+				role: connection.role,
+				password: connection.password,
+				format: self.selectedFormat(),
+				parameters: self.parameterValues(),
+				variables: self.variableValues(),
+			};
+
+			if (self.objects().trim() !== "") {
+				if (!self.validate()) {
+					return;
+				}
+
+				data.ids = parser.parse(self.objects());
+			}
+
+			reqwest({
+				url: "/api/v1/cache/invalidate",
+				method: "post",
+				data: JSON.stringify(data),
+				type: "json",
+				contentType: "application/json",
+			})
+				.then(function(responce) {
+					if (!responce.success) {
+						self.messageCallback(message.error(responce.message, "Error occurred"));
+					}
+
+					self.isLoading(false);
+				})
+				.fail(self.processFail);
+
+			self.isLoading(true);
+		};
+
+		// Shows objects requested by permalink, available formats, parameters and variables required to select format.
+		this.processRequests = function() {
+			const isLoaded = self.isFormatsLoaded() && self.isParametersLoaded() && self.isVariablesLoaded();
+
+			if (!isLoaded || self.requests().length === 0) {
+				return;
+			}
+
+			for (const request of self.requests.removeAll()) {
+				if (self.availableFormats().indexOf(request.format) === -1) {
+					self.messageCallback(message.error("Format " + request.format + " is not available.", "Permalink"));
+					continue;
+				}
+
+				self.objects(request.ids.join(", "));
+				self.selectedFormat(request.format);
+				self.applyFormatValues(request.parameters || {}, request.variables || {});
+				self.show();
+			}
+		};
+
 		this.hide = function() {
 			self.closeCallback();
 		};
@@ -101,7 +302,13 @@ define(["knockout", "reqwest", "messageModel", "localStorage", "integerParser"],
 			self.objects("");
 		};
 
+		this.requests.subscribe(this.processRequests);
+		this.isFormatsLoaded.subscribe(this.processRequests);
+		this.isParametersLoaded.subscribe(this.processRequests);
+		this.isVariablesLoaded.subscribe(this.processRequests);
 		this.loadAvailableFormats();
+		this.loadFormatParameters();
+		this.loadFormatVariables();
 	};
 
 	Objects.prototype.loadAvailableFormats = function() {
@@ -114,10 +321,55 @@ define(["knockout", "reqwest", "messageModel", "localStorage", "integerParser"],
 			.then(
 				function(responce) {
 					this.availableFormats(responce.result);
+					this.isFormatsLoaded(true);
 				}.bind(this)
 			)
 			.fail(this.processFail.bind(this));
 	};
 
+	Objects.prototype.loadFormatParameters = function() {
+		reqwest({
+			url: "/api/v1/parameters",
+			method: "post",
+			type: "json",
+			contentType: "application/json",
+		})
+			.then(
+				function(responce) {
+					if (responce.success) {
+						this.parameterFields = {};
+						this.formatParameters(responce.result);
+					}
+				}.bind(this)
+			)
+			.always(
+				function() {
+					this.isParametersLoaded(true);
+				}.bind(this)
+			);
+	};
+
+	Objects.prototype.loadFormatVariables = function() {
+		reqwest({
+			url: "/api/v1/variables",
+			method: "post",
+			type: "json",
+			contentType: "application/json",
+		})
+			.then(
+				function(responce) {
+					if (responce.success) {
+						this.variableFields = {};
+						this.formatVariables(responce.result);
+					}
+				}.bind(this)
+			)
+			.always(
+				function() {
+					this.isVariablesLoaded(true);
+				}.bind(this)
+			);
+	};
+
 	return Objects;
 });
@@ -0,0 +1,33 @@
+"use strict";
+
+define(["knockout"], function(ko) {
+	return function(params) {
+		const self = this;
+
+		this.link = params.link;
+		this.closeCallback = params.closeCallback;
+		this.isCopied = ko.observable(false);
+
+		this.link.subscribe(function() {
+			self.isCopied(false);
+		});
+
+		this.copy = function(_, event) {
+			const input = event.currentTarget.parentNode.querySelector("input");
+
+			input.select();
+
+			if (navigator.clipboard) {
+				navigator.clipboard.writeText(self.link()).then(function() {
+					self.isCopied(true);
+				});
+			} else {
+				self.isCopied(document.execCommand("copy"));
+			}
+		};
+
+		this.hide = function() {
+			self.closeCallback();
+		};
+	};
+});
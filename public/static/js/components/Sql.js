@@ -0,0 +1,104 @@
+"use strict";
+
+define(["knockout", "reqwest", "messageModel", "localStorage", "geometryDecoder"], function(
+	ko,
+	reqwest,
+	message,
+	storage,
+	decoder
+) {
+	const columnsText = function(columns) {
+		const parts = ["Geometry: " + columns.geometry.join(", ")];
+
+		if (columns.id !== null) {
+			parts.push("identifier: " + columns.id);
+		}
+
+		if (columns.name !== null) {
+			parts.push("name: " + columns.name);
+		}
+
+		return parts.join(", ");
+	};
+
+	return function(params) {
+		const self = this;
+
+		this.showCallback = params.showCallback;
+		this.closeCallback = params.closeCallback;
+		this.messageCallback = params.messageCallback;
+
+		this.query = ko.observable("");
+		this.columnsText = ko.observable("");
+		this.isLoading = ko.observable(false);
+
+		this.isColumnsVisible = ko.pureComputed(function() {
+			return this.columnsText() !== "";
+		}, this);
+
+		this.processResponce = function(responce) {
+			self.isLoading(false);
+
+			if (!responce.success) {
+				self.messageCallback(message.error(responce.message, "Query failed"));
+
+				return;
+			}
+
+			const result = responce.result;
+
+			if (result.truncated) {
+				self.messageCallback(
+					message.warn("Query returned too many rows, only first rows shown.", "Query result truncated")
+				);
+			}
+
+			self.columnsText(columnsText(result.columns));
+			self.showCallback(
+				result.objects.map(function(object) {
+					const decoded = decoder.decode(object);
+
+					// Objects of ad-hoc queries have no format, exports and permalinks skip them
+					decoded.format = null;
+
+					return decoded;
+				})
+			);
+		};
+
+		this.run = function() {
+			const connection = storage.getConnectionSettings();
+
+			if (!connection || self.query().trim() === "") {
+				return;
+			}
+
+			reqwest({
+				url: "/api/v1/sql",
+				method: "post",
+				data: JSON.stringify({
+					host: connection.host,
+					port: connection.port,
+					database: connection.database,
+					role: connection.role,
+					password: connection.password,
+					query: self.query(),
+				}),
+				type: "json",
+				contentType: "application/json",
+			})
+				.then(self.processResponce)
+				.fail(function() {
+					self.messageCallback(message.error("Request failed", "Query failed"));
+					self.isLoading(false);
+				});
+
+			self.columnsText("");
+			self.isLoading(true);
+		};
+
+		this.hide = function() {
+			self.closeCallback();
+		};
+	};
+});
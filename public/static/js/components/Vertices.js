@@ -0,0 +1,11 @@
+"use strict";
+
+define(["knockout"], function(ko) {
+	return function(params) {
+		this.vertices = params.vertices;
+
+		this.isVisible = ko.pureComputed(function() {
+			return this.vertices().length > 0;
+		}, this);
+	};
+});
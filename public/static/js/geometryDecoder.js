@@ -0,0 +1,106 @@
+"use strict";
+
+define([], function() {
+	const POLYLINE_PRECISION = 1e6;
+	const DELTA_PRECISION = 1e7;
+
+	const decodeFlat = function(coordinates) {
+		const points = [];
+
+		for (let i = 0; i + 1 < coordinates.length; i += 2) {
+			points.push({ lat: coordinates[i], lon: coordinates[i + 1] });
+		}
+
+		return points;
+	};
+
+	// Decodes Google encoded polyline, latitude goes first.
+	const decodePolyline = function(text) {
+		const values = [];
+		let index = 0;
+
+		while (index < text.length) {
+			let result = 0;
+			let shift = 0;
+			let byte;
+
+			do {
+				byte = text.charCodeAt(index++) - 63;
+				result += (byte & 0x1f) * Math.pow(2, shift);
+				shift += 5;
+			} while (byte >= 0x20);
+
+			values.push(result % 2 === 1 ? -(result + 1) / 2 : result / 2);
+		}
+
+		return accumulate(values, POLYLINE_PRECISION);
+	};
+
+	// Decodes base64 stream of zigzag varint coordinate deltas.
+	const decodeDelta = function(text) {
+		const bytes = atob(text);
+		const values = [];
+		let index = 0;
+
+		while (index < bytes.length) {
+			let result = 0;
+			let multiplier = 1;
+			let byte;
+
+			do {
+				byte = bytes.charCodeAt(index++);
+				result += (byte & 0x7f) * multiplier;
+				multiplier *= 128;
+			} while (byte >= 0x80);
+
+			values.push(result % 2 === 1 ? -(result + 1) / 2 : result / 2);
+		}
+
+		return accumulate(values, DELTA_PRECISION);
+	};
+
+	const accumulate = function(deltas, precision) {
+		const points = [];
+		let lat = 0;
+		let lon = 0;
+
+		for (let i = 0; i + 1 < deltas.length; i += 2) {
+			lat += deltas[i];
+			lon += deltas[i + 1];
+			points.push({ lat: lat / precision, lon: lon / precision });
+		}
+
+		return points;
+	};
+
+	const DECODERS = {
+		flat: decodeFlat,
+		polyline: decodePolyline,
+		delta: decodeDelta,
+	};
+
+	return {
+		encodings: ["points", "flat", "polyline", "delta"],
+
+		// Replaces encoded parts of object with arrays of points.
+		decode: function(object) {
+			const decoder = DECODERS[object.encoding];
+
+			if (!decoder) {
+				return object;
+			}
+
+			if (object.lines) {
+				object.lines = object.lines.map(decoder);
+			}
+
+			if (object.polygons) {
+				object.polygons = object.polygons.map(decoder);
+			}
+
+			delete object.encoding;
+
+			return object;
+		},
+	};
+});
@@ -1,12 +1,20 @@
 "use strict";
 
-define(["knockout", "exports", "messageModel"], function(ko, exports, message) {
-	const MapModel = function(selectionCallback) {
+define(["knockout", "reqwest", "exports", "messageModel"], function(ko, reqwest, exports, message) {
+	const MapModel = function(selectionCallback, identifyCallback) {
 		const self = this;
 
 		this.selectionChanged = selectionCallback;
+		this.identifyCallback = identifyCallback;
 		this.isTilesVisible = ko.observable(true);
 		this.mapObjects = ko.observableArray();
+		// Base map layers served by backend, OSM used when none configured
+		this.tileLayers = ko.observableArray();
+		// Formats shown as vector tiles with connection used to load them
+		this.formatTiles = ko.observableArray();
+		// Current center and zoom of the map, and view to keep instead of zooming to objects
+		this.view = ko.observable(null);
+		this.requestedView = ko.observable(null);
 
 		this.selectionCallback = function(selectedIds) {
 			if (this.selectionChanged !== null) {
@@ -24,8 +32,26 @@ define(["knockout", "exports", "messageModel"], function(ko, exports, message) {
 		};
 
 		this.clearShapes = function() {
+			self.requestedView(null);
 			self.mapObjects([]);
 		};
+
+		this.loadLayers();
+	};
+
+	MapModel.prototype.loadLayers = function() {
+		reqwest({
+			url: "/api/v1/layers",
+			method: "post",
+			type: "json",
+			contentType: "application/json",
+		}).then(
+			function(responce) {
+				if (responce.success) {
+					this.tileLayers(responce.result);
+				}
+			}.bind(this)
+		);
 	};
 
 	MapModel.prototype.showObjects = function(mapObjects, messageCallback) {
@@ -34,7 +60,7 @@ define(["knockout", "exports", "messageModel"], function(ko, exports, message) {
 		}
 	};
 
-	exports.create = function(selectionCallback) {
-		return new MapModel(selectionCallback);
+	exports.create = function(selectionCallback, identifyCallback) {
+		return new MapModel(selectionCallback, identifyCallback);
 	};
 });
@@ -0,0 +1,42 @@
+"use strict";
+
+define([], function() {
+	// Objects of the same format requested with the same parameters and variables are one group
+	const groupKey = function(mapObject) {
+		return JSON.stringify([mapObject.format, mapObject.parameters || {}, mapObject.variables || {}]);
+	};
+
+	return {
+		// Groups ids of objects shown on the map by source format, parameters and variables, object shown twice
+		// included once. Objects of SQL console have no format and are skipped.
+		group: function(mapObjects) {
+			const groups = {};
+			const result = [];
+
+			for (const mapObject of mapObjects) {
+				if (mapObject.format === null) {
+					continue;
+				}
+
+				const key = groupKey(mapObject);
+				let group = groups[key];
+
+				if (!group) {
+					group = groups[key] = {
+						format: mapObject.format,
+						parameters: mapObject.parameters || {},
+						variables: mapObject.variables || {},
+						ids: [],
+					};
+					result.push(group);
+				}
+
+				if (group.ids.indexOf(mapObject.id) === -1) {
+					group.ids.push(mapObject.id);
+				}
+			}
+
+			return result;
+		},
+	};
+});
@@ -0,0 +1,149 @@
+"use strict";
+
+define([], function() {
+	const KEY_SHARE = "share";
+
+	// Values as "name=value,name=value", names and values escaped
+	const encodeValues = function(values) {
+		return Object.keys(values)
+			.map(function(name) {
+				return encodeURIComponent(name) + "=" + encodeURIComponent(values[name]);
+			})
+			.join(",");
+	};
+
+	const parseValues = function(value) {
+		const result = {};
+
+		for (const pair of value.split(",")) {
+			const separator = pair.indexOf("=");
+
+			if (separator > 0) {
+				const name = decodeURIComponent(pair.substring(0, separator));
+
+				result[name] = decodeURIComponent(pair.substring(separator + 1));
+			}
+		}
+
+		return result;
+	};
+
+	// Empty trailing parts omitted
+	const encodeGroup = function(group) {
+		const parts = [
+			encodeURIComponent(group.format),
+			group.ids.join(","),
+			encodeValues(group.parameters || {}),
+			encodeValues(group.variables || {}),
+		];
+
+		while (parts[parts.length - 1] === "") {
+			parts.pop();
+		}
+
+		return parts.join(":");
+	};
+
+	// Hash parameters: connection without password, objects as "format:id,id:parameters:variables;format:id" and view
+	// as "lat,lon,zoom". Parameters and variables of objects are optional.
+	const encode = function(state) {
+		const params = [
+			["host", state.host],
+			["port", state.port],
+			["database", state.database],
+			["role", state.role],
+			[
+				"objects",
+				state.objects.map(encodeGroup).join(";"),
+			],
+		];
+
+		if (state.view) {
+			params.push(["view", [state.view.lat.toFixed(6), state.view.lon.toFixed(6), state.view.zoom].join(",")]);
+		}
+
+		return params
+			.filter(function(param) {
+				return param[1] !== undefined && param[1] !== null;
+			})
+			.map(function(param) {
+				return param[0] + "=" + encodeURIComponent(param[1]);
+			})
+			.join("&");
+	};
+
+	const parseObjects = function(value) {
+		return value
+			.split(";")
+			.filter(function(group) {
+				return group.indexOf(":") > 0;
+			})
+			.map(function(group) {
+				const parts = group.split(":");
+
+				return {
+					format: decodeURIComponent(parts[0]),
+					ids: parts[1]
+						.split(",")
+						.map(Number)
+						.filter(Number.isInteger),
+					parameters: parts[2] ? parseValues(parts[2]) : {},
+					variables: parts[3] ? parseValues(parts[3]) : {},
+				};
+			});
+	};
+
+	const parseView = function(value) {
+		const parts = value.split(",").map(Number);
+
+		if (parts.length !== 3 || parts.some(isNaN)) {
+			return null;
+		}
+
+		return { lat: parts[0], lon: parts[1], zoom: parts[2] };
+	};
+
+	// Returns `{ share: id }` for short link, state for full link or null if hash is not a permalink.
+	const decode = function(hash) {
+		const params = {};
+
+		for (const param of hash.replace(/^#/, "").split("&")) {
+			const separator = param.indexOf("=");
+
+			if (separator > 0) {
+				params[param.substring(0, separator)] = decodeURIComponent(param.substring(separator + 1));
+			}
+		}
+
+		if (params[KEY_SHARE]) {
+			return { share: params[KEY_SHARE] };
+		}
+
+		if (!params.host || !params.database || !params.objects) {
+			return null;
+		}
+
+		return {
+			host: params.host,
+			port: params.port ? params.port | 0 : undefined,
+			database: params.database,
+			role: params.role,
+			objects: parseObjects(params.objects),
+			view: params.view ? parseView(params.view) : null,
+		};
+	};
+
+	const link = function(hash) {
+		return window.location.origin + window.location.pathname + "#" + hash;
+	};
+
+	return {
+		encode: encode,
+		decode: decode,
+		link: link,
+
+		shareHash: function(id) {
+			return KEY_SHARE + "=" + encodeURIComponent(id);
+		},
+	};
+});
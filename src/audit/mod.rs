@@ -0,0 +1,93 @@
+use crate::config::AuditConfig;
+use serde::Serialize;
+use std::fs;
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io::Result as IoResult;
+use std::io::Write;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+pub type AuditRef = Arc<AuditLog>;
+
+pub struct AuditLog {
+    path: String,
+    max_size: u64,
+    max_files: usize,
+    file: Mutex<Option<File>>,
+}
+
+impl AuditLog {
+    pub fn new(config: &AuditConfig) -> AuditLog {
+        AuditLog {
+            path: config.path().into(),
+            max_size: config.max_size(),
+            max_files: config.max_files(),
+            file: Mutex::new(None),
+        }
+    }
+
+    pub fn write<T>(&self, record: &T)
+    where
+        T: Serialize,
+    {
+        if let Err(error) = self.try_write(record) {
+            warn!("Failed to write audit record - {}", error);
+        }
+    }
+
+    fn try_write<T>(&self, record: &T) -> IoResult<()>
+    where
+        T: Serialize,
+    {
+        let mut line = serde_json::to_string(record)?;
+        line.push('\n');
+
+        let mut file = self.file.lock().unwrap();
+
+        if let Some(ref current) = *file {
+            let size = current.metadata()?.len();
+
+            if size > 0 && size + line.len() as u64 > self.max_size {
+                *file = None;
+
+                self.rotate()?;
+            }
+        }
+
+        if file.is_none() {
+            *file = Some(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)?,
+            );
+        }
+
+        match *file {
+            Some(ref mut file) => file.write_all(line.as_bytes()),
+            None => Ok(()),
+        }
+    }
+
+    // Shifts rotated files: `audit.log.1` becomes `audit.log.2` and so on, current file becomes `audit.log.1`.
+    fn rotate(&self) -> IoResult<()> {
+        if self.max_files == 0 {
+            return fs::remove_file(&self.path);
+        }
+
+        for index in (1..self.max_files).rev() {
+            let from = self.rotated_path(index);
+
+            if fs::metadata(&from).is_ok() {
+                fs::rename(&from, self.rotated_path(index + 1))?;
+            }
+        }
+
+        fs::rename(&self.path, self.rotated_path(1))
+    }
+
+    fn rotated_path(&self, index: usize) -> String {
+        format!("{}.{}", self.path, index)
+    }
+}
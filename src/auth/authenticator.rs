@@ -0,0 +1,28 @@
+#[derive(Debug, Clone)]
+pub struct Identity {
+    login: String,
+    roles: Vec<String>,
+}
+
+impl Identity {
+    pub fn new(login: &str, roles: &[String]) -> Identity {
+        Identity {
+            login: login.into(),
+            roles: roles.into(),
+        }
+    }
+
+    pub fn login(&self) -> &str {
+        &self.login
+    }
+
+    pub fn roles(&self) -> &[String] {
+        &self.roles
+    }
+}
+
+pub trait Authenticator: Send + Sync {
+    fn authenticate_password(&self, login: &str, password: &str) -> Option<Identity>;
+
+    fn authenticate_token(&self, token: &str) -> Option<Identity>;
+}
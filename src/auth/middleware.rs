@@ -0,0 +1,86 @@
+use super::AuthRef;
+use super::User;
+use super::SESSION_COOKIE;
+use crate::backend::HandlerError;
+use crate::metrics::MetricsRef;
+use iron::headers::Authorization;
+use iron::headers::Basic;
+use iron::headers::Bearer;
+use iron::headers::Cookie;
+use iron::status;
+use iron::typemap::Key;
+use iron::BeforeMiddleware;
+use iron::IronError;
+use iron::IronResult;
+use iron::Request;
+use std::time::Instant;
+
+pub struct CurrentUser;
+
+impl Key for CurrentUser {
+    type Value = User;
+}
+
+pub struct AuthMiddleware {
+    auth: AuthRef,
+    metrics: MetricsRef,
+}
+
+impl AuthMiddleware {
+    pub fn new(auth: AuthRef, metrics: MetricsRef) -> AuthMiddleware {
+        AuthMiddleware { auth, metrics }
+    }
+
+    fn authenticate(&self, request: &Request) -> Option<User> {
+        if let Some(Authorization(basic)) = request.headers.get::<Authorization<Basic>>() {
+            let password = basic.password.as_ref().map_or("", String::as_str);
+
+            return self.auth.password_user(&basic.username, password);
+        }
+
+        if let Some(Authorization(bearer)) = request.headers.get::<Authorization<Bearer>>() {
+            return self.auth.token_user(&bearer.token);
+        }
+
+        session_id(request).and_then(|session_id| self.auth.session_user(&session_id))
+    }
+}
+
+impl BeforeMiddleware for AuthMiddleware {
+    // Rejected requests don't reach handlers and are counted as requests of `auth` handler
+    fn before(&self, request: &mut Request) -> IronResult<()> {
+        let started = Instant::now();
+
+        match self.authenticate(request) {
+            Some(user) => {
+                request.extensions.insert::<CurrentUser>(user);
+
+                Ok(())
+            }
+            None => {
+                self.metrics.request_started();
+                self.metrics.request_finished(
+                    "auth",
+                    status::Unauthorized.to_u16(),
+                    started.elapsed(),
+                );
+
+                Err(IronError::new(
+                    HandlerError::new("Authentication required"),
+                    (status::Unauthorized, "Authentication required"),
+                ))
+            }
+        }
+    }
+}
+
+pub fn session_id(request: &Request) -> Option<String> {
+    let Cookie(cookies) = request.headers.get::<Cookie>()?;
+    let prefix = format!("{}=", SESSION_COOKIE);
+
+    cookies
+        .iter()
+        .map(|cookie| cookie.trim())
+        .find(|cookie| cookie.starts_with(&prefix))
+        .map(|cookie| cookie[prefix.len()..].into())
+}
@@ -0,0 +1,116 @@
+mod authenticator;
+mod middleware;
+mod session;
+mod user;
+mod users_file;
+
+pub use self::authenticator::Authenticator;
+pub use self::authenticator::Identity;
+pub use self::middleware::session_id;
+pub use self::middleware::AuthMiddleware;
+pub use self::middleware::CurrentUser;
+pub use self::session::SessionStore;
+pub use self::user::User;
+pub use self::users_file::UsersFile;
+
+use crate::config::AuthProvider;
+use crate::config::ConfigRef;
+use crate::config::ConfigResult;
+use std::sync::Arc;
+use std::time::Duration;
+
+pub const SESSION_COOKIE: &str = "session";
+
+pub type AuthRef = Arc<Auth>;
+
+pub struct Auth {
+    config: ConfigRef,
+    authenticator: Box<dyn Authenticator>,
+    sessions: SessionStore,
+}
+
+impl Auth {
+    pub fn from_config(config: ConfigRef) -> ConfigResult<Option<AuthRef>> {
+        let auth_config = match config.auth() {
+            Some(auth_config) => auth_config,
+            None => return Ok(None),
+        };
+        let authenticator: Box<dyn Authenticator> = match auth_config.provider() {
+            AuthProvider::UsersFile { path } => Box::new(UsersFile::load(path)?),
+        };
+        let sessions = SessionStore::new(Duration::from_secs(auth_config.session_timeout()));
+
+        Ok(Some(Arc::new(Auth {
+            config,
+            authenticator,
+            sessions,
+        })))
+    }
+
+    pub fn session_timeout(&self) -> u64 {
+        self.config.auth().map_or(0, |auth| auth.session_timeout())
+    }
+
+    pub fn login(&self, login: &str, password: &str) -> Option<(String, User)> {
+        let user = self.password_user(login, password)?;
+        let session_id = self.sessions.create(user.clone());
+
+        Some((session_id, user))
+    }
+
+    pub fn logout(&self, session_id: &str) {
+        self.sessions.remove(session_id);
+    }
+
+    pub fn session_user(&self, session_id: &str) -> Option<User> {
+        self.sessions.get(session_id)
+    }
+
+    pub fn password_user(&self, login: &str, password: &str) -> Option<User> {
+        self.authenticator
+            .authenticate_password(login, password)
+            .map(|identity| self.make_user(&identity))
+    }
+
+    pub fn token_user(&self, token: &str) -> Option<User> {
+        self.authenticator
+            .authenticate_token(token)
+            .map(|identity| self.make_user(&identity))
+    }
+
+    fn make_user(&self, identity: &Identity) -> User {
+        match self.config.auth() {
+            Some(auth_config) => User::new(identity, auth_config.roles()),
+            None => User::new(identity, &Default::default()),
+        }
+    }
+}
+
+pub fn is_format_allowed(user: Option<&User>, format: &str) -> bool {
+    match user {
+        Some(user) => user.is_format_allowed(format),
+        None => true,
+    }
+}
+
+/// Checks if SQL console is enabled and user has one of its roles, console is never available
+/// without authentication.
+pub fn is_sql_allowed(config: &ConfigRef, user: Option<&User>) -> bool {
+    match (config.sql(), user) {
+        (Some(sql), Some(user)) => user.has_any_role(sql.roles()),
+        _ => false,
+    }
+}
+
+pub fn is_connection_allowed(
+    config: &ConfigRef,
+    user: Option<&User>,
+    host: &str,
+    port: i16,
+    database: &str,
+) -> bool {
+    match user {
+        Some(user) => user.is_connection_allowed(config.connections(), host, port, database),
+        None => true,
+    }
+}
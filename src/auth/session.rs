@@ -0,0 +1,71 @@
+use super::User;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::Instant;
+
+const SESSION_ID_LENGTH: usize = 32;
+
+struct Session {
+    user: User,
+    expires: Instant,
+}
+
+pub struct SessionStore {
+    timeout: Duration,
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+impl SessionStore {
+    pub fn new(timeout: Duration) -> SessionStore {
+        SessionStore {
+            timeout,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn create(&self, user: User) -> String {
+        let session_id: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(SESSION_ID_LENGTH)
+            .map(char::from)
+            .collect();
+        let now = Instant::now();
+        let mut sessions = self.sessions.lock().unwrap();
+        sessions.retain(|_, session| session.expires > now);
+        sessions.insert(
+            session_id.clone(),
+            Session {
+                user,
+                expires: now + self.timeout,
+            },
+        );
+
+        session_id
+    }
+
+    pub fn get(&self, session_id: &str) -> Option<User> {
+        let now = Instant::now();
+        let mut sessions = self.sessions.lock().unwrap();
+
+        match sessions.get_mut(session_id) {
+            Some(session) if session.expires > now => {
+                session.expires = now + self.timeout;
+
+                Some(session.user.clone())
+            }
+            Some(_) => {
+                sessions.remove(session_id);
+
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub fn remove(&self, session_id: &str) {
+        self.sessions.lock().unwrap().remove(session_id);
+    }
+}
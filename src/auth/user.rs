@@ -0,0 +1,82 @@
+use super::Identity;
+use crate::config::ConnectionConfig;
+use crate::config::RoleConfig;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct User {
+    login: String,
+    roles: Vec<String>,
+    #[serde(skip)]
+    formats: Option<BTreeSet<String>>,
+    #[serde(skip)]
+    connections: Option<BTreeSet<String>>,
+}
+
+impl User {
+    pub fn new(identity: &Identity, role_configs: &BTreeMap<String, RoleConfig>) -> User {
+        let mut formats = Some(BTreeSet::new());
+        let mut connections = Some(BTreeSet::new());
+
+        for role in identity.roles() {
+            match role_configs.get(role) {
+                Some(role_config) => {
+                    merge_allowed(&mut formats, role_config.formats());
+                    merge_allowed(&mut connections, role_config.connections());
+                }
+                None => warn!("User {} has unknown role {}", identity.login(), role),
+            }
+        }
+
+        User {
+            login: identity.login().into(),
+            roles: identity.roles().into(),
+            formats,
+            connections,
+        }
+    }
+
+    pub fn login(&self) -> &str {
+        &self.login
+    }
+
+    pub fn has_any_role(&self, roles: &[String]) -> bool {
+        self.roles.iter().any(|role| roles.contains(role))
+    }
+
+    pub fn is_format_allowed(&self, format: &str) -> bool {
+        match self.formats {
+            Some(ref formats) => formats.contains(format),
+            None => true,
+        }
+    }
+
+    pub fn is_connection_allowed(
+        &self,
+        connections: &BTreeMap<String, ConnectionConfig>,
+        host: &str,
+        port: i16,
+        database: &str,
+    ) -> bool {
+        match self.connections {
+            Some(ref allowed) => allowed
+                .iter()
+                .filter_map(|name| connections.get(name))
+                .any(|connection| connection.matches(host, port, database)),
+            None => true,
+        }
+    }
+}
+
+// Role without list allows everything, otherwise allowed values are union of all user roles.
+fn merge_allowed(allowed: &mut Option<BTreeSet<String>>, values: Option<&[String]>) {
+    match values {
+        Some(values) => {
+            if let Some(allowed) = allowed {
+                allowed.extend(values.iter().cloned());
+            }
+        }
+        None => *allowed = None,
+    }
+}
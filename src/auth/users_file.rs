@@ -0,0 +1,79 @@
+use super::Authenticator;
+use super::Identity;
+use crate::config::ConfigError;
+use crate::config::ConfigResult;
+use sha2::Digest;
+use sha2::Sha256;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::path::Path;
+use subtle::ConstantTimeEq;
+
+// Verified when login is unknown, so response time doesn't tell which logins exist. Cost is the
+// same as of hashes created as described in README.
+const DUMMY_HASH: &str = "$2b$10$/D5AnbnXXZ5SeIDitEQcgu4e3wBcthF.GhFaRcxXznc6bT2wedIdq";
+
+#[derive(Debug, Deserialize)]
+struct UserEntry {
+    password: String,
+    #[serde(default)]
+    roles: Vec<String>,
+    #[serde(default)]
+    tokens: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UsersFile {
+    users: BTreeMap<String, UserEntry>,
+}
+
+impl UsersFile {
+    pub fn load<P>(path: P) -> ConfigResult<UsersFile>
+    where
+        P: AsRef<Path>,
+    {
+        let reader = File::open(path).map_err(ConfigError::io_error)?;
+        let users = serde_yaml::from_reader(reader).map_err(ConfigError::yaml_error)?;
+
+        Ok(users)
+    }
+}
+
+impl Authenticator for UsersFile {
+    fn authenticate_password(&self, login: &str, password: &str) -> Option<Identity> {
+        let entry = match self.users.get(login) {
+            Some(entry) => entry,
+            None => {
+                let _ = bcrypt::verify(password, DUMMY_HASH);
+
+                return None;
+            }
+        };
+
+        match bcrypt::verify(password, &entry.password) {
+            Ok(true) => Some(Identity::new(login, &entry.roles)),
+            Ok(false) => None,
+            Err(error) => {
+                warn!("Invalid password hash for user {} - {}", login, error);
+
+                None
+            }
+        }
+    }
+
+    fn authenticate_token(&self, token: &str) -> Option<Identity> {
+        let token_hash = format!("{:x}", Sha256::digest(token.as_bytes()));
+        let mut result = None;
+
+        // Every token compared in constant time, comparison doesn't stop on the found one
+        for (login, entry) in &self.users {
+            for user_token in &entry.tokens {
+                if bool::from(user_token.as_bytes().ct_eq(token_hash.as_bytes())) {
+                    result = Some(Identity::new(login, &entry.roles));
+                }
+            }
+        }
+
+        result
+    }
+}
@@ -0,0 +1,109 @@
+use crate::auth;
+use crate::auth::CurrentUser;
+use crate::backend::handler::loader::resolve_parameters;
+use crate::backend::handler::loader::resolve_variables;
+use crate::backend::handler::loader::scope_key;
+use crate::backend::handler::loader::ObjectLoader;
+use crate::backend::handler::util::handle_request;
+use crate::backend::HandlerError;
+use crate::cache::CacheRef;
+use crate::cache::CacheScope;
+use crate::config::ConfigRef;
+use crate::config::ParameterValue;
+use iron::Handler;
+use iron::IronResult;
+use iron::Request as IronRequest;
+use iron::Response as IronResponse;
+use std::collections::BTreeMap;
+
+pub struct CacheHandler {
+    config: ConfigRef,
+    cache: Option<CacheRef>,
+    loader: ObjectLoader,
+}
+
+impl CacheHandler {
+    pub fn new(config: ConfigRef, cache: Option<CacheRef>, loader: ObjectLoader) -> CacheHandler {
+        CacheHandler {
+            config,
+            cache,
+            loader,
+        }
+    }
+}
+
+impl Handler for CacheHandler {
+    fn handle(&self, request: &mut IronRequest) -> IronResult<IronResponse> {
+        let user = request.extensions.get::<CurrentUser>().cloned();
+
+        handle_request(request, move |request: Request| {
+            let cache = self
+                .cache
+                .as_ref()
+                .ok_or_else(|| HandlerError::new("Cache is not enabled"))?;
+
+            let format = self
+                .config
+                .formats()
+                .get(&request.format)
+                .filter(|_| auth::is_format_allowed(user.as_ref(), &request.format))
+                .ok_or_else(|| HandlerError::new("Request must contain valid format name"))?;
+
+            if !auth::is_connection_allowed(
+                &self.config,
+                user.as_ref(),
+                &request.host,
+                request.port,
+                &request.database,
+            ) {
+                return Err(HandlerError::new(
+                    "Connection to this database is not allowed",
+                ));
+            }
+
+            self.loader.check_credentials(
+                &request.host,
+                request.port,
+                &request.database,
+                &request.role,
+                &request.password,
+            )?;
+
+            let parameters = resolve_parameters(format, &request.parameters)?;
+            let variables = resolve_variables(
+                &self.config,
+                format,
+                &request.host,
+                request.port,
+                &request.database,
+                &request.variables,
+            )?;
+            let scope = CacheScope::new(
+                &request.host,
+                request.port,
+                &request.database,
+                &request.role,
+                &request.format,
+                &scope_key(format, &variables, &parameters),
+            );
+            cache.invalidate(&scope, request.ids.as_deref());
+
+            Ok(true)
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct Request {
+    host: String,
+    port: i16,
+    database: String,
+    role: String,
+    password: String,
+    format: String,
+    ids: Option<Vec<i64>>,
+    #[serde(default)]
+    parameters: BTreeMap<String, ParameterValue>,
+    #[serde(default)]
+    variables: BTreeMap<String, String>,
+}
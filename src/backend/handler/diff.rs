@@ -0,0 +1,91 @@
+use crate::backend::handler::loader::ConnectionQuery;
+use crate::backend::handler::loader::ObjectLoader;
+use crate::backend::handler::loader::ObjectQuery;
+use crate::backend::handler::loader::RequestContext;
+use crate::backend::handler::object::OutputOptions;
+use crate::backend::handler::object::ResponseObject;
+use crate::backend::handler::util::handle_request;
+use crate::config::FormatConfig;
+use crate::database::NamesGeometry;
+use crate::diff;
+use crate::diff::ObjectDiff;
+use iron::Handler;
+use iron::IronResult;
+use iron::Request as IronRequest;
+use iron::Response as IronResponse;
+
+pub struct DiffHandler {
+    loader: ObjectLoader,
+}
+
+impl DiffHandler {
+    pub fn new(loader: ObjectLoader) -> DiffHandler {
+        DiffHandler { loader }
+    }
+}
+
+impl Handler for DiffHandler {
+    fn handle(&self, request: &mut IronRequest) -> IronResult<IronResponse> {
+        let context = RequestContext::new(request);
+
+        handle_request(request, move |request: Request| {
+            let target = request.query.with_connection(&request.target);
+            let (format, before) = self.loader.load("diff", &request.query, &context)?;
+            let (_, after) = self.loader.load("diff", &target, &context)?;
+            let mut ids: Vec<i64> = request.query.ids().to_vec();
+            ids.sort_unstable();
+            ids.dedup();
+
+            let result: Vec<_> = ids
+                .into_iter()
+                .filter_map(|id| {
+                    let before = before.get(&id);
+                    let after = after.get(&id);
+
+                    if before.is_none() && after.is_none() {
+                        return None;
+                    }
+
+                    Some(ResponseDiff {
+                        id,
+                        diff: diff::compare(before, after),
+                        before: before.map(|data| response_object(format, id, data)),
+                        after: after.map(|data| response_object(format, id, data)),
+                    })
+                })
+                .collect();
+
+            Ok(result)
+        })
+    }
+}
+
+fn response_object(format: &FormatConfig, id: i64, data: &NamesGeometry) -> ResponseObject {
+    let style = format.style().resolve(data.attributes());
+
+    ResponseObject::new(
+        id,
+        data,
+        style,
+        Vec::new(),
+        OutputOptions::points(format.precision()),
+    )
+}
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(flatten)]
+    query: ObjectQuery,
+    target: ConnectionQuery,
+}
+
+#[derive(Serialize)]
+struct ResponseDiff {
+    id: i64,
+    #[serde(flatten)]
+    diff: ObjectDiff,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    before: Option<ResponseObject>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    after: Option<ResponseObject>,
+}
@@ -0,0 +1,54 @@
+use crate::backend::handler::loader::ObjectLoader;
+use crate::backend::handler::loader::ObjectQuery;
+use crate::backend::handler::loader::RequestContext;
+use crate::backend::handler::util::file_name;
+use crate::backend::handler::util::handle_download;
+use crate::backend::handler::util::Download;
+use crate::backend::HandlerError;
+use crate::export;
+use crate::export::ExportFormat;
+use crate::export::ExportObjects;
+use iron::Handler;
+use iron::IronResult;
+use iron::Request as IronRequest;
+use iron::Response as IronResponse;
+
+pub struct ExportHandler {
+    loader: ObjectLoader,
+}
+
+impl ExportHandler {
+    pub fn new(loader: ObjectLoader) -> ExportHandler {
+        ExportHandler { loader }
+    }
+}
+
+impl Handler for ExportHandler {
+    fn handle(&self, request: &mut IronRequest) -> IronResult<IronResponse> {
+        let context = RequestContext::new(request);
+
+        handle_download(request, move |request: Request| {
+            let (_, objects) = self.loader.load("export", &request.query, &context)?;
+            let objects: ExportObjects = objects.into_iter().collect();
+            let name = file_name(request.query.format());
+            let file = export::export(request.export_format, &name, &objects)
+                .map_err(|error| HandlerError::new(&format!("Export error - {}", error)))?;
+
+            let file_name = file.file_name().to_string();
+            let content_type = file.content_type().to_string();
+
+            Ok(Download::new(
+                &file_name,
+                &content_type,
+                file.into_content(),
+            ))
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(flatten)]
+    query: ObjectQuery,
+    export_format: ExportFormat,
+}
@@ -1,9 +1,12 @@
+use crate::auth;
+use crate::auth::CurrentUser;
 use crate::backend::handler::util::handle_empty;
 use crate::config::ConfigRef;
 use iron::Handler;
 use iron::IronResult;
 use iron::Request as IronRequest;
 use iron::Response as IronResponse;
+use std::collections::BTreeMap;
 
 pub struct FormatHandler {
     config: ConfigRef,
@@ -16,9 +19,85 @@ impl FormatHandler {
 }
 
 impl Handler for FormatHandler {
-    fn handle(&self, _request: &mut IronRequest) -> IronResult<IronResponse> {
+    fn handle(&self, request: &mut IronRequest) -> IronResult<IronResponse> {
+        let user = request.extensions.get::<CurrentUser>().cloned();
+
+        handle_empty(move || {
+            let result: Vec<_> = self
+                .config
+                .formats()
+                .keys()
+                .filter(|name| auth::is_format_allowed(user.as_ref(), name))
+                .cloned()
+                .collect();
+
+            Ok(result)
+        })
+    }
+}
+
+pub struct ParametersHandler {
+    config: ConfigRef,
+}
+
+impl ParametersHandler {
+    pub fn new(config: ConfigRef) -> ParametersHandler {
+        ParametersHandler { config }
+    }
+}
+
+impl Handler for ParametersHandler {
+    fn handle(&self, request: &mut IronRequest) -> IronResult<IronResponse> {
+        let user = request.extensions.get::<CurrentUser>().cloned();
+
         handle_empty(move || {
-            let result: Vec<_> = self.config.formats().keys().cloned().collect();
+            let result: BTreeMap<_, _> = self
+                .config
+                .formats()
+                .iter()
+                .filter(|(name, format)| {
+                    !format.parameters().is_empty() && auth::is_format_allowed(user.as_ref(), name)
+                })
+                .map(|(name, format)| (name, format.parameters()))
+                .collect();
+
+            Ok(result)
+        })
+    }
+}
+
+pub struct VariablesHandler {
+    config: ConfigRef,
+}
+
+impl VariablesHandler {
+    pub fn new(config: ConfigRef) -> VariablesHandler {
+        VariablesHandler { config }
+    }
+}
+
+impl Handler for VariablesHandler {
+    fn handle(&self, request: &mut IronRequest) -> IronResult<IronResponse> {
+        let user = request.extensions.get::<CurrentUser>().cloned();
+
+        handle_empty(move || {
+            let variables = self.config.variables();
+            let result: BTreeMap<_, BTreeMap<_, _>> = self
+                .config
+                .formats()
+                .iter()
+                .filter(|(name, _)| auth::is_format_allowed(user.as_ref(), name))
+                .map(|(name, format)| {
+                    let used = format
+                        .variables()
+                        .into_iter()
+                        .filter_map(|name| variables.get(&name).map(|variable| (name, variable)))
+                        .collect();
+
+                    (name, used)
+                })
+                .filter(|(_, used): &(_, BTreeMap<_, _>)| !used.is_empty())
+                .collect();
 
             Ok(result)
         })
@@ -0,0 +1,202 @@
+use crate::backend::handler::util::json_response;
+use crate::config::ConfigRef;
+use crate::database;
+use iron::status;
+use iron::Handler;
+use iron::IronResult;
+use iron::Request;
+use iron::Response;
+use std::collections::BTreeMap;
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+const CHECK_CACHE_DURATION: Duration = Duration::from_secs(10);
+
+pub struct HealthHandler {
+    config: ConfigRef,
+}
+
+impl HealthHandler {
+    pub fn new(config: ConfigRef) -> HealthHandler {
+        HealthHandler { config }
+    }
+}
+
+impl Handler for HealthHandler {
+    fn handle(&self, _: &mut Request) -> IronResult<Response> {
+        json_response(
+            status::Ok,
+            &HealthResponse {
+                status: "ok",
+                formats: self.config.formats().len(),
+                connections: self.config.connections().len(),
+            },
+        )
+    }
+}
+
+pub struct ReadyHandler {
+    config: ConfigRef,
+    last_check: Mutex<Option<(Instant, BTreeMap<String, TargetResponse>)>>,
+}
+
+impl ReadyHandler {
+    pub fn new(config: ConfigRef) -> ReadyHandler {
+        ReadyHandler {
+            config,
+            last_check: Mutex::new(None),
+        }
+    }
+}
+
+impl Handler for ReadyHandler {
+    fn handle(&self, _: &mut Request) -> IronResult<Response> {
+        let targets = self.cached_targets();
+        let ready = targets
+            .values()
+            .all(|target| target.status != TargetStatus::Error);
+        let status = if ready {
+            status::Ok
+        } else {
+            status::ServiceUnavailable
+        };
+
+        json_response(status, &ReadyResponse { ready, targets })
+    }
+}
+
+impl ReadyHandler {
+    // Only one check runs at a time, probes arriving during the check wait for it and probes
+    // arriving shortly after get the same result.
+    fn cached_targets(&self) -> BTreeMap<String, TargetResponse> {
+        let mut last_check = match self.last_check.lock() {
+            Ok(last_check) => last_check,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+
+        if let Some((checked, ref targets)) = *last_check {
+            if checked.elapsed() < CHECK_CACHE_DURATION {
+                return targets.clone();
+            }
+        }
+
+        let targets = self.check_targets();
+        *last_check = Some((Instant::now(), targets.clone()));
+
+        targets
+    }
+
+    // Checks all connections with configured role in parallel. Connections without role
+    // can not be checked and reported as skipped, checks not finished in time reported as errors.
+    fn check_targets(&self) -> BTreeMap<String, TargetResponse> {
+        let (sender, receiver) = mpsc::channel();
+        let mut result = BTreeMap::new();
+
+        for (name, connection) in self.config.connections() {
+            let role = match connection.role() {
+                Some(role) => role.to_string(),
+                None => {
+                    result.insert(name.clone(), TargetResponse::skipped());
+                    continue;
+                }
+            };
+            let name = name.clone();
+            let connection = connection.clone();
+            let sender = sender.clone();
+
+            result.insert(name.clone(), TargetResponse::timeout());
+            thread::spawn(move || {
+                let started = Instant::now();
+                let check = database::check_connection(
+                    connection.host(),
+                    connection.port(),
+                    connection.database(),
+                    &role,
+                    connection.password(),
+                    CHECK_TIMEOUT,
+                );
+                let duration_ms = started.elapsed().as_millis();
+                let target = match check {
+                    Ok(()) => TargetResponse {
+                        status: TargetStatus::Ok,
+                        message: None,
+                        duration_ms: Some(duration_ms),
+                    },
+                    Err(error) => TargetResponse {
+                        status: TargetStatus::Error,
+                        message: Some(format!("{}", error)),
+                        duration_ms: Some(duration_ms),
+                    },
+                };
+                let _ = sender.send((name, target));
+            });
+        }
+
+        drop(sender);
+
+        let deadline = Instant::now() + CHECK_TIMEOUT;
+
+        while let Some(timeout) = deadline.checked_duration_since(Instant::now()) {
+            match receiver.recv_timeout(timeout) {
+                Ok((name, target)) => {
+                    result.insert(name, target);
+                }
+                Err(_) => break,
+            }
+        }
+
+        result
+    }
+}
+
+#[derive(Serialize)]
+struct HealthResponse {
+    status: &'static str,
+    formats: usize,
+    connections: usize,
+}
+
+#[derive(Serialize)]
+struct ReadyResponse {
+    ready: bool,
+    targets: BTreeMap<String, TargetResponse>,
+}
+
+#[derive(Clone, Serialize)]
+struct TargetResponse {
+    status: TargetStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<u128>,
+}
+
+impl TargetResponse {
+    fn skipped() -> TargetResponse {
+        TargetResponse {
+            status: TargetStatus::Skipped,
+            message: Some("No role configured for connection".into()),
+            duration_ms: None,
+        }
+    }
+
+    fn timeout() -> TargetResponse {
+        TargetResponse {
+            status: TargetStatus::Error,
+            message: Some("Connection check timed out".into()),
+            duration_ms: None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum TargetStatus {
+    Ok,
+    Error,
+    Skipped,
+}
@@ -0,0 +1,92 @@
+use crate::auth;
+use crate::auth::CurrentUser;
+use crate::backend::handler::util::handle_request;
+use crate::backend::HandlerError;
+use crate::config::ConfigRef;
+use crate::database;
+use crate::introspect;
+use crate::introspect::TableProposal;
+use iron::Handler;
+use iron::IronResult;
+use iron::Request as IronRequest;
+use iron::Response as IronResponse;
+
+pub struct IntrospectHandler {
+    config: ConfigRef,
+}
+
+impl IntrospectHandler {
+    pub fn new(config: ConfigRef) -> IntrospectHandler {
+        IntrospectHandler { config }
+    }
+}
+
+impl Handler for IntrospectHandler {
+    fn handle(&self, request: &mut IronRequest) -> IronResult<IronResponse> {
+        let user = request.extensions.get::<CurrentUser>().cloned();
+
+        handle_request(request, move |request: Request| {
+            if !auth::is_connection_allowed(
+                &self.config,
+                user.as_ref(),
+                &request.host,
+                request.port,
+                &request.database,
+            ) {
+                return Err(HandlerError::new(
+                    "Connection to this database is not allowed",
+                ));
+            }
+
+            let tables = database::load_geometry_tables(
+                &request.host,
+                request.port,
+                &request.database,
+                &request.role,
+                &request.password,
+            )
+            .map_err(|error| HandlerError::new(&format!("Database error - {}", error)))?;
+            let proposals = introspect::propose_formats(tables);
+            let mut chosen = Vec::new();
+
+            for reference in &request.tables {
+                let proposal = proposals
+                    .iter()
+                    .find(|proposal| proposal.matches(reference))
+                    .ok_or_else(|| {
+                        HandlerError::new(&format!("Unknown geometry table {}", reference))
+                    })?;
+                chosen.push(proposal);
+            }
+
+            if request.tables.is_empty() {
+                chosen = proposals.iter().collect();
+            }
+
+            let yaml =
+                introspect::formats_yaml(&chosen).map_err(|message| HandlerError::new(&message))?;
+
+            Ok(Response {
+                tables: proposals,
+                yaml,
+            })
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct Request {
+    host: String,
+    port: i16,
+    database: String,
+    role: String,
+    password: String,
+    #[serde(default)]
+    tables: Vec<String>,
+}
+
+#[derive(Serialize)]
+struct Response {
+    tables: Vec<TableProposal>,
+    yaml: String,
+}
@@ -0,0 +1,595 @@
+use crate::audit::AuditRef;
+use crate::auth;
+use crate::auth::CurrentUser;
+use crate::auth::User;
+use crate::backend::HandlerError;
+use crate::backend::HandlerResult;
+use crate::cache::CacheRef;
+use crate::cache::CacheScope;
+use crate::config::variables_key;
+use crate::config::Config;
+use crate::config::ConfigRef;
+use crate::config::FormatConfig;
+use crate::config::ParameterValue;
+use crate::config::QueryParameter;
+use crate::database;
+use crate::database::DatabaseClient;
+use crate::database::NamesGeometry;
+use crate::database::QueryStats;
+use crate::metrics::MetricsRef;
+use crate::mvt;
+use crate::mvt::TileCoordinates;
+use iron::Request as IronRequest;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+const CREDENTIALS_CHECK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Deserialize)]
+pub struct ObjectQuery {
+    host: String,
+    port: i16,
+    database: String,
+    role: String,
+    password: String,
+    format: String,
+    ids: Vec<i64>,
+    #[serde(default)]
+    parameters: BTreeMap<String, ParameterValue>,
+    #[serde(default)]
+    variables: BTreeMap<String, String>,
+    #[serde(default)]
+    refresh: bool,
+}
+
+impl ObjectQuery {
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+
+    pub fn ids(&self) -> &[i64] {
+        &self.ids
+    }
+
+    pub fn with_connection(&self, connection: &ConnectionQuery) -> ObjectQuery {
+        ObjectQuery {
+            host: connection.host.clone(),
+            port: connection.port,
+            database: connection.database.clone(),
+            role: connection.role.clone(),
+            password: connection.password.clone(),
+            format: self.format.clone(),
+            ids: self.ids.clone(),
+            parameters: self.parameters.clone(),
+            variables: self.variables.clone(),
+            refresh: self.refresh,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ConnectionQuery {
+    host: String,
+    port: i16,
+    database: String,
+    role: String,
+    password: String,
+}
+
+impl ConnectionQuery {
+    pub fn new(
+        host: &str,
+        port: i16,
+        database: &str,
+        role: &str,
+        password: &str,
+    ) -> ConnectionQuery {
+        ConnectionQuery {
+            host: host.into(),
+            port,
+            database: database.into(),
+            role: role.into(),
+            password: password.into(),
+        }
+    }
+}
+
+pub struct TileQuery {
+    host: String,
+    port: i16,
+    database: String,
+    role: String,
+    password: String,
+    parameters: BTreeMap<String, ParameterValue>,
+    variables: BTreeMap<String, String>,
+    refresh: bool,
+}
+
+impl TileQuery {
+    pub fn new(
+        connection: ConnectionQuery,
+        parameters: BTreeMap<String, ParameterValue>,
+        variables: BTreeMap<String, String>,
+        refresh: bool,
+    ) -> TileQuery {
+        TileQuery {
+            host: connection.host,
+            port: connection.port,
+            database: connection.database,
+            role: connection.role,
+            password: connection.password,
+            parameters,
+            variables,
+            refresh,
+        }
+    }
+}
+
+pub struct RequestContext {
+    user: Option<User>,
+    client: SocketAddr,
+}
+
+impl RequestContext {
+    pub fn new(request: &IronRequest) -> RequestContext {
+        RequestContext {
+            user: request.extensions.get::<CurrentUser>().cloned(),
+            client: request.remote_addr,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ObjectLoader {
+    config: ConfigRef,
+    audit: Option<AuditRef>,
+    cache: Option<CacheRef>,
+    metrics: MetricsRef,
+}
+
+impl ObjectLoader {
+    pub fn new(
+        config: ConfigRef,
+        audit: Option<AuditRef>,
+        cache: Option<CacheRef>,
+        metrics: MetricsRef,
+    ) -> ObjectLoader {
+        ObjectLoader {
+            config,
+            audit,
+            cache,
+            metrics,
+        }
+    }
+
+    pub fn load(
+        &self,
+        action: &'static str,
+        query: &ObjectQuery,
+        context: &RequestContext,
+    ) -> HandlerResult<(&FormatConfig, HashMap<i64, NamesGeometry>)> {
+        let started = Instant::now();
+        let result = self.try_load(query, context.user.as_ref());
+
+        // Unknown format names are not recorded to keep the number of series bounded
+        if self.config.formats().contains_key(&query.format) {
+            self.metrics
+                .format_request(&query.format, result.is_ok(), started.elapsed());
+        }
+
+        if let Some(ref audit) = self.audit {
+            let (objects, stats) = match result {
+                Ok((_, ref objects, stats)) => (objects.len(), stats),
+                Err(_) => (0, QueryStats::default()),
+            };
+
+            audit.write(&AuditRecord {
+                timestamp: format!("{}", time::now_utc().rfc3339()),
+                action,
+                client: format!("{}", context.client),
+                user: context.user.as_ref().map(User::login),
+                host: &query.host,
+                port: query.port,
+                database: &query.database,
+                role: &query.role,
+                format: &query.format,
+                id_count: query.ids.len(),
+                ids: &query.ids,
+                tile: None,
+                parameters: &query.parameters,
+                variables: &query.variables,
+                objects,
+                names_rows: stats.names_rows(),
+                geometry_rows: stats.geometry_rows(),
+                duration_ms: started.elapsed().as_millis(),
+                success: result.is_ok(),
+                error: result.as_ref().err().map(|error| format!("{}", error)),
+            });
+        }
+
+        result.map(|(format, objects, _)| (format, objects))
+    }
+
+    pub fn load_tile(
+        &self,
+        format_name: &str,
+        tile: TileCoordinates,
+        query: &TileQuery,
+        context: &RequestContext,
+    ) -> HandlerResult<Arc<Vec<u8>>> {
+        let started = Instant::now();
+        let result = self.try_load_tile(format_name, tile, query, context.user.as_ref());
+
+        if self.config.formats().contains_key(format_name) {
+            self.metrics
+                .format_request(format_name, result.is_ok(), started.elapsed());
+        }
+
+        // Tiles taken from cache recorded without object and row counts
+        if let Some(ref audit) = self.audit {
+            let (objects, stats) = match result {
+                Ok((_, objects, stats)) => (objects, stats),
+                Err(_) => (0, QueryStats::default()),
+            };
+
+            audit.write(&AuditRecord {
+                timestamp: format!("{}", time::now_utc().rfc3339()),
+                action: "tile",
+                client: format!("{}", context.client),
+                user: context.user.as_ref().map(User::login),
+                host: &query.host,
+                port: query.port,
+                database: &query.database,
+                role: &query.role,
+                format: format_name,
+                id_count: 0,
+                ids: &[],
+                tile: Some(format!("{}/{}/{}", tile.z(), tile.x(), tile.y())),
+                parameters: &query.parameters,
+                variables: &query.variables,
+                objects,
+                names_rows: stats.names_rows(),
+                geometry_rows: stats.geometry_rows(),
+                duration_ms: started.elapsed().as_millis(),
+                success: result.is_ok(),
+                error: result.as_ref().err().map(|error| format!("{}", error)),
+            });
+        }
+
+        result.map(|(content, _, _)| content)
+    }
+
+    fn try_load_tile(
+        &self,
+        format_name: &str,
+        tile: TileCoordinates,
+        query: &TileQuery,
+        user: Option<&User>,
+    ) -> HandlerResult<(Arc<Vec<u8>>, usize, QueryStats)> {
+        let format =
+            self.check_access(format_name, &query.host, query.port, &query.database, user)?;
+
+        if format.tile_query().is_none() {
+            return Err(HandlerError::new("Format has no tile query"));
+        }
+
+        let parameters = resolve_parameters(format, &query.parameters)?;
+        let variables = resolve_variables(
+            &self.config,
+            format,
+            &query.host,
+            query.port,
+            &query.database,
+            &query.variables,
+        )?;
+
+        if tile.z() < format.tile_min_zoom() {
+            let content = mvt::encode(format_name, tile, &HashMap::new());
+
+            return Ok((Arc::new(content), 0, QueryStats::default()));
+        }
+
+        let cache = self.cache.as_ref().filter(|_| format.cache());
+        let scope = CacheScope::new(
+            &query.host,
+            query.port,
+            &query.database,
+            &query.role,
+            format_name,
+            &scope_key(format, &variables, &parameters),
+        );
+
+        if let Some(cache) = cache.filter(|_| !query.refresh) {
+            if let Some(content) = cache.get_tile(&scope, tile.z(), tile.x(), tile.y()) {
+                self.check_credentials(
+                    &query.host,
+                    query.port,
+                    &query.database,
+                    &query.role,
+                    &query.password,
+                )?;
+
+                return Ok((content, 0, QueryStats::default()));
+            }
+        }
+
+        let client = DatabaseClient::new(
+            format,
+            &query.host,
+            query.port,
+            &query.database,
+            &query.role,
+            &query.password,
+        )
+        .with_parameters(parameters)
+        .with_variables(variables);
+        self.metrics.connection_opened();
+        let result = client.query_tile(tile.bounds());
+        self.metrics.connection_closed();
+
+        let (objects, stats) = match result {
+            Ok(result) => result,
+            Err(error) => {
+                self.metrics.database_error(error.kind());
+                return Err(HandlerError::new(&format!("Database error - {}", error)));
+            }
+        };
+
+        self.metrics.database_query(
+            format_name,
+            stats.connect_time(),
+            stats.query_time(),
+            stats.names_rows(),
+            stats.geometry_rows(),
+        );
+
+        let content = Arc::new(mvt::encode(format_name, tile, &objects));
+
+        if let Some(cache) = cache {
+            cache.put_tile(&scope, tile.z(), tile.x(), tile.y(), content.clone());
+        }
+
+        Ok((content, objects.len(), stats))
+    }
+
+    fn try_load(
+        &self,
+        query: &ObjectQuery,
+        user: Option<&User>,
+    ) -> HandlerResult<(&FormatConfig, HashMap<i64, NamesGeometry>, QueryStats)> {
+        let format = self.check_access(
+            &query.format,
+            &query.host,
+            query.port,
+            &query.database,
+            user,
+        )?;
+
+        if query.ids.is_empty() {
+            return Err(HandlerError::new("Request must contain at least one id"));
+        }
+
+        let parameters = resolve_parameters(format, &query.parameters)?;
+        let variables = resolve_variables(
+            &self.config,
+            format,
+            &query.host,
+            query.port,
+            &query.database,
+            &query.variables,
+        )?;
+        let (objects, stats) = self.load_objects(query, format, parameters, variables)?;
+
+        Ok((format, objects, stats))
+    }
+
+    fn check_access(
+        &self,
+        format_name: &str,
+        host: &str,
+        port: i16,
+        database: &str,
+        user: Option<&User>,
+    ) -> HandlerResult<&FormatConfig> {
+        let format = self
+            .config
+            .formats()
+            .get(format_name)
+            .filter(|_| auth::is_format_allowed(user, format_name))
+            .ok_or_else(|| HandlerError::new("Request must contain valid format name"))?;
+
+        if !auth::is_connection_allowed(&self.config, user, host, port, database) {
+            return Err(HandlerError::new(
+                "Connection to this database is not allowed",
+            ));
+        }
+
+        Ok(format)
+    }
+
+    /// Checks that role and password are accepted by the database, cache is not keyed by password
+    /// so cached objects and tiles are returned only after the check.
+    pub fn check_credentials(
+        &self,
+        host: &str,
+        port: i16,
+        database: &str,
+        role: &str,
+        password: &str,
+    ) -> HandlerResult<()> {
+        self.metrics.connection_opened();
+        let result = database::check_connection(
+            host,
+            port,
+            database,
+            role,
+            password,
+            CREDENTIALS_CHECK_TIMEOUT,
+        );
+        self.metrics.connection_closed();
+
+        result.map_err(|error| {
+            self.metrics.database_error(error.kind());
+            HandlerError::new(&format!("Database error - {}", error))
+        })
+    }
+
+    // Takes objects from cache if it is enabled for the format, only missing objects
+    // are queried from database and put to cache.
+    fn load_objects(
+        &self,
+        query: &ObjectQuery,
+        format: &FormatConfig,
+        parameters: Vec<QueryParameter>,
+        variables: BTreeMap<String, String>,
+    ) -> HandlerResult<(HashMap<i64, NamesGeometry>, QueryStats)> {
+        let cache = self.cache.as_ref().filter(|_| format.cache());
+        let scope = CacheScope::new(
+            &query.host,
+            query.port,
+            &query.database,
+            &query.role,
+            &query.format,
+            &scope_key(format, &variables, &parameters),
+        );
+        let mut objects = HashMap::new();
+        let mut missing = Vec::new();
+
+        match cache {
+            Some(cache) if !query.refresh => {
+                for &id in &query.ids {
+                    match cache.get(&scope, id) {
+                        Some(object) => {
+                            objects.insert(id, object);
+                        }
+                        None => missing.push(id),
+                    }
+                }
+
+                self.metrics
+                    .cache_lookup(&query.format, objects.len(), missing.len());
+            }
+            _ => missing = query.ids.clone(),
+        }
+
+        if missing.is_empty() {
+            self.check_credentials(
+                &query.host,
+                query.port,
+                &query.database,
+                &query.role,
+                &query.password,
+            )?;
+
+            return Ok((objects, QueryStats::default()));
+        }
+
+        let client = DatabaseClient::new(
+            format,
+            &query.host,
+            query.port,
+            &query.database,
+            &query.role,
+            &query.password,
+        )
+        .with_parameters(parameters)
+        .with_variables(variables);
+        self.metrics.connection_opened();
+        let result = client.query(&missing);
+        self.metrics.connection_closed();
+
+        let (loaded, stats) = match result {
+            Ok(result) => result,
+            Err(error) => {
+                self.metrics.database_error(error.kind());
+                return Err(HandlerError::new(&format!("Database error - {}", error)));
+            }
+        };
+
+        self.metrics.database_query(
+            &query.format,
+            stats.connect_time(),
+            stats.query_time(),
+            stats.names_rows(),
+            stats.geometry_rows(),
+        );
+
+        for (id, object) in loaded {
+            if let Some(cache) = cache {
+                cache.put(&scope, id, &object);
+            }
+
+            objects.insert(id, object);
+        }
+
+        Ok((objects, stats))
+    }
+}
+
+pub fn resolve_parameters(
+    format: &FormatConfig,
+    values: &BTreeMap<String, ParameterValue>,
+) -> HandlerResult<Vec<QueryParameter>> {
+    format
+        .resolve_parameters(values)
+        .map_err(|message| HandlerError::new(&message))
+}
+
+pub fn resolve_variables(
+    config: &Config,
+    format: &FormatConfig,
+    host: &str,
+    port: i16,
+    database: &str,
+    values: &BTreeMap<String, String>,
+) -> HandlerResult<BTreeMap<String, String>> {
+    config
+        .resolve_variables(format, host, port, database, values)
+        .map_err(|message| HandlerError::new(&message))
+}
+
+pub fn scope_key(
+    format: &FormatConfig,
+    variables: &BTreeMap<String, String>,
+    parameters: &[QueryParameter],
+) -> String {
+    let keys = [variables_key(variables), format.parameters_key(parameters)];
+
+    keys.iter()
+        .filter(|key| !key.is_empty())
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+#[derive(Serialize)]
+struct AuditRecord<'a> {
+    timestamp: String,
+    action: &'static str,
+    client: String,
+    user: Option<&'a str>,
+    host: &'a str,
+    port: i16,
+    database: &'a str,
+    role: &'a str,
+    format: &'a str,
+    id_count: usize,
+    ids: &'a [i64],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tile: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    parameters: &'a BTreeMap<String, ParameterValue>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    variables: &'a BTreeMap<String, String>,
+    objects: usize,
+    names_rows: usize,
+    geometry_rows: usize,
+    duration_ms: u128,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
@@ -0,0 +1,85 @@
+use crate::auth::session_id;
+use crate::auth::AuthRef;
+use crate::auth::SESSION_COOKIE;
+use crate::backend::handler::util::handle_empty;
+use crate::backend::handler::util::handle_request;
+use crate::backend::HandlerError;
+use iron::headers::SetCookie;
+use iron::Handler;
+use iron::IronResult;
+use iron::Request as IronRequest;
+use iron::Response as IronResponse;
+
+pub struct LoginHandler {
+    auth: Option<AuthRef>,
+}
+
+impl LoginHandler {
+    pub fn new(auth: Option<AuthRef>) -> LoginHandler {
+        LoginHandler { auth }
+    }
+}
+
+impl Handler for LoginHandler {
+    fn handle(&self, request: &mut IronRequest) -> IronResult<IronResponse> {
+        let mut session = None;
+        let mut response = handle_request(request, |request: Request| {
+            let auth = self
+                .auth
+                .as_ref()
+                .ok_or_else(|| HandlerError::new("Authentication is disabled"))?;
+
+            match auth.login(&request.login, &request.password) {
+                Some((session_id, user)) => {
+                    session = Some(session_id);
+
+                    Ok(user)
+                }
+                None => Err(HandlerError::new("Invalid login or password")),
+            }
+        })?;
+
+        if let (Some(session_id), Some(auth)) = (session, &self.auth) {
+            response.headers.set(SetCookie(vec![format!(
+                "{}={}; Path=/; Max-Age={}; HttpOnly; SameSite=Strict",
+                SESSION_COOKIE,
+                session_id,
+                auth.session_timeout()
+            )]));
+        }
+
+        Ok(response)
+    }
+}
+
+pub struct LogoutHandler {
+    auth: Option<AuthRef>,
+}
+
+impl LogoutHandler {
+    pub fn new(auth: Option<AuthRef>) -> LogoutHandler {
+        LogoutHandler { auth }
+    }
+}
+
+impl Handler for LogoutHandler {
+    fn handle(&self, request: &mut IronRequest) -> IronResult<IronResponse> {
+        if let (Some(session_id), Some(auth)) = (session_id(request), &self.auth) {
+            auth.logout(&session_id);
+        }
+
+        let mut response = handle_empty(|| Ok(true))?;
+        response.headers.set(SetCookie(vec![format!(
+            "{}=; Path=/; Max-Age=0; HttpOnly; SameSite=Strict",
+            SESSION_COOKIE
+        )]));
+
+        Ok(response)
+    }
+}
+
+#[derive(Deserialize)]
+struct Request {
+    login: String,
+    password: String,
+}
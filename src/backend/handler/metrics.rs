@@ -0,0 +1,31 @@
+use crate::metrics::MetricsRef;
+use iron::mime::Mime;
+use iron::mime::SubLevel;
+use iron::mime::TopLevel;
+use iron::status;
+use iron::Handler;
+use iron::IronResult;
+use iron::Request;
+use iron::Response;
+
+pub struct MetricsHandler {
+    metrics: MetricsRef,
+}
+
+impl MetricsHandler {
+    pub fn new(metrics: MetricsRef) -> MetricsHandler {
+        MetricsHandler { metrics }
+    }
+}
+
+impl Handler for MetricsHandler {
+    fn handle(&self, _: &mut Request) -> IronResult<Response> {
+        let content_type = Mime(TopLevel::Text, SubLevel::Plain, vec![]);
+
+        Ok(Response::with((
+            status::Ok,
+            content_type,
+            self.metrics.render(),
+        )))
+    }
+}
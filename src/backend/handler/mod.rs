@@ -1,8 +1,43 @@
+mod cache;
+mod diff;
 mod empty;
+mod export;
 mod format;
+mod health;
+mod introspect;
+mod loader;
+mod login;
+mod metrics;
 mod object;
+mod render;
+mod share;
+mod sql;
+mod tile;
+mod user;
 mod util;
+mod vector_tile;
 
+pub use self::cache::CacheHandler;
+pub use self::diff::DiffHandler;
 pub use self::empty::EmptyHandler;
+pub use self::export::ExportHandler;
 pub use self::format::FormatHandler;
+pub use self::format::ParametersHandler;
+pub use self::format::VariablesHandler;
+pub use self::health::HealthHandler;
+pub use self::health::ReadyHandler;
+pub use self::introspect::IntrospectHandler;
+pub use self::loader::ObjectLoader;
+pub use self::login::LoginHandler;
+pub use self::login::LogoutHandler;
+pub use self::metrics::MetricsHandler;
 pub use self::object::ObjectHandler;
+pub use self::render::RenderHandler;
+pub use self::share::ShareHandler;
+pub use self::share::SharedStateHandler;
+pub use self::sql::SqlHandler;
+pub use self::tile::LayerHandler;
+pub use self::tile::TileHandler;
+pub use self::user::UserHandler;
+pub use self::vector_tile::TileFormatsHandler;
+pub use self::vector_tile::VectorTileHandler;
@@ -1,120 +1,185 @@
+use crate::backend::handler::loader::ObjectLoader;
+use crate::backend::handler::loader::ObjectQuery;
+use crate::backend::handler::loader::RequestContext;
 use crate::backend::handler::util::handle_request;
-use crate::backend::HandlerError;
-use crate::config::ConfigRef;
-use crate::database::DatabaseClient;
+use crate::backend::HandlerResult;
+use crate::config::StyleProperties;
+use crate::database::Attributes;
 use crate::database::Geometry;
 use crate::database::MultiLine;
 use crate::database::MultiPolygon;
+use crate::database::NamesGeometry;
 use crate::database::Point;
+use crate::database::EPSILON;
+use crate::encoding;
+use crate::encoding::GeometryEncoding;
+use crate::metrics::MetricsRef;
+use crate::validation;
+use crate::validation::Issue;
 use iron::Handler;
 use iron::IronResult;
 use iron::Request as IronRequest;
 use iron::Response as IronResponse;
+use serde::Serialize;
+use serde::Serializer;
 
 pub struct ObjectHandler {
-    config: ConfigRef,
+    loader: ObjectLoader,
+    metrics: MetricsRef,
 }
 
 impl ObjectHandler {
-    pub fn new(config: ConfigRef) -> ObjectHandler {
-        ObjectHandler { config }
+    pub fn new(loader: ObjectLoader, metrics: MetricsRef) -> ObjectHandler {
+        ObjectHandler { loader, metrics }
     }
 }
 
 impl Handler for ObjectHandler {
     fn handle(&self, request: &mut IronRequest) -> IronResult<IronResponse> {
+        let context = RequestContext::new(request);
+
         handle_request(request, move |request: Request| {
-            let format = &request.format;
-            let format = self
-                .config
-                .formats()
-                .get(format)
-                .ok_or_else(|| HandlerError::new("Request must contain valid format name"))?;
-
-            if request.ids.is_empty() {
-                return Err(HandlerError::new("Request must contain at least one id"));
-            }
+            let objects = self.query_objects(&request, &context)?;
+            let points = objects.iter().map(ResponseObject::points_count).sum();
+            self.metrics
+                .points_serialized(request.query.format(), points);
+
+            Ok(objects)
+        })
+    }
+}
 
-            let client = DatabaseClient::new(
-                &format,
-                &request.host,
-                request.port,
-                &request.database,
-                &request.role,
-                &request.password,
-            );
-            let objects = match client.query(&request.ids) {
-                Ok(objects) => objects,
-                Err(error) => return Err(HandlerError::new(&format!("Database error - {}", error))),
+impl ObjectHandler {
+    fn query_objects(
+        &self,
+        request: &Request,
+        context: &RequestContext,
+    ) -> HandlerResult<Vec<ResponseObject>> {
+        let (format, objects) = self.loader.load("object", &request.query, context)?;
+        // Point indices and duplicate marks of inspection mode available only as points
+        let encoding = if request.inspect {
+            GeometryEncoding::Points
+        } else {
+            request.encoding
+        };
+        let options = OutputOptions {
+            inspect: request.inspect,
+            encoding,
+            precision: format.precision(),
+        };
+        let mut result = Vec::new();
+
+        for (id, data) in objects {
+            let style = format.style().resolve(data.attributes());
+            let issues = if request.validate {
+                validation::validate(data.geometry())
+            } else {
+                Vec::new()
             };
 
-            let mut result = Vec::new();
-
-            for (id, data) in objects {
-                let object = match data.geometry() {
-                    Geometry::MultiLine(lines) => ResponseObject::MultiLineString {
-                        id,
-                        names: data.names().into(),
-                        lines: collect_lines(lines),
-                    },
-                    Geometry::MultiPolygon(polygons) => ResponseObject::MultiPolygon {
-                        id,
-                        names: data.names().into(),
-                        polygons: collect_polygons(polygons),
-                    },
-                };
-
-                result.push(object);
-            }
+            result.push(ResponseObject::new(id, &data, style, issues, options));
+        }
 
-            Ok(result)
-        })
+        Ok(result)
     }
 }
 
-fn collect_lines(multi_line: &MultiLine) -> Vec<Vec<ResponsePoint>> {
+#[derive(Clone, Copy)]
+pub struct OutputOptions {
+    inspect: bool,
+    encoding: GeometryEncoding,
+    // Number of decimal places of coordinates, full precision if not set
+    precision: Option<u32>,
+}
+
+impl OutputOptions {
+    pub fn points(precision: Option<u32>) -> OutputOptions {
+        OutputOptions {
+            inspect: false,
+            encoding: GeometryEncoding::Points,
+            precision,
+        }
+    }
+}
+
+fn collect_lines(multi_line: &MultiLine, options: OutputOptions) -> Vec<ResponsePart> {
     multi_line
         .lines()
         .iter()
-        .map(|line| collect_points(line.points()))
+        .map(|line| encode_part(collect_points(line.points(), options), options.encoding))
         .collect()
 }
 
-fn collect_polygons(multi_polygon: &MultiPolygon) -> Vec<Vec<ResponsePoint>> {
+fn collect_polygons(multi_polygon: &MultiPolygon, options: OutputOptions) -> Vec<ResponsePart> {
     multi_polygon
         .polygons()
         .iter()
-        .map(|polygon| collect_points(polygon.points()))
+        .map(|polygon| encode_part(collect_points(polygon.points(), options), options.encoding))
         .collect()
 }
 
-const EPSILON: f32 = 0.000001;
+fn encode_part(points: Vec<ResponsePoint>, encoding: GeometryEncoding) -> ResponsePart {
+    let count = points.len();
+    let coordinates = points.iter().map(|point| (point.lat, point.lon));
+    let data = match encoding {
+        GeometryEncoding::Points => PartData::Points(points),
+        GeometryEncoding::Flat => PartData::Flat(
+            points
+                .iter()
+                .flat_map(|point| [point.lat, point.lon])
+                .collect(),
+        ),
+        GeometryEncoding::Polyline => PartData::Encoded(encoding::encode_polyline(coordinates)),
+        GeometryEncoding::Delta => PartData::Encoded(encoding::encode_delta(coordinates)),
+    };
 
-fn collect_points(points: &[Point]) -> Vec<ResponsePoint> {
-    let mut it = points.iter();
-    let mut result = Vec::new();
-
-    if let Some(point) = it.next() {
-        let mut last_lat = point.lat();
-        let mut last_lon = point.lon();
+    ResponsePart { count, data }
+}
 
-        result.push(ResponsePoint {
-            lat: point.lat(),
-            lon: point.lon(),
-        });
+// Skips points equal to previous one. In inspection mode all points returned with their
+// original index and duplicate points are marked instead of being dropped. Duplicates
+// detected before coordinates rounded to output precision.
+fn collect_points(points: &[Point], options: OutputOptions) -> Vec<ResponsePoint> {
+    let round = |value: f64| match options.precision {
+        Some(precision) => {
+            let factor = 10f64.powi(precision as i32);
 
-        for point in it {
-            let delta = (point.lat() - last_lat).abs() + (point.lon() - last_lon).abs();
+            (value * factor).round() / factor
+        }
+        None => value,
+    };
+    let mut last_point: Option<&Point> = None;
+    let mut result = Vec::new();
 
-            if delta > EPSILON {
-                last_lat = point.lat();
-                last_lon = point.lon();
+    for (index, point) in points.iter().enumerate() {
+        let duplicate = match last_point {
+            Some(last_point) => {
+                let delta =
+                    (point.lat() - last_point.lat()).abs() + (point.lon() - last_point.lon()).abs();
 
-                result.push(ResponsePoint {
-                    lat: point.lat(),
-                    lon: point.lon(),
-                });
+                delta <= EPSILON
             }
+            None => false,
+        };
+
+        if options.inspect {
+            result.push(ResponsePoint {
+                lat: round(point.lat()),
+                lon: round(point.lon()),
+                index: Some(index),
+                duplicate: Some(duplicate),
+            });
+        } else if !duplicate {
+            result.push(ResponsePoint {
+                lat: round(point.lat()),
+                lon: round(point.lon()),
+                index: None,
+                duplicate: None,
+            });
+        }
+
+        if !duplicate {
+            last_point = Some(point);
         }
     }
 
@@ -123,32 +188,112 @@ fn collect_points(points: &[Point]) -> Vec<ResponsePoint> {
 
 #[derive(Deserialize)]
 struct Request {
-    host: String,
-    port: i16,
-    database: String,
-    role: String,
-    password: String,
-    format: String,
-    ids: Vec<i64>,
+    #[serde(flatten)]
+    query: ObjectQuery,
+    #[serde(default)]
+    inspect: bool,
+    #[serde(default)]
+    validate: bool,
+    #[serde(default)]
+    encoding: GeometryEncoding,
 }
 
-#[serde(tag = "type")]
 #[derive(Serialize)]
-enum ResponseObject {
+#[serde(tag = "type")]
+pub enum ResponseObject {
     MultiLineString {
         id: i64,
         names: Vec<String>,
-        lines: Vec<Vec<ResponsePoint>>,
+        attributes: Attributes,
+        style: StyleProperties,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        issues: Vec<Issue>,
+        #[serde(skip_serializing_if = "GeometryEncoding::is_points")]
+        encoding: GeometryEncoding,
+        lines: Vec<ResponsePart>,
     },
     MultiPolygon {
         id: i64,
         names: Vec<String>,
-        polygons: Vec<Vec<ResponsePoint>>,
+        attributes: Attributes,
+        style: StyleProperties,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        issues: Vec<Issue>,
+        #[serde(skip_serializing_if = "GeometryEncoding::is_points")]
+        encoding: GeometryEncoding,
+        polygons: Vec<ResponsePart>,
     },
 }
 
+impl ResponseObject {
+    pub fn new(
+        id: i64,
+        data: &NamesGeometry,
+        style: StyleProperties,
+        issues: Vec<Issue>,
+        options: OutputOptions,
+    ) -> ResponseObject {
+        match data.geometry() {
+            Geometry::MultiLine(lines) => ResponseObject::MultiLineString {
+                id,
+                names: data.names().into(),
+                attributes: data.attributes().clone(),
+                style,
+                issues,
+                encoding: options.encoding,
+                lines: collect_lines(lines, options),
+            },
+            Geometry::MultiPolygon(polygons) => ResponseObject::MultiPolygon {
+                id,
+                names: data.names().into(),
+                attributes: data.attributes().clone(),
+                style,
+                issues,
+                encoding: options.encoding,
+                polygons: collect_polygons(polygons, options),
+            },
+        }
+    }
+
+    pub fn points_count(&self) -> usize {
+        let parts = match self {
+            ResponseObject::MultiLineString { lines, .. } => lines,
+            ResponseObject::MultiPolygon { polygons, .. } => polygons,
+        };
+
+        parts.iter().map(|part| part.count).sum()
+    }
+}
+
+// Part of object in requested encoding, number of points kept for metrics.
+pub struct ResponsePart {
+    count: usize,
+    data: PartData,
+}
+
+impl Serialize for ResponsePart {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.data.serialize(serializer)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum PartData {
+    Points(Vec<ResponsePoint>),
+    Flat(Vec<f64>),
+    Encoded(String),
+}
+
 #[derive(Serialize)]
-struct ResponsePoint {
-    lat: f32,
-    lon: f32,
+pub struct ResponsePoint {
+    lat: f64,
+    lon: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    index: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duplicate: Option<bool>,
 }
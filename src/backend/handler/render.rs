@@ -0,0 +1,77 @@
+use crate::backend::handler::loader::ObjectLoader;
+use crate::backend::handler::loader::ObjectQuery;
+use crate::backend::handler::loader::RequestContext;
+use crate::backend::handler::util::file_name;
+use crate::backend::handler::util::handle_download;
+use crate::backend::handler::util::Download;
+use crate::backend::HandlerError;
+use crate::config::ConfigRef;
+use crate::export::ExportObjects;
+use crate::render;
+use crate::render::RenderFormat;
+use crate::render::RenderObject;
+use crate::render::RenderOptions;
+use iron::Handler;
+use iron::IronResult;
+use iron::Request as IronRequest;
+use iron::Response as IronResponse;
+
+pub struct RenderHandler {
+    config: ConfigRef,
+    loader: ObjectLoader,
+}
+
+impl RenderHandler {
+    pub fn new(config: ConfigRef, loader: ObjectLoader) -> RenderHandler {
+        RenderHandler { config, loader }
+    }
+}
+
+impl Handler for RenderHandler {
+    fn handle(&self, request: &mut IronRequest) -> IronResult<IronResponse> {
+        let context = RequestContext::new(request);
+
+        handle_download(request, move |request: Request| {
+            let options = RenderOptions::from_config(
+                self.config.render(),
+                request.width,
+                request.height,
+                request.base_map,
+            )
+            .map_err(|error| HandlerError::new(&format!("{}", error)))?;
+            let (format, objects) = self.loader.load("render", &request.query, &context)?;
+            let objects: ExportObjects = objects.into_iter().collect();
+            let objects = RenderObject::styled(format, &objects);
+            let image = render::render(request.image_format, &objects, &options)
+                .map_err(|error| HandlerError::new(&format!("Render error - {}", error)))?;
+            let file_name = format!(
+                "{}.{}",
+                file_name(request.query.format()),
+                image.extension()
+            );
+            let content_type = image.content_type().to_string();
+
+            Ok(Download::new(
+                &file_name,
+                &content_type,
+                image.into_content(),
+            ))
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(flatten)]
+    query: ObjectQuery,
+    #[serde(default)]
+    image_format: RenderFormat,
+    width: Option<u32>,
+    height: Option<u32>,
+    #[serde(default = "default_base_map")]
+    base_map: bool,
+}
+
+fn default_base_map() -> bool {
+    true
+}
@@ -0,0 +1,72 @@
+use crate::backend::handler::util::handle_empty;
+use crate::backend::handler::util::handle_request;
+use crate::backend::HandlerError;
+use crate::share::ShareRef;
+use crate::share::SharedState;
+use iron::Handler;
+use iron::IronResult;
+use iron::Request as IronRequest;
+use iron::Response as IronResponse;
+use router::Router;
+
+const MAX_SHARED_IDS: usize = 100_000;
+
+pub struct ShareHandler {
+    store: ShareRef,
+}
+
+impl ShareHandler {
+    pub fn new(store: ShareRef) -> ShareHandler {
+        ShareHandler { store }
+    }
+}
+
+impl Handler for ShareHandler {
+    fn handle(&self, request: &mut IronRequest) -> IronResult<IronResponse> {
+        handle_request(request, move |state: SharedState| {
+            if state.objects().is_empty() {
+                return Err(HandlerError::new("Shared state must contain objects"));
+            }
+
+            if state.ids_count() > MAX_SHARED_IDS {
+                return Err(HandlerError::new(&format!(
+                    "Shared state can contain at most {} identifiers",
+                    MAX_SHARED_IDS
+                )));
+            }
+
+            self.store.put(&state).map_err(|error| {
+                error!("Failed to store shared state - {}", error);
+
+                HandlerError::new("Failed to store shared state")
+            })
+        })
+    }
+}
+
+pub struct SharedStateHandler {
+    store: ShareRef,
+}
+
+impl SharedStateHandler {
+    pub fn new(store: ShareRef) -> SharedStateHandler {
+        SharedStateHandler { store }
+    }
+}
+
+impl Handler for SharedStateHandler {
+    fn handle(&self, request: &mut IronRequest) -> IronResult<IronResponse> {
+        let id = request
+            .extensions
+            .get::<Router>()
+            .and_then(|params| params.find("id"))
+            .unwrap_or("")
+            .to_string();
+
+        handle_empty(move || {
+            self.store
+                .get(&id)
+                .ok_or_else(|| HandlerError::new("Shared state not found"))
+        })
+    }
+}
@@ -0,0 +1,141 @@
+use crate::audit::AuditRef;
+use crate::auth;
+use crate::auth::CurrentUser;
+use crate::auth::User;
+use crate::backend::handler::object::OutputOptions;
+use crate::backend::handler::object::ResponseObject;
+use crate::backend::handler::util::handle_request;
+use crate::backend::HandlerError;
+use crate::config::ConfigRef;
+use crate::database;
+use crate::database::SqlColumns;
+use iron::Handler;
+use iron::IronResult;
+use iron::Request as IronRequest;
+use iron::Response as IronResponse;
+use std::time::Duration;
+use std::time::Instant;
+
+pub struct SqlHandler {
+    config: ConfigRef,
+    audit: Option<AuditRef>,
+}
+
+impl SqlHandler {
+    pub fn new(config: ConfigRef, audit: Option<AuditRef>) -> SqlHandler {
+        SqlHandler { config, audit }
+    }
+}
+
+impl Handler for SqlHandler {
+    fn handle(&self, request: &mut IronRequest) -> IronResult<IronResponse> {
+        let user = request.extensions.get::<CurrentUser>().cloned();
+        let client = request.remote_addr;
+
+        handle_request(request, move |request: Request| {
+            let sql = match self.config.sql() {
+                Some(sql) if auth::is_sql_allowed(&self.config, user.as_ref()) => sql,
+                _ => return Err(HandlerError::new("SQL console is not allowed")),
+            };
+
+            if !auth::is_connection_allowed(
+                &self.config,
+                user.as_ref(),
+                &request.host,
+                request.port,
+                &request.database,
+            ) {
+                return Err(HandlerError::new(
+                    "Connection to this database is not allowed",
+                ));
+            }
+
+            let started = Instant::now();
+            let result = database::run_sql(
+                &request.host,
+                request.port,
+                &request.database,
+                &request.role,
+                &request.password,
+                &request.query,
+                Duration::from_secs(sql.timeout()),
+                sql.max_rows(),
+            )
+            .map_err(|error| HandlerError::new(&format!("Database error - {}", error)));
+
+            if let Some(ref audit) = self.audit {
+                audit.write(&AuditRecord {
+                    timestamp: format!("{}", time::now_utc().rfc3339()),
+                    action: "sql",
+                    client: format!("{}", client),
+                    user: user.as_ref().map(User::login),
+                    host: &request.host,
+                    port: request.port,
+                    database: &request.database,
+                    role: &request.role,
+                    query: &request.query,
+                    objects: result.as_ref().map_or(0, |result| result.objects().len()),
+                    duration_ms: started.elapsed().as_millis(),
+                    success: result.is_ok(),
+                    error: result.as_ref().err().map(|error| format!("{}", error)),
+                });
+            }
+
+            let result = result?;
+            let objects = result
+                .objects()
+                .iter()
+                .map(|(id, data)| {
+                    ResponseObject::new(
+                        *id,
+                        data,
+                        Default::default(),
+                        Vec::new(),
+                        OutputOptions::points(None),
+                    )
+                })
+                .collect();
+
+            Ok(Response {
+                objects,
+                columns: result.columns().clone(),
+                truncated: result.truncated(),
+            })
+        })
+    }
+}
+
+#[derive(Deserialize)]
+struct Request {
+    host: String,
+    port: i16,
+    database: String,
+    role: String,
+    password: String,
+    query: String,
+}
+
+#[derive(Serialize)]
+struct Response {
+    objects: Vec<ResponseObject>,
+    columns: SqlColumns,
+    truncated: bool,
+}
+
+#[derive(Serialize)]
+struct AuditRecord<'a> {
+    timestamp: String,
+    action: &'static str,
+    client: String,
+    user: Option<&'a str>,
+    host: &'a str,
+    port: i16,
+    database: &'a str,
+    role: &'a str,
+    query: &'a str,
+    objects: usize,
+    duration_ms: u128,
+    success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
@@ -0,0 +1,124 @@
+use crate::backend::handler::util::handle_empty;
+use crate::config::ConfigRef;
+use crate::config::LayerType;
+use crate::tiles::TileStoreRef;
+use iron::headers::CacheControl;
+use iron::headers::CacheDirective;
+use iron::headers::ContentEncoding;
+use iron::headers::Encoding;
+use iron::mime::Mime;
+use iron::mime::SubLevel;
+use iron::mime::TopLevel;
+use iron::status;
+use iron::Handler;
+use iron::IronResult;
+use iron::Request as IronRequest;
+use iron::Response as IronResponse;
+use router::Router;
+
+const TILE_MAX_AGE: u32 = 86400;
+
+pub struct TileHandler {
+    store: TileStoreRef,
+}
+
+impl TileHandler {
+    pub fn new(store: TileStoreRef) -> TileHandler {
+        TileHandler { store }
+    }
+}
+
+impl Handler for TileHandler {
+    fn handle(&self, request: &mut IronRequest) -> IronResult<IronResponse> {
+        let params = match request.extensions.get::<Router>() {
+            Some(params) => params,
+            None => return Ok(IronResponse::with(status::NotFound)),
+        };
+        let layer = params.find("layer").unwrap_or("");
+        let coordinates = (
+            params.find("z").and_then(|z| z.parse::<u32>().ok()),
+            params.find("x").and_then(|x| x.parse::<u32>().ok()),
+            params
+                .find("y")
+                .and_then(|y| y.split('.').next())
+                .and_then(|y| y.parse::<u32>().ok()),
+        );
+        let (z, x, y) = match coordinates {
+            (Some(z), Some(x), Some(y)) => (z, x, y),
+            _ => return Ok(IronResponse::with((status::BadRequest, "Invalid tile"))),
+        };
+
+        if !self.store.contains(layer) {
+            return Ok(IronResponse::with((status::NotFound, "Unknown layer")));
+        }
+
+        match self.store.get(layer, z, x, y) {
+            Ok(Some(tile)) => {
+                let content_type = tile.content_type().parse::<Mime>().unwrap_or_else(|_| {
+                    Mime(
+                        TopLevel::Application,
+                        SubLevel::Ext("octet-stream".into()),
+                        vec![],
+                    )
+                });
+                let gzipped = tile.is_gzipped();
+                let mut response =
+                    IronResponse::with((status::Ok, content_type, tile.into_content()));
+
+                if gzipped {
+                    response.headers.set(ContentEncoding(vec![Encoding::Gzip]));
+                }
+
+                response
+                    .headers
+                    .set(CacheControl(vec![CacheDirective::MaxAge(TILE_MAX_AGE)]));
+
+                Ok(response)
+            }
+            Ok(None) => Ok(IronResponse::with(status::NotFound)),
+            Err(error) => {
+                error!("Failed to read tile from layer {} - {}", layer, error);
+
+                Ok(IronResponse::with(status::InternalServerError))
+            }
+        }
+    }
+}
+
+pub struct LayerHandler {
+    config: ConfigRef,
+    store: TileStoreRef,
+}
+
+impl LayerHandler {
+    pub fn new(config: ConfigRef, store: TileStoreRef) -> LayerHandler {
+        LayerHandler { config, store }
+    }
+}
+
+impl Handler for LayerHandler {
+    fn handle(&self, _: &mut IronRequest) -> IronResult<IronResponse> {
+        handle_empty(move || {
+            let result: Vec<_> = self
+                .config
+                .layers()
+                .iter()
+                .filter(|(name, _)| self.store.contains(name))
+                .map(|(name, layer)| Layer {
+                    name,
+                    title: layer.title().unwrap_or(name),
+                    layer_type: layer.layer_type(),
+                })
+                .collect();
+
+            Ok(result)
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct Layer<'a> {
+    name: &'a str,
+    title: &'a str,
+    layer_type: LayerType,
+}
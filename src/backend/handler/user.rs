@@ -0,0 +1,22 @@
+use crate::auth::CurrentUser;
+use crate::backend::handler::util::handle_empty;
+use iron::Handler;
+use iron::IronResult;
+use iron::Request as IronRequest;
+use iron::Response as IronResponse;
+
+pub struct UserHandler;
+
+impl UserHandler {
+    pub fn new() -> UserHandler {
+        UserHandler {}
+    }
+}
+
+impl Handler for UserHandler {
+    fn handle(&self, request: &mut IronRequest) -> IronResult<IronResponse> {
+        let user = request.extensions.get::<CurrentUser>().cloned();
+
+        handle_empty(move || Ok(user))
+    }
+}
@@ -3,12 +3,12 @@ use iron::mime::Mime;
 use iron::mime::SubLevel;
 use iron::mime::TopLevel;
 use iron::status;
+use iron::status::Status;
 use iron::IronResult;
 use iron::Request;
 use iron::Response;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use serde_json;
 use std::io::Read;
 
 pub fn handle_empty<Res, F>(callback: F) -> IronResult<Response>
@@ -42,7 +42,95 @@ where
     struct_to_response(&response)
 }
 
+pub struct Download {
+    file_name: Option<String>,
+    content_type: String,
+    content: Vec<u8>,
+}
+
+impl Download {
+    pub fn new(file_name: &str, content_type: &str, content: Vec<u8>) -> Download {
+        Download {
+            file_name: Some(file_name.into()),
+            content_type: content_type.into(),
+            content,
+        }
+    }
+
+    pub fn inline(content_type: &str, content: Vec<u8>) -> Download {
+        Download {
+            file_name: None,
+            content_type: content_type.into(),
+            content,
+        }
+    }
+}
+
+pub fn handle_download<Req, F>(request: &mut Request, callback: F) -> IronResult<Response>
+where
+    Req: DeserializeOwned,
+    F: FnOnce(Req) -> HandlerResult<Download>,
+{
+    let result = match serde_json::from_reader(request.body.by_ref()) {
+        Ok(request) => callback(request),
+        Err(err) => return Ok(Response::with((status::BadRequest, format!("{}", err)))),
+    };
+
+    download_response(result)
+}
+
+pub fn download_response(result: HandlerResult<Download>) -> IronResult<Response> {
+    match result {
+        Ok(download) => {
+            let content_type = download.content_type.parse::<Mime>().unwrap_or_else(|_| {
+                Mime(
+                    TopLevel::Application,
+                    SubLevel::Ext("octet-stream".into()),
+                    vec![],
+                )
+            });
+            let mut response = Response::with((status::Ok, content_type, download.content));
+
+            if let Some(file_name) = download.file_name {
+                response.headers.set_raw(
+                    "Content-Disposition",
+                    vec![format!("attachment; filename=\"{}\"", file_name).into_bytes()],
+                );
+            }
+
+            Ok(response)
+        }
+        Err(err) => struct_to_response(&ErrorResponse::<()>::error(&format!("{}", err))),
+    }
+}
+
+pub fn file_name(format: &str) -> String {
+    let name: String = format
+        .chars()
+        .map(|symbol| {
+            if symbol.is_alphanumeric() || symbol == '-' {
+                symbol
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if name.is_empty() {
+        "objects".into()
+    } else {
+        name
+    }
+}
+
 fn struct_to_response<Res>(value: &Res) -> IronResult<Response>
+where
+    Res: Serialize,
+{
+    json_response(status::Ok, value)
+}
+
+pub fn json_response<Res>(status: Status, value: &Res) -> IronResult<Response>
 where
     Res: Serialize,
 {
@@ -50,7 +138,7 @@ where
         Ok(body) => {
             let content_type = Mime(TopLevel::Application, SubLevel::Json, vec![]);
 
-            Ok(Response::with((status::Ok, content_type, body)))
+            Ok(Response::with((status, content_type, body)))
         }
         Err(_) => Ok(Response::with((status::InternalServerError,))),
     }
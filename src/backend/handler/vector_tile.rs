@@ -0,0 +1,175 @@
+use crate::auth;
+use crate::auth::CurrentUser;
+use crate::backend::handler::loader::ConnectionQuery;
+use crate::backend::handler::loader::ObjectLoader;
+use crate::backend::handler::loader::RequestContext;
+use crate::backend::handler::loader::TileQuery;
+use crate::backend::handler::util::download_response;
+use crate::backend::handler::util::handle_empty;
+use crate::backend::handler::util::Download;
+use crate::backend::HandlerError;
+use crate::backend::HandlerResult;
+use crate::config::ConfigRef;
+use crate::config::ParameterValue;
+use crate::mvt::TileCoordinates;
+use iron::headers::CacheControl;
+use iron::headers::CacheDirective;
+use iron::status;
+use iron::url::percent_encoding::percent_decode;
+use iron::url::Url;
+use iron::Handler;
+use iron::IronResult;
+use iron::Request as IronRequest;
+use iron::Response as IronResponse;
+use router::Router;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+const MVT_CONTENT_TYPE: &str = "application/vnd.mapbox-vector-tile";
+const PASSWORD_HEADER: &str = "X-Database-Password";
+const PARAMETER_PREFIX: &str = "parameter.";
+const VARIABLE_PREFIX: &str = "variable.";
+const TILE_MAX_AGE: u32 = 300;
+
+pub struct VectorTileHandler {
+    loader: ObjectLoader,
+}
+
+impl VectorTileHandler {
+    pub fn new(loader: ObjectLoader) -> VectorTileHandler {
+        VectorTileHandler { loader }
+    }
+}
+
+impl Handler for VectorTileHandler {
+    fn handle(&self, request: &mut IronRequest) -> IronResult<IronResponse> {
+        let context = RequestContext::new(request);
+        let params = request.extensions.get::<Router>();
+        let param = |name| params.and_then(|params| params.find(name)).unwrap_or("");
+        let format = percent_decode(param("format").as_bytes())
+            .decode_utf8_lossy()
+            .to_string();
+        let tile = match (
+            param("z").parse(),
+            param("x").parse(),
+            param("y").trim_end_matches(".mvt").parse(),
+        ) {
+            (Ok(z), Ok(x), Ok(y)) => TileCoordinates::new(z, x, y),
+            _ => None,
+        };
+        let (tile, query) = match (tile, tile_query(request)) {
+            (Some(tile), Ok(query)) => (tile, query),
+            (None, _) => {
+                return Ok(IronResponse::with((
+                    status::BadRequest,
+                    "Invalid tile coordinates",
+                )))
+            }
+            (_, Err(error)) => {
+                return Ok(IronResponse::with((
+                    status::BadRequest,
+                    format!("{}", error),
+                )))
+            }
+        };
+        let result = self
+            .loader
+            .load_tile(&format, tile, &query, &context)
+            .map(|content| Download::inline(MVT_CONTENT_TYPE, content.to_vec()));
+        let cacheable = result.is_ok();
+        let mut response = download_response(result)?;
+
+        if cacheable {
+            response.headers.set(CacheControl(vec![
+                CacheDirective::Private,
+                CacheDirective::MaxAge(TILE_MAX_AGE),
+            ]));
+        }
+
+        Ok(response)
+    }
+}
+
+// Connection, `refresh`, format parameters (`parameter.{name}`) and template variables
+// (`variable.{name}`) taken from query string, password from header to keep it out of URLs.
+fn tile_query(request: &IronRequest) -> HandlerResult<TileQuery> {
+    let url: Url = request.url.clone().into();
+    let mut fields = HashMap::new();
+    let mut parameters = BTreeMap::new();
+    let mut variables = BTreeMap::new();
+
+    for (name, value) in url.query_pairs() {
+        if let Some(name) = name.strip_prefix(PARAMETER_PREFIX) {
+            parameters.insert(name.into(), ParameterValue::Text(value.into_owned()));
+        } else if let Some(name) = name.strip_prefix(VARIABLE_PREFIX) {
+            variables.insert(name.into(), value.into_owned());
+        } else {
+            fields.insert(name.into_owned(), value.into_owned());
+        }
+    }
+
+    let field = |name: &str| {
+        fields
+            .get(name)
+            .map(String::as_str)
+            .ok_or_else(|| HandlerError::new(&format!("Request must contain {}", name)))
+    };
+    let port = field("port")?
+        .parse()
+        .map_err(|_| HandlerError::new("Request must contain valid port"))?;
+    let password = request
+        .headers
+        .get_raw(PASSWORD_HEADER)
+        .and_then(|values| values.first())
+        .map(|value| String::from_utf8_lossy(value).into_owned())
+        .unwrap_or_default();
+    let connection = ConnectionQuery::new(
+        field("host")?,
+        port,
+        field("database")?,
+        field("role")?,
+        &password,
+    );
+    let refresh = fields.get("refresh").map(String::as_str) == Some("true");
+
+    Ok(TileQuery::new(connection, parameters, variables, refresh))
+}
+
+pub struct TileFormatsHandler {
+    config: ConfigRef,
+}
+
+impl TileFormatsHandler {
+    pub fn new(config: ConfigRef) -> TileFormatsHandler {
+        TileFormatsHandler { config }
+    }
+}
+
+impl Handler for TileFormatsHandler {
+    fn handle(&self, request: &mut IronRequest) -> IronResult<IronResponse> {
+        let user = request.extensions.get::<CurrentUser>().cloned();
+
+        handle_empty(move || {
+            let result: Vec<_> = self
+                .config
+                .formats()
+                .iter()
+                .filter(|(name, format)| {
+                    format.tile_query().is_some() && auth::is_format_allowed(user.as_ref(), name)
+                })
+                .map(|(name, format)| TileFormat {
+                    format: name,
+                    min_zoom: format.tile_min_zoom(),
+                })
+                .collect();
+
+            Ok(result)
+        })
+    }
+}
+
+#[derive(Serialize)]
+struct TileFormat<'a> {
+    format: &'a str,
+    min_zoom: u32,
+}
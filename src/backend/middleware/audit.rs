@@ -0,0 +1,96 @@
+use super::take_body;
+use crate::audit::AuditRef;
+use crate::auth::CurrentUser;
+use crate::auth::User;
+use iron::status;
+use iron::status::Status;
+use iron::AfterMiddleware;
+use iron::IronError;
+use iron::IronResult;
+use iron::Request;
+use iron::Response;
+use iron::Set;
+
+/// Writes audit records of requests rejected before handlers write them: requests without
+/// valid credentials and requests with body or query which can't be parsed.
+pub struct AuditMiddleware {
+    audit: AuditRef,
+}
+
+impl AuditMiddleware {
+    pub fn new(audit: AuditRef) -> AuditMiddleware {
+        AuditMiddleware { audit }
+    }
+
+    fn write(&self, request: &Request, status: Status, error: &str) {
+        let action = match audited_action(request) {
+            Some(action) => action,
+            None => return,
+        };
+
+        self.audit.write(&RejectedRecord {
+            timestamp: format!("{}", time::now_utc().rfc3339()),
+            action,
+            client: format!("{}", request.remote_addr),
+            user: request.extensions.get::<CurrentUser>().map(User::login),
+            status: status.to_u16(),
+            success: false,
+            error,
+        });
+    }
+}
+
+impl AfterMiddleware for AuditMiddleware {
+    fn after(&self, request: &mut Request, mut response: Response) -> IronResult<Response> {
+        if response.status != Some(status::BadRequest) {
+            return Ok(response);
+        }
+
+        match take_body(&mut response) {
+            Ok(Some(body)) => {
+                self.write(request, status::BadRequest, &String::from_utf8_lossy(&body));
+
+                Ok(response.set(body))
+            }
+            _ => {
+                self.write(request, status::BadRequest, "Bad request");
+
+                Ok(response)
+            }
+        }
+    }
+
+    fn catch(&self, request: &mut Request, error: IronError) -> IronResult<Response> {
+        if let Some(status) = error.response.status {
+            self.write(request, status, &format!("{}", error.error));
+        }
+
+        Err(error)
+    }
+}
+
+// Actions written to audit log by handlers, path is relative to API mount point
+fn audited_action(request: &Request) -> Option<&'static str> {
+    let path = request.url.path();
+
+    match path.first().copied() {
+        Some("object") => Some("object"),
+        Some("export") => Some("export"),
+        Some("render") => Some("render"),
+        Some("diff") => Some("diff"),
+        Some("sql") => Some("sql"),
+        Some("tiles") if path.len() > 1 => Some("tile"),
+        _ => None,
+    }
+}
+
+#[derive(Serialize)]
+struct RejectedRecord<'a> {
+    timestamp: String,
+    action: &'static str,
+    client: String,
+    user: Option<&'a str>,
+    status: u16,
+    success: bool,
+    error: &'a str,
+}
@@ -0,0 +1,153 @@
+use super::take_body;
+use brotli::enc::BrotliEncoderParams;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use iron::headers::AcceptEncoding;
+use iron::headers::ContentEncoding;
+use iron::headers::ContentType;
+use iron::headers::Encoding;
+use iron::mime::Mime;
+use iron::mime::SubLevel;
+use iron::mime::TopLevel;
+use iron::status;
+use iron::AfterMiddleware;
+use iron::IronResult;
+use iron::Request;
+use iron::Response;
+use iron::Set;
+use std::io::Result as IoResult;
+use std::io::Write;
+
+// Smaller bodies are not worth compressing
+const MIN_SIZE: usize = 1024;
+
+const BROTLI_QUALITY: i32 = 5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Method {
+    Brotli,
+    Gzip,
+}
+
+pub struct CompressionMiddleware;
+
+impl CompressionMiddleware {
+    pub fn new() -> CompressionMiddleware {
+        CompressionMiddleware {}
+    }
+}
+
+impl AfterMiddleware for CompressionMiddleware {
+    fn after(&self, request: &mut Request, mut response: Response) -> IronResult<Response> {
+        if response.body.is_none()
+            || response.headers.has::<ContentEncoding>()
+            || !is_compressible(&response)
+        {
+            return Ok(response);
+        }
+
+        response
+            .headers
+            .set_raw("Vary", vec![b"Accept-Encoding".to_vec()]);
+
+        let method = match select_method(request) {
+            Some(method) => method,
+            None => return Ok(response),
+        };
+        let body = match take_body(&mut response) {
+            Ok(Some(body)) => body,
+            Ok(None) => return Ok(response),
+            Err(error) => {
+                warn!("Failed to read response body - {}", error);
+                return Ok(Response::with(status::InternalServerError));
+            }
+        };
+
+        if body.len() < MIN_SIZE {
+            return Ok(response.set(body));
+        }
+
+        match compress(&body, method) {
+            Ok(compressed) => {
+                let encoding = match method {
+                    Method::Brotli => Encoding::EncodingExt("br".into()),
+                    Method::Gzip => Encoding::Gzip,
+                };
+                response.headers.set(ContentEncoding(vec![encoding]));
+
+                Ok(response.set(compressed))
+            }
+            Err(error) => {
+                warn!("Failed to compress response - {}", error);
+
+                Ok(response.set(body))
+            }
+        }
+    }
+}
+
+fn is_compressible(response: &Response) -> bool {
+    let Mime(top_level, sub_level, _) = match response.headers.get::<ContentType>() {
+        Some(content_type) => &content_type.0,
+        None => return false,
+    };
+
+    match (top_level, sub_level) {
+        (TopLevel::Text, _) => true,
+        (TopLevel::Application, SubLevel::Json) => true,
+        (TopLevel::Application, SubLevel::Javascript) => true,
+        (TopLevel::Application, SubLevel::Ext(name)) => {
+            name == "xml" || name.ends_with("+xml") || name == "vnd.mapbox-vector-tile"
+        }
+        (TopLevel::Image, SubLevel::Ext(name)) => name == "svg+xml",
+        _ => false,
+    }
+}
+
+// Selects method with the highest quality, brotli preferred when qualities are equal.
+fn select_method(request: &Request) -> Option<Method> {
+    let accept = request.headers.get::<AcceptEncoding>()?;
+    let mut result: Option<(Method, u16)> = None;
+
+    for item in accept.iter() {
+        let method = match item.item {
+            Encoding::Gzip => Method::Gzip,
+            Encoding::EncodingExt(ref name) if name == "br" => Method::Brotli,
+            _ => continue,
+        };
+        let quality = item.quality.0;
+
+        if quality == 0 {
+            continue;
+        }
+
+        result = match result {
+            Some((_, best)) if best > quality => result,
+            Some((Method::Brotli, best)) if best == quality => result,
+            _ => Some((method, quality)),
+        };
+    }
+
+    result.map(|(method, _)| method)
+}
+
+fn compress(body: &[u8], method: Method) -> IoResult<Vec<u8>> {
+    match method {
+        Method::Brotli => {
+            let mut result = Vec::new();
+            let params = BrotliEncoderParams {
+                quality: BROTLI_QUALITY,
+                ..BrotliEncoderParams::default()
+            };
+            brotli::BrotliCompress(&mut &body[..], &mut result, &params)?;
+
+            Ok(result)
+        }
+        Method::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+            encoder.write_all(body)?;
+
+            encoder.finish()
+        }
+    }
+}
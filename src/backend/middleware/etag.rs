@@ -0,0 +1,78 @@
+use super::take_body;
+use iron::headers::CacheControl;
+use iron::headers::CacheDirective;
+use iron::headers::ETag;
+use iron::headers::EntityTag;
+use iron::headers::IfNoneMatch;
+use iron::headers::SetCookie;
+use iron::method::Method;
+use iron::status;
+use iron::AfterMiddleware;
+use iron::IronResult;
+use iron::Request;
+use iron::Response;
+use iron::Set;
+use sha2::Digest;
+use sha2::Sha256;
+
+/// Adds weak ETag computed from response body to successful `GET` and `HEAD` responses and
+/// replies with `304 Not Modified` when request `If-None-Match` header contains the same tag.
+/// Tag is weak because the body may be compressed differently for different clients.
+pub struct ETagMiddleware;
+
+impl ETagMiddleware {
+    pub fn new() -> ETagMiddleware {
+        ETagMiddleware {}
+    }
+}
+
+impl AfterMiddleware for ETagMiddleware {
+    fn after(&self, request: &mut Request, mut response: Response) -> IronResult<Response> {
+        // Responses setting cookies are never replaced with empty one
+        if response.status != Some(status::Ok)
+            || response.headers.has::<SetCookie>()
+            || !can_revalidate(request, &response)
+        {
+            return Ok(response);
+        }
+
+        let body = match take_body(&mut response) {
+            Ok(Some(body)) => body,
+            Ok(None) => return Ok(response),
+            Err(error) => {
+                warn!("Failed to read response body - {}", error);
+                return Ok(Response::with(status::InternalServerError));
+            }
+        };
+        let digest = format!("{:x}", Sha256::digest(&body));
+        let tag = EntityTag::weak(digest[..32].into());
+        let matches = match request.headers.get::<IfNoneMatch>() {
+            Some(IfNoneMatch::Any) => true,
+            Some(IfNoneMatch::Items(tags)) => tags.iter().any(|item| item.weak_eq(&tag)),
+            None => false,
+        };
+
+        if matches {
+            let mut not_modified = Response::with(status::NotModified);
+            not_modified.headers.set(ETag(tag));
+
+            return Ok(not_modified);
+        }
+
+        response.headers.set(ETag(tag));
+
+        Ok(response.set(body))
+    }
+}
+
+// Only responses of safe methods can be revalidated, bodies of other responses are not hashed
+fn can_revalidate(request: &Request, response: &Response) -> bool {
+    let no_store = match response.headers.get::<CacheControl>() {
+        Some(CacheControl(directives)) => directives.contains(&CacheDirective::NoStore),
+        None => false,
+    };
+
+    (request.method == Method::Get || request.method == Method::Head)
+        && !no_store
+        && !response.headers.has::<ETag>()
+}
@@ -0,0 +1,23 @@
+mod audit;
+mod compression;
+mod etag;
+
+pub use self::audit::AuditMiddleware;
+pub use self::compression::CompressionMiddleware;
+pub use self::etag::ETagMiddleware;
+
+use iron::Response;
+use std::io::Result as IoResult;
+
+// Writes response body into memory, body must be put back with `set_mut` afterwards.
+fn take_body(response: &mut Response) -> IoResult<Option<Vec<u8>>> {
+    match response.body.take() {
+        Some(mut body) => {
+            let mut buffer = Vec::new();
+            body.write_body(&mut buffer)?;
+
+            Ok(Some(buffer))
+        }
+        None => Ok(None),
+    }
+}
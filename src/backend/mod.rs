@@ -1,10 +1,32 @@
 mod error;
 mod handler;
+mod middleware;
 mod starter;
 
 pub use self::error::HandlerError;
 pub use self::error::HandlerResult;
+pub use self::handler::CacheHandler;
+pub use self::handler::DiffHandler;
 pub use self::handler::EmptyHandler;
+pub use self::handler::ExportHandler;
 pub use self::handler::FormatHandler;
+pub use self::handler::HealthHandler;
+pub use self::handler::IntrospectHandler;
+pub use self::handler::LayerHandler;
+pub use self::handler::LoginHandler;
+pub use self::handler::LogoutHandler;
+pub use self::handler::MetricsHandler;
 pub use self::handler::ObjectHandler;
+pub use self::handler::ObjectLoader;
+pub use self::handler::ParametersHandler;
+pub use self::handler::ReadyHandler;
+pub use self::handler::RenderHandler;
+pub use self::handler::ShareHandler;
+pub use self::handler::SharedStateHandler;
+pub use self::handler::SqlHandler;
+pub use self::handler::TileFormatsHandler;
+pub use self::handler::TileHandler;
+pub use self::handler::UserHandler;
+pub use self::handler::VariablesHandler;
+pub use self::handler::VectorTileHandler;
 pub use self::starter::start_backend;
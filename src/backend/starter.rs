@@ -1,28 +1,241 @@
+use super::middleware::AuditMiddleware;
+use super::middleware::CompressionMiddleware;
+use super::middleware::ETagMiddleware;
+use super::CacheHandler;
+use super::DiffHandler;
 use super::EmptyHandler;
+use super::ExportHandler;
 use super::FormatHandler;
+use super::HealthHandler;
+use super::IntrospectHandler;
+use super::LayerHandler;
+use super::LoginHandler;
+use super::LogoutHandler;
+use super::MetricsHandler;
 use super::ObjectHandler;
+use super::ObjectLoader;
+use super::ParametersHandler;
+use super::ReadyHandler;
+use super::RenderHandler;
+use super::ShareHandler;
+use super::SharedStateHandler;
+use super::SqlHandler;
+use super::TileFormatsHandler;
+use super::TileHandler;
+use super::UserHandler;
+use super::VariablesHandler;
+use super::VectorTileHandler;
+use crate::audit::AuditLog;
+use crate::auth::AuthMiddleware;
+use crate::auth::AuthRef;
+use crate::cache::ObjectCache;
 use crate::config::ConfigRef;
+use crate::metrics::Instrumented;
+use crate::metrics::Metrics;
+use crate::share::ShareStore;
+use crate::tiles::TileStore;
+use iron::Chain;
 use iron::Iron;
 use mount::Mount;
 use router::Router;
 use staticfile::Static;
+use std::sync::Arc;
 
-pub fn start_backend(config: ConfigRef, address: &str, port: u16) {
+pub fn start_backend(config: ConfigRef, auth: Option<AuthRef>, address: &str, port: u16) {
+    let audit = config.audit().map(|audit| Arc::new(AuditLog::new(audit)));
+    let cache = config
+        .cache()
+        .map(|cache| Arc::new(ObjectCache::new(cache)));
+    let metrics = Arc::new(Metrics::new());
+    let share = Arc::new(ShareStore::new(config.share()));
+    let tiles = Arc::new(TileStore::new(&config));
+    let loader = ObjectLoader::new(
+        config.clone(),
+        audit.clone(),
+        cache.clone(),
+        metrics.clone(),
+    );
     let mut router = Router::new();
     router
-        .post("/format", FormatHandler::new(config.clone()), "format")
-        .post("/object", ObjectHandler::new(config.clone()), "object")
+        .post(
+            "/format",
+            Instrumented::new(
+                "format",
+                FormatHandler::new(config.clone()),
+                metrics.clone(),
+            ),
+            "format",
+        )
+        .post(
+            "/parameters",
+            Instrumented::new(
+                "parameters",
+                ParametersHandler::new(config.clone()),
+                metrics.clone(),
+            ),
+            "parameters",
+        )
+        .post(
+            "/variables",
+            Instrumented::new(
+                "variables",
+                VariablesHandler::new(config.clone()),
+                metrics.clone(),
+            ),
+            "variables",
+        )
+        .post(
+            "/object",
+            Instrumented::new(
+                "object",
+                ObjectHandler::new(loader.clone(), metrics.clone()),
+                metrics.clone(),
+            ),
+            "object",
+        )
+        .post(
+            "/export",
+            Instrumented::new(
+                "export",
+                ExportHandler::new(loader.clone()),
+                metrics.clone(),
+            ),
+            "export",
+        )
+        .post(
+            "/diff",
+            Instrumented::new("diff", DiffHandler::new(loader.clone()), metrics.clone()),
+            "diff",
+        )
+        .post(
+            "/render",
+            Instrumented::new(
+                "render",
+                RenderHandler::new(config.clone(), loader.clone()),
+                metrics.clone(),
+            ),
+            "render",
+        )
+        .post(
+            "/tiles",
+            Instrumented::new(
+                "tile_formats",
+                TileFormatsHandler::new(config.clone()),
+                metrics.clone(),
+            ),
+            "tile_formats",
+        )
+        .get(
+            "/tiles/:format/:z/:x/:y",
+            Instrumented::new(
+                "vector_tile",
+                VectorTileHandler::new(loader.clone()),
+                metrics.clone(),
+            ),
+            "vector_tile",
+        )
+        .post(
+            "/cache/invalidate",
+            Instrumented::new(
+                "cache",
+                CacheHandler::new(config.clone(), cache, loader),
+                metrics.clone(),
+            ),
+            "cache",
+        )
+        .post(
+            "/introspect",
+            Instrumented::new(
+                "introspect",
+                IntrospectHandler::new(config.clone()),
+                metrics.clone(),
+            ),
+            "introspect",
+        )
+        .post(
+            "/sql",
+            Instrumented::new(
+                "sql",
+                SqlHandler::new(config.clone(), audit.clone()),
+                metrics.clone(),
+            ),
+            "sql",
+        )
+        .post(
+            "/share",
+            Instrumented::new("share", ShareHandler::new(share.clone()), metrics.clone()),
+            "share",
+        )
+        .get(
+            "/share/:id",
+            Instrumented::new("shared", SharedStateHandler::new(share), metrics.clone()),
+            "shared",
+        )
+        .post(
+            "/layers",
+            Instrumented::new(
+                "layers",
+                LayerHandler::new(config.clone(), tiles.clone()),
+                metrics.clone(),
+            ),
+            "layers",
+        )
+        .post(
+            "/user",
+            Instrumented::new("user", UserHandler::new(), metrics.clone()),
+            "user",
+        )
         .get("/", EmptyHandler::new(), "empty");
 
+    let mut chain = Chain::new(router);
+
+    if let Some(ref auth) = auth {
+        chain.link_before(AuthMiddleware::new(auth.clone(), metrics.clone()));
+    }
+
+    if let Some(audit) = audit {
+        chain.link_after(AuditMiddleware::new(audit));
+    }
+
+    let mut auth_router = Router::new();
+    auth_router
+        .post(
+            "/login",
+            Instrumented::new("login", LoginHandler::new(auth.clone()), metrics.clone()),
+            "login",
+        )
+        .post(
+            "/logout",
+            Instrumented::new("logout", LogoutHandler::new(auth), metrics.clone()),
+            "logout",
+        );
+
+    let mut tile_router = Router::new();
+    tile_router.get(
+        "/:layer/:z/:x/:y",
+        Instrumented::new("tile", TileHandler::new(tiles), metrics.clone()),
+        "tile",
+    );
+
     let mut mount = Mount::new();
     mount
-        .mount("/api/v1", router)
+        .mount("/health", HealthHandler::new(config.clone()))
+        .mount("/ready", ReadyHandler::new(config))
+        .mount("/metrics", MetricsHandler::new(metrics))
+        .mount("/api/v1/auth", auth_router)
+        .mount("/api/v1", chain)
+        .mount("/tiles", tile_router)
         .mount("/static", Static::new("public/static"))
         .mount("/", Static::new("public/index.html"));
 
+    let mut server = Chain::new(mount);
+    server
+        .link_after(ETagMiddleware::new())
+        .link_after(CompressionMiddleware::new());
+
     println!("Starting HTTP server on {}:{}", address, port);
 
-    match Iron::new(mount).http((address, port)) {
+    match Iron::new(server).http((address, port)) {
         Ok(_) => {}
         Err(err) => error!("Failed to start HTTP server: {}", err),
     }
@@ -0,0 +1,195 @@
+use super::CacheScope;
+use crate::database::NamesGeometry;
+use std::ffi::OsStr;
+use std::fs;
+use std::fs::File;
+use std::io::ErrorKind;
+use std::io::Result as IoResult;
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::SystemTime;
+
+// Number of writes between passes removing expired and oldest entries
+const EVICTION_INTERVAL: u64 = 100;
+
+pub struct DiskStore {
+    path: PathBuf,
+    max_entries: usize,
+    ttl: Duration,
+    // Counts writes, also makes names of temporary files unique
+    writes: AtomicU64,
+    eviction: Mutex<()>,
+}
+
+impl DiskStore {
+    pub fn new(path: &str, max_entries: usize, ttl: u64) -> DiskStore {
+        DiskStore {
+            path: path.into(),
+            max_entries,
+            ttl: Duration::from_secs(ttl),
+            writes: AtomicU64::new(0),
+            eviction: Mutex::new(()),
+        }
+    }
+
+    // Returns entry with its creation time, entries older than TTL are removed.
+    pub fn get(&self, scope: &CacheScope, id: i64, ttl: Duration) -> Option<DiskEntry> {
+        let path = self.entry_path(scope, id);
+        let file = match File::open(&path) {
+            Ok(file) => file,
+            Err(error) => {
+                if error.kind() != ErrorKind::NotFound {
+                    warn!("Failed to read cache entry {} - {}", path.display(), error);
+                }
+
+                return None;
+            }
+        };
+        let entry: DiskEntry = match serde_json::from_reader(file) {
+            Ok(entry) => entry,
+            Err(error) => {
+                warn!("Failed to parse cache entry {} - {}", path.display(), error);
+                let _ = fs::remove_file(&path);
+
+                return None;
+            }
+        };
+
+        if entry.is_expired(ttl) {
+            let _ = fs::remove_file(&path);
+
+            return None;
+        }
+
+        Some(entry)
+    }
+
+    pub fn put(&self, scope: &CacheScope, id: i64, value: &NamesGeometry, created: SystemTime) {
+        let write = self.writes.fetch_add(1, Ordering::Relaxed);
+
+        if let Err(error) = self.try_put(scope, id, value, created, write) {
+            warn!("Failed to write cache entry - {}", error);
+        }
+
+        // First write after start checks entries left by previous runs
+        if write.is_multiple_of(EVICTION_INTERVAL) {
+            if let Err(error) = self.evict() {
+                warn!("Failed to remove old cache entries - {}", error);
+            }
+        }
+    }
+
+    pub fn remove(&self, scope: &CacheScope, id: i64) {
+        let _ = fs::remove_file(self.entry_path(scope, id));
+    }
+
+    pub fn remove_scope(&self, scope: &CacheScope) {
+        let _ = fs::remove_dir_all(self.path.join(scope.digest()));
+    }
+
+    fn try_put(
+        &self,
+        scope: &CacheScope,
+        id: i64,
+        value: &NamesGeometry,
+        created: SystemTime,
+        write: u64,
+    ) -> IoResult<()> {
+        fs::create_dir_all(self.path.join(scope.digest()))?;
+
+        let path = self.entry_path(scope, id);
+        let temp_path = path.with_extension(format!("{}.{}.tmp", process::id(), write));
+        let created = created
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        // Written to temporary file first so concurrent readers never see partial entry
+        serde_json::to_writer(File::create(&temp_path)?, &DiskEntryRef { created, value })?;
+        fs::rename(temp_path, path)
+    }
+
+    // Removes expired entries, then the oldest entries above the limit. Entry files are never
+    // modified, so modification time is creation time of the entry.
+    fn evict(&self) -> IoResult<()> {
+        let _lock = match self.eviction.try_lock() {
+            Ok(lock) => lock,
+            Err(_) => return Ok(()),
+        };
+        let now = SystemTime::now();
+        let mut files = Vec::new();
+
+        for scope in fs::read_dir(&self.path)? {
+            let scope = scope?;
+
+            if !scope.file_type()?.is_dir() {
+                continue;
+            }
+
+            for entry in fs::read_dir(scope.path())? {
+                let entry = entry?;
+                let path = entry.path();
+
+                if path.extension().and_then(OsStr::to_str) != Some("json") {
+                    continue;
+                }
+
+                let modified = entry.metadata()?.modified()?;
+
+                match now.duration_since(modified) {
+                    Ok(age) if age > self.ttl => {
+                        let _ = fs::remove_file(&path);
+                    }
+                    _ => files.push((modified, path)),
+                }
+            }
+        }
+
+        if files.len() > self.max_entries {
+            files.sort();
+
+            for (_, path) in &files[..files.len() - self.max_entries] {
+                let _ = fs::remove_file(path);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn entry_path(&self, scope: &CacheScope, id: i64) -> PathBuf {
+        self.path.join(scope.digest()).join(format!("{}.json", id))
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DiskEntry {
+    created: u64,
+    value: NamesGeometry,
+}
+
+impl DiskEntry {
+    pub fn created(&self) -> SystemTime {
+        SystemTime::UNIX_EPOCH + Duration::from_secs(self.created)
+    }
+
+    pub fn into_value(self) -> NamesGeometry {
+        self.value
+    }
+
+    fn is_expired(&self, ttl: Duration) -> bool {
+        match SystemTime::now().duration_since(self.created()) {
+            Ok(age) => age > ttl,
+            Err(_) => false,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct DiskEntryRef<'a> {
+    created: u64,
+    value: &'a NamesGeometry,
+}
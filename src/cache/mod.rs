@@ -0,0 +1,234 @@
+mod disk;
+mod scope;
+
+pub use self::scope::CacheScope;
+
+use self::disk::DiskStore;
+use crate::config::CacheConfig;
+use crate::database::NamesGeometry;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::SystemTime;
+
+pub type CacheRef = Arc<ObjectCache>;
+
+type CacheKey = (CacheScope, i64);
+
+// Tile of format identified by zoom, column and row
+type TileKey = (CacheScope, u32, u32, u32);
+
+struct MemoryEntry<V> {
+    value: V,
+    created: SystemTime,
+    used: u64,
+}
+
+struct MemoryStore<K, V> {
+    entries: HashMap<K, MemoryEntry<V>>,
+    // Keys ordered by last use, first key is the least recently used one
+    usage: BTreeMap<u64, K>,
+    counter: u64,
+}
+
+impl<K, V> Default for MemoryStore<K, V> {
+    fn default() -> Self {
+        MemoryStore {
+            entries: HashMap::default(),
+            usage: BTreeMap::default(),
+            counter: 0,
+        }
+    }
+}
+
+impl<K, V> MemoryStore<K, V>
+where
+    K: Clone + Eq + Hash,
+{
+    fn touch(&mut self, key: &K) {
+        self.counter += 1;
+
+        if let Some(entry) = self.entries.get_mut(key) {
+            self.usage.remove(&entry.used);
+            entry.used = self.counter;
+            self.usage.insert(self.counter, key.clone());
+        }
+    }
+
+    fn insert(&mut self, key: K, value: V, created: SystemTime, max: usize) {
+        self.remove(&key);
+        self.counter += 1;
+        self.usage.insert(self.counter, key.clone());
+        self.entries.insert(
+            key,
+            MemoryEntry {
+                value,
+                created,
+                used: self.counter,
+            },
+        );
+
+        while self.entries.len() > max {
+            let oldest = match self.usage.keys().next() {
+                Some(&used) => used,
+                None => break,
+            };
+
+            if let Some(key) = self.usage.remove(&oldest) {
+                self.entries.remove(&key);
+            }
+        }
+    }
+
+    fn remove(&mut self, key: &K) {
+        if let Some(entry) = self.entries.remove(key) {
+            self.usage.remove(&entry.used);
+        }
+    }
+}
+
+pub struct ObjectCache {
+    ttl: Duration,
+    max_entries: usize,
+    memory: Mutex<MemoryStore<CacheKey, NamesGeometry>>,
+    tiles: Mutex<MemoryStore<TileKey, Arc<Vec<u8>>>>,
+    disk: Option<DiskStore>,
+}
+
+impl ObjectCache {
+    pub fn new(config: &CacheConfig) -> ObjectCache {
+        ObjectCache {
+            ttl: Duration::from_secs(config.ttl()),
+            max_entries: config.max_entries(),
+            memory: Mutex::new(MemoryStore::default()),
+            tiles: Mutex::new(MemoryStore::default()),
+            disk: config
+                .path()
+                .map(|path| DiskStore::new(path, config.max_disk_entries(), config.ttl())),
+        }
+    }
+
+    pub fn get(&self, scope: &CacheScope, id: i64) -> Option<NamesGeometry> {
+        let key = (scope.clone(), id);
+        let mut memory = self.memory.lock().unwrap();
+
+        let expired = match memory.entries.get(&key) {
+            Some(entry) => self.is_expired(entry.created),
+            None => false,
+        };
+
+        if expired {
+            memory.remove(&key);
+        } else if memory.entries.contains_key(&key) {
+            memory.touch(&key);
+
+            return memory.entries.get(&key).map(|entry| entry.value.clone());
+        }
+
+        let entry = self.disk.as_ref()?.get(scope, id, self.ttl)?;
+        let created = entry.created();
+        let value = entry.into_value();
+        memory.insert(key, value.clone(), created, self.max_entries);
+
+        Some(value)
+    }
+
+    pub fn put(&self, scope: &CacheScope, id: i64, value: &NamesGeometry) {
+        let created = SystemTime::now();
+
+        if let Some(ref disk) = self.disk {
+            disk.put(scope, id, value, created);
+        }
+
+        self.memory.lock().unwrap().insert(
+            (scope.clone(), id),
+            value.clone(),
+            created,
+            self.max_entries,
+        );
+    }
+
+    pub fn get_tile(&self, scope: &CacheScope, z: u32, x: u32, y: u32) -> Option<Arc<Vec<u8>>> {
+        let key = (scope.clone(), z, x, y);
+        let mut tiles = self.tiles.lock().unwrap();
+
+        let expired = match tiles.entries.get(&key) {
+            Some(entry) => self.is_expired(entry.created),
+            None => return None,
+        };
+
+        if expired {
+            tiles.remove(&key);
+
+            return None;
+        }
+
+        tiles.touch(&key);
+        tiles.entries.get(&key).map(|entry| entry.value.clone())
+    }
+
+    pub fn put_tile(&self, scope: &CacheScope, z: u32, x: u32, y: u32, tile: Arc<Vec<u8>>) {
+        self.tiles.lock().unwrap().insert(
+            (scope.clone(), z, x, y),
+            tile,
+            SystemTime::now(),
+            self.max_entries,
+        );
+    }
+
+    /// Removes given objects from cache, all objects of the scope removed if no ids given.
+    /// Tiles of the scope always removed, because tiles containing objects are unknown.
+    pub fn invalidate(&self, scope: &CacheScope, ids: Option<&[i64]>) {
+        let mut tiles = self.tiles.lock().unwrap();
+        let tile_keys: Vec<TileKey> = tiles
+            .entries
+            .keys()
+            .filter(|(tile_scope, ..)| tile_scope == scope)
+            .cloned()
+            .collect();
+
+        for key in tile_keys {
+            tiles.remove(&key);
+        }
+
+        let mut memory = self.memory.lock().unwrap();
+
+        match ids {
+            Some(ids) => {
+                for &id in ids {
+                    memory.remove(&(scope.clone(), id));
+
+                    if let Some(ref disk) = self.disk {
+                        disk.remove(scope, id);
+                    }
+                }
+            }
+            None => {
+                let keys: Vec<CacheKey> = memory
+                    .entries
+                    .keys()
+                    .filter(|(entry_scope, _)| entry_scope == scope)
+                    .cloned()
+                    .collect();
+
+                for key in keys {
+                    memory.remove(&key);
+                }
+
+                if let Some(ref disk) = self.disk {
+                    disk.remove_scope(scope);
+                }
+            }
+        }
+    }
+
+    fn is_expired(&self, created: SystemTime) -> bool {
+        match SystemTime::now().duration_since(created) {
+            Ok(age) => age > self.ttl,
+            Err(_) => false,
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use sha2::Digest;
+use sha2::Sha256;
+use std::fmt::Display;
+use std::fmt::Error as FmtError;
+use std::fmt::Formatter;
+
+/// Connection and format objects cached for. Role is part of the scope because different roles
+/// may see different rows of the same database, parameters because format queries return
+/// different objects for different parameter values.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CacheScope {
+    host: String,
+    port: i16,
+    database: String,
+    role: String,
+    format: String,
+    parameters: String,
+}
+
+impl CacheScope {
+    pub fn new(
+        host: &str,
+        port: i16,
+        database: &str,
+        role: &str,
+        format: &str,
+        parameters: &str,
+    ) -> CacheScope {
+        CacheScope {
+            host: host.into(),
+            port,
+            database: database.into(),
+            role: role.into(),
+            format: format.into(),
+            parameters: parameters.into(),
+        }
+    }
+
+    pub fn digest(&self) -> String {
+        format!("{:x}", Sha256::digest(self.to_string().as_bytes()))
+    }
+}
+
+impl Display for CacheScope {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+        write!(
+            f,
+            "{}@{}:{}/{}#{}?{}",
+            self.role, self.host, self.port, self.database, self.format, self.parameters
+        )
+    }
+}
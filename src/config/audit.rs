@@ -0,0 +1,30 @@
+#[derive(Debug, Deserialize)]
+pub struct AuditConfig {
+    path: String,
+    #[serde(default = "default_max_size")]
+    max_size: u64,
+    #[serde(default = "default_max_files")]
+    max_files: usize,
+}
+
+impl AuditConfig {
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn max_size(&self) -> u64 {
+        self.max_size
+    }
+
+    pub fn max_files(&self) -> usize {
+        self.max_files
+    }
+}
+
+fn default_max_size() -> u64 {
+    10 * 1024 * 1024
+}
+
+fn default_max_files() -> usize {
+    5
+}
@@ -0,0 +1,50 @@
+use std::collections::BTreeMap;
+
+#[derive(Debug, Deserialize)]
+pub struct AuthConfig {
+    provider: AuthProvider,
+    #[serde(default = "default_session_timeout")]
+    session_timeout: u64,
+    #[serde(default)]
+    roles: BTreeMap<String, RoleConfig>,
+}
+
+impl AuthConfig {
+    pub fn provider(&self) -> &AuthProvider {
+        &self.provider
+    }
+
+    pub fn session_timeout(&self) -> u64 {
+        self.session_timeout
+    }
+
+    pub fn roles(&self) -> &BTreeMap<String, RoleConfig> {
+        &self.roles
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type")]
+pub enum AuthProvider {
+    UsersFile { path: String },
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct RoleConfig {
+    formats: Option<Vec<String>>,
+    connections: Option<Vec<String>>,
+}
+
+impl RoleConfig {
+    pub fn formats(&self) -> Option<&[String]> {
+        self.formats.as_deref()
+    }
+
+    pub fn connections(&self) -> Option<&[String]> {
+        self.connections.as_deref()
+    }
+}
+
+fn default_session_timeout() -> u64 {
+    8 * 60 * 60
+}
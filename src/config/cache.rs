@@ -0,0 +1,40 @@
+#[derive(Debug, Deserialize)]
+pub struct CacheConfig {
+    #[serde(default = "default_ttl")]
+    ttl: u64,
+    #[serde(default = "default_max_entries")]
+    max_entries: usize,
+    path: Option<String>,
+    #[serde(default = "default_max_disk_entries")]
+    max_disk_entries: usize,
+}
+
+impl CacheConfig {
+    pub fn ttl(&self) -> u64 {
+        self.ttl
+    }
+
+    pub fn max_entries(&self) -> usize {
+        self.max_entries
+    }
+
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    pub fn max_disk_entries(&self) -> usize {
+        self.max_disk_entries
+    }
+}
+
+fn default_ttl() -> u64 {
+    60 * 60
+}
+
+fn default_max_entries() -> usize {
+    10000
+}
+
+fn default_max_disk_entries() -> usize {
+    100000
+}
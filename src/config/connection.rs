@@ -0,0 +1,48 @@
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConnectionConfig {
+    host: String,
+    #[serde(default = "default_port")]
+    port: i16,
+    database: String,
+    role: Option<String>,
+    #[serde(default)]
+    password: String,
+    #[serde(default)]
+    variables: BTreeMap<String, String>,
+}
+
+impl ConnectionConfig {
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    pub fn port(&self) -> i16 {
+        self.port
+    }
+
+    pub fn database(&self) -> &str {
+        &self.database
+    }
+
+    pub fn role(&self) -> Option<&str> {
+        self.role.as_deref()
+    }
+
+    pub fn password(&self) -> &str {
+        &self.password
+    }
+
+    pub fn variables(&self) -> &BTreeMap<String, String> {
+        &self.variables
+    }
+
+    pub fn matches(&self, host: &str, port: i16, database: &str) -> bool {
+        self.host == host && self.port == port && self.database == database
+    }
+}
+
+fn default_port() -> i16 {
+    5432
+}
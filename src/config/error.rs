@@ -30,6 +30,14 @@ impl ConfigError {
             message: format!("{}", error),
         }
     }
+
+    pub fn invalid(message: &str) -> ConfigError {
+        warn!("Invalid configuration - {}", message);
+
+        ConfigError {
+            message: message.into(),
+        }
+    }
 }
 
 impl Error for ConfigError {}
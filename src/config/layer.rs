@@ -0,0 +1,39 @@
+#[derive(Debug, Deserialize)]
+pub struct LayerConfig {
+    title: Option<String>,
+    path: String,
+    #[serde(default)]
+    layer_type: LayerType,
+    #[serde(default = "default_extension")]
+    extension: String,
+}
+
+impl LayerConfig {
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    pub fn layer_type(&self) -> LayerType {
+        self.layer_type
+    }
+
+    pub fn extension(&self) -> &str {
+        &self.extension
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LayerType {
+    #[default]
+    Raster,
+    Vector,
+}
+
+fn default_extension() -> String {
+    "png".into()
+}
@@ -1,9 +1,43 @@
+mod audit;
+mod auth;
+mod cache;
+mod connection;
 mod error;
+mod layer;
+mod parameter;
+mod render;
+mod share;
+mod sql;
+mod statement;
+mod style;
+mod variable;
 
+pub use self::audit::AuditConfig;
+pub use self::auth::AuthConfig;
+pub use self::auth::AuthProvider;
+pub use self::auth::RoleConfig;
+pub use self::cache::CacheConfig;
+pub use self::connection::ConnectionConfig;
 pub use self::error::ConfigError;
 pub use self::error::ConfigResult;
+pub use self::layer::LayerConfig;
+pub use self::layer::LayerType;
+pub use self::parameter::date_days;
+pub use self::parameter::ParameterConfig;
+pub use self::parameter::ParameterValue;
+pub use self::parameter::QueryParameter;
+pub use self::render::RenderConfig;
+pub use self::share::ShareConfig;
+pub use self::sql::SqlConfig;
+pub use self::style::StyleConfig;
+pub use self::style::StyleProperties;
+pub use self::variable::quote_identifier;
+pub use self::variable::render_template;
+pub use self::variable::variables_key;
+pub use self::variable::VariableConfig;
 
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::fs::File;
 use std::path::Path;
 use std::sync::Arc;
@@ -13,12 +47,99 @@ pub type ConfigRef = Arc<Config>;
 #[derive(Debug, Deserialize)]
 pub struct Config {
     formats: BTreeMap<String, FormatConfig>,
+    #[serde(default)]
+    connections: BTreeMap<String, ConnectionConfig>,
+    auth: Option<AuthConfig>,
+    audit: Option<AuditConfig>,
+    cache: Option<CacheConfig>,
+    #[serde(default)]
+    share: ShareConfig,
+    #[serde(default)]
+    render: RenderConfig,
+    #[serde(default)]
+    layers: BTreeMap<String, LayerConfig>,
+    #[serde(default)]
+    variables: BTreeMap<String, VariableConfig>,
+    sql: Option<SqlConfig>,
 }
 
 impl Config {
     pub fn formats(&self) -> &BTreeMap<String, FormatConfig> {
         &self.formats
     }
+
+    pub fn connections(&self) -> &BTreeMap<String, ConnectionConfig> {
+        &self.connections
+    }
+
+    pub fn auth(&self) -> Option<&AuthConfig> {
+        self.auth.as_ref()
+    }
+
+    pub fn audit(&self) -> Option<&AuditConfig> {
+        self.audit.as_ref()
+    }
+
+    pub fn cache(&self) -> Option<&CacheConfig> {
+        self.cache.as_ref()
+    }
+
+    pub fn share(&self) -> &ShareConfig {
+        &self.share
+    }
+
+    pub fn render(&self) -> &RenderConfig {
+        &self.render
+    }
+
+    pub fn layers(&self) -> &BTreeMap<String, LayerConfig> {
+        &self.layers
+    }
+
+    pub fn variables(&self) -> &BTreeMap<String, VariableConfig> {
+        &self.variables
+    }
+
+    pub fn sql(&self) -> Option<&SqlConfig> {
+        self.sql.as_ref()
+    }
+
+    /// Returns values of variables used by format queries. Value taken from request, from
+    /// configured connection to the same database or default value of the variable.
+    pub fn resolve_variables(
+        &self,
+        format: &FormatConfig,
+        host: &str,
+        port: i16,
+        database: &str,
+        values: &BTreeMap<String, String>,
+    ) -> Result<BTreeMap<String, String>, String> {
+        for (name, value) in values {
+            self.variables
+                .get(name)
+                .ok_or_else(|| format!("Unknown variable {}", name))?
+                .check(name, value)?;
+        }
+
+        let connection = self
+            .connections
+            .values()
+            .find(|connection| connection.matches(host, port, database));
+        let mut result = BTreeMap::new();
+
+        for name in format.variables() {
+            let value = values
+                .get(&name)
+                .or_else(|| connection.and_then(|connection| connection.variables().get(&name)))
+                .map(String::as_str)
+                .or_else(|| self.variables.get(&name).and_then(VariableConfig::default))
+                .ok_or_else(|| format!("Variable {} has no value", name))?;
+
+            result.insert(name, value.to_string());
+        }
+
+        Ok(result)
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -26,6 +147,20 @@ pub struct FormatConfig {
     format_type: FormatType,
     names_query: String,
     geometry_query: String,
+    #[serde(default)]
+    part_order: PartOrder,
+    #[serde(default)]
+    stitch: bool,
+    #[serde(default)]
+    style: StyleConfig,
+    #[serde(default = "default_cache")]
+    cache: bool,
+    precision: Option<u32>,
+    tile_query: Option<String>,
+    #[serde(default)]
+    tile_min_zoom: u32,
+    #[serde(default)]
+    parameters: Vec<ParameterConfig>,
 }
 
 impl FormatConfig {
@@ -40,6 +175,64 @@ impl FormatConfig {
     pub fn geometry_query(&self) -> &str {
         &self.geometry_query
     }
+
+    pub fn part_order(&self) -> PartOrder {
+        self.part_order
+    }
+
+    pub fn stitch(&self) -> bool {
+        self.stitch
+    }
+
+    pub fn style(&self) -> &StyleConfig {
+        &self.style
+    }
+
+    pub fn cache(&self) -> bool {
+        self.cache
+    }
+
+    pub fn precision(&self) -> Option<u32> {
+        self.precision
+    }
+
+    pub fn tile_query(&self) -> Option<&str> {
+        self.tile_query.as_deref()
+    }
+
+    pub fn tile_min_zoom(&self) -> u32 {
+        self.tile_min_zoom
+    }
+
+    pub fn parameters(&self) -> &[ParameterConfig] {
+        &self.parameters
+    }
+
+    pub fn resolve_parameters(
+        &self,
+        values: &BTreeMap<String, ParameterValue>,
+    ) -> Result<Vec<QueryParameter>, String> {
+        parameter::resolve_parameters(&self.parameters, values)
+    }
+
+    pub fn variables(&self) -> BTreeSet<String> {
+        let mut result = variable::template_variables(&self.names_query);
+        result.extend(variable::template_variables(&self.geometry_query));
+
+        if let Some(ref tile_query) = self.tile_query {
+            result.extend(variable::template_variables(tile_query));
+        }
+
+        result
+    }
+
+    pub fn parameters_key(&self, values: &[QueryParameter]) -> String {
+        parameter::parameters_key(&self.parameters, values)
+    }
+}
+
+fn default_cache() -> bool {
+    true
 }
 
 #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone, Copy, Deserialize)]
@@ -49,12 +242,73 @@ pub enum FormatType {
     Wkt,
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Deserialize)]
+pub enum PartOrder {
+    #[default]
+    Appearance,
+    Sequence,
+}
+
 pub fn load<P>(path: P) -> ConfigResult<ConfigRef>
 where
     P: AsRef<Path>,
 {
     let reader = File::open(path).map_err(ConfigError::io_error)?;
-    let config = serde_yaml::from_reader(reader).map_err(ConfigError::yaml_error)?;
+    let config: Config = serde_yaml::from_reader(reader).map_err(ConfigError::yaml_error)?;
+
+    variable::check_variables(config.variables())
+        .map_err(|message| ConfigError::invalid(&message))?;
+
+    for (name, format) in config.formats() {
+        parameter::check_parameters(format.parameters())
+            .map_err(|message| ConfigError::invalid(&format!("Format {} - {}", name, message)))?;
+
+        let queries = [
+            ("names_query", Some(format.names_query())),
+            ("geometry_query", Some(format.geometry_query())),
+            ("tile_query", format.tile_query()),
+        ];
+
+        for (field, query) in queries.iter() {
+            if let Some(query) = query {
+                statement::check_statement(query).map_err(|message| {
+                    ConfigError::invalid(&format!("Format {} - {} - {}", name, field, message))
+                })?;
+            }
+        }
+
+        if let Some(variable) = format
+            .variables()
+            .into_iter()
+            .find(|variable| !config.variables().contains_key(variable))
+        {
+            return Err(ConfigError::invalid(&format!(
+                "Format {} - Unknown variable {}",
+                name, variable
+            )));
+        }
+    }
+
+    if let Some(sql) = config.sql() {
+        if config.auth().is_none() || sql.roles().is_empty() {
+            return Err(ConfigError::invalid(
+                "SQL console requires authentication and at least one role",
+            ));
+        }
+    }
+
+    for (name, connection) in config.connections() {
+        for (variable, value) in connection.variables() {
+            config
+                .variables()
+                .get(variable)
+                .ok_or_else(|| format!("Unknown variable {}", variable))
+                .and_then(|config| config.check(variable, value))
+                .map_err(|message| {
+                    ConfigError::invalid(&format!("Connection {} - {}", name, message))
+                })?;
+        }
+    }
 
     Ok(Arc::new(config))
 }
@@ -0,0 +1,241 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::fmt::Error as FmtError;
+use std::fmt::Formatter;
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ParameterConfig {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    title: Option<String>,
+    #[serde(rename = "type")]
+    parameter_type: ParameterType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default: Option<ParameterValue>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    values: Vec<String>,
+}
+
+impl ParameterConfig {
+    pub fn resolve(&self, value: Option<&ParameterValue>) -> Result<QueryParameter, String> {
+        let value = value
+            .or(self.default.as_ref())
+            .ok_or_else(|| format!("Parameter {} is required", self.name))?;
+
+        match (self.parameter_type, value) {
+            (ParameterType::Text, value) => Ok(QueryParameter::Text(value.to_string())),
+            (ParameterType::Int, ParameterValue::Int(value)) => Ok(QueryParameter::Int(*value)),
+            (ParameterType::Int, ParameterValue::Text(text)) => text
+                .trim()
+                .parse()
+                .map(QueryParameter::Int)
+                .map_err(|_| format!("Parameter {} must be an integer", self.name)),
+            (ParameterType::Date, value) => {
+                let text = value.to_string();
+
+                match date_days(&text) {
+                    Some(_) => Ok(QueryParameter::Date(text)),
+                    None => Err(format!(
+                        "Parameter {} must be a date in YYYY-MM-DD format",
+                        self.name
+                    )),
+                }
+            }
+            (ParameterType::Enum, value) => {
+                let text = value.to_string();
+
+                if self.values.contains(&text) {
+                    Ok(QueryParameter::Text(text))
+                } else {
+                    Err(format!(
+                        "Parameter {} must be one of {}",
+                        self.name,
+                        self.values.join(", ")
+                    ))
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ParameterType {
+    Text,
+    Int,
+    Date,
+    Enum,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum ParameterValue {
+    Int(i64),
+    Text(String),
+}
+
+impl Display for ParameterValue {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+        match *self {
+            ParameterValue::Int(value) => write!(f, "{}", value),
+            ParameterValue::Text(ref value) => write!(f, "{}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryParameter {
+    Text(String),
+    Int(i64),
+    Date(String),
+}
+
+impl Display for QueryParameter {
+    fn fmt(&self, f: &mut Formatter) -> Result<(), FmtError> {
+        match *self {
+            QueryParameter::Text(ref value) | QueryParameter::Date(ref value) => {
+                write!(f, "{}", value)
+            }
+            QueryParameter::Int(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+pub fn resolve_parameters(
+    parameters: &[ParameterConfig],
+    values: &BTreeMap<String, ParameterValue>,
+) -> Result<Vec<QueryParameter>, String> {
+    if let Some(name) = values
+        .keys()
+        .find(|name| !parameters.iter().any(|parameter| parameter.name == **name))
+    {
+        return Err(format!("Unknown parameter {}", name));
+    }
+
+    parameters
+        .iter()
+        .map(|parameter| parameter.resolve(values.get(&parameter.name)))
+        .collect()
+}
+
+pub fn parameters_key(parameters: &[ParameterConfig], values: &[QueryParameter]) -> String {
+    parameters
+        .iter()
+        .zip(values)
+        .map(|(parameter, value)| format!("{}={}", parameter.name, value))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+pub fn check_parameters(parameters: &[ParameterConfig]) -> Result<(), String> {
+    for (index, parameter) in parameters.iter().enumerate() {
+        if parameters[..index]
+            .iter()
+            .any(|other| other.name == parameter.name)
+        {
+            return Err(format!("Parameter {} is declared twice", parameter.name));
+        }
+
+        if parameter.parameter_type == ParameterType::Enum && parameter.values.is_empty() {
+            return Err(format!(
+                "Parameter {} has no allowed values",
+                parameter.name
+            ));
+        }
+
+        if let Some(ref default) = parameter.default {
+            parameter.resolve(Some(default))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns number of days since 2000-01-01 (PostgreSQL date epoch) of `YYYY-MM-DD` date.
+pub fn date_days(text: &str) -> Option<i32> {
+    let bytes = text.as_bytes();
+
+    if bytes.len() != 10
+        || bytes[4] != b'-'
+        || bytes[7] != b'-'
+        || !text
+            .chars()
+            .enumerate()
+            .all(|(index, symbol)| index == 4 || index == 7 || symbol.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let year: i64 = text[0..4].parse().ok()?;
+    let month: i64 = text[5..7].parse().ok()?;
+    let day: i64 = text[8..10].parse().ok()?;
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let month_days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return None,
+    };
+
+    if !(1..=month_days).contains(&day) {
+        return None;
+    }
+
+    // Days from civil date, years start in March to put leap day at the end
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = (if year >= 0 { year } else { year - 399 }) / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let unix_days = era * 146_097 + day_of_era - 719_468;
+
+    Some((unix_days - 10_957) as i32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::date_days;
+
+    #[test]
+    fn counts_days_from_2000() {
+        assert_eq!(date_days("2000-01-01"), Some(0));
+        assert_eq!(date_days("1999-12-31"), Some(-1));
+        assert_eq!(date_days("1999-01-01"), Some(-365));
+        assert_eq!(date_days("2000-12-31"), Some(365));
+        assert_eq!(date_days("2001-01-01"), Some(366));
+        assert_eq!(date_days("2001-12-31"), Some(730));
+        assert_eq!(date_days("1970-01-01"), Some(-10_957));
+    }
+
+    #[test]
+    fn accepts_leap_days() {
+        assert_eq!(date_days("2000-02-29"), Some(59));
+        assert_eq!(date_days("2000-03-01"), Some(60));
+        assert_eq!(date_days("2024-02-29"), Some(8825));
+        assert_eq!(date_days("1996-02-29"), Some(-1402));
+        assert_eq!(date_days("1900-02-29"), None);
+        assert_eq!(date_days("2001-02-29"), None);
+        assert_eq!(date_days("2100-02-29"), None);
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        for text in &[
+            "",
+            "2000-1-01",
+            "2000-01-1",
+            "2000/01/01",
+            "2000-01-01T00",
+            "+200-01-01",
+            "2000-00-10",
+            "2000-13-01",
+            "2000-01-00",
+            "2000-01-32",
+            "2000-04-31",
+            "20x0-01-01",
+        ] {
+            assert_eq!(date_days(text), None, "{}", text);
+        }
+    }
+}
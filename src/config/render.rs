@@ -0,0 +1,51 @@
+#[derive(Debug, Deserialize)]
+pub struct RenderConfig {
+    #[serde(default = "default_width")]
+    width: u32,
+    #[serde(default = "default_height")]
+    height: u32,
+    #[serde(default = "default_max_size")]
+    max_size: u32,
+    tiles: Option<String>,
+}
+
+impl RenderConfig {
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn max_size(&self) -> u32 {
+        self.max_size
+    }
+
+    pub fn tiles(&self) -> Option<&str> {
+        self.tiles.as_deref()
+    }
+}
+
+impl Default for RenderConfig {
+    fn default() -> RenderConfig {
+        RenderConfig {
+            width: default_width(),
+            height: default_height(),
+            max_size: default_max_size(),
+            tiles: None,
+        }
+    }
+}
+
+fn default_width() -> u32 {
+    800
+}
+
+fn default_height() -> u32 {
+    600
+}
+
+fn default_max_size() -> u32 {
+    4096
+}
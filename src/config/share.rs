@@ -0,0 +1,29 @@
+#[derive(Debug, Deserialize)]
+pub struct ShareConfig {
+    #[serde(default = "default_max_entries")]
+    max_entries: usize,
+    path: Option<String>,
+}
+
+impl ShareConfig {
+    pub fn max_entries(&self) -> usize {
+        self.max_entries
+    }
+
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+}
+
+impl Default for ShareConfig {
+    fn default() -> ShareConfig {
+        ShareConfig {
+            max_entries: default_max_entries(),
+            path: None,
+        }
+    }
+}
+
+fn default_max_entries() -> usize {
+    10000
+}
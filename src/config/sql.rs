@@ -0,0 +1,30 @@
+#[derive(Debug, Deserialize)]
+pub struct SqlConfig {
+    roles: Vec<String>,
+    #[serde(default = "default_timeout")]
+    timeout: u64,
+    #[serde(default = "default_max_rows")]
+    max_rows: usize,
+}
+
+impl SqlConfig {
+    pub fn roles(&self) -> &[String] {
+        &self.roles
+    }
+
+    pub fn timeout(&self) -> u64 {
+        self.timeout
+    }
+
+    pub fn max_rows(&self) -> usize {
+        self.max_rows
+    }
+}
+
+fn default_timeout() -> u64 {
+    10
+}
+
+fn default_max_rows() -> usize {
+    1000
+}
@@ -0,0 +1,234 @@
+const READ_KEYWORDS: [&str; 2] = ["select", "with"];
+const WRITE_KEYWORDS: [&str; 4] = ["insert", "update", "delete", "merge"];
+
+/// Checks that query is single `select` or `with` statement without data-modifying `with`
+/// queries, trailing semicolon is allowed. String literals, quoted identifiers and comments
+/// are not checked.
+pub fn check_statement(query: &str) -> Result<(), String> {
+    let code = strip_literals(query);
+    let code = code.trim();
+    let code = code.strip_suffix(';').unwrap_or(code);
+
+    if code.contains(';') {
+        return Err("Query must be single statement".into());
+    }
+
+    if !READ_KEYWORDS.contains(&leading_word(code).as_str()) {
+        return Err("Query must start with select or with".into());
+    }
+
+    // Data-modifying statements of `with` queries are always in parentheses
+    for (index, _) in code.match_indices('(') {
+        let word = leading_word(&code[index + 1..]);
+
+        if WRITE_KEYWORDS.contains(&word.as_str()) {
+            return Err(format!("Query must not contain {} statement", word));
+        }
+    }
+
+    Ok(())
+}
+
+// Whole identifier, so function calls like `max(update_time)` are not taken for keywords
+fn leading_word(code: &str) -> String {
+    code.trim_start()
+        .bytes()
+        .take_while(|byte| is_identifier_byte(*byte))
+        .map(char::from)
+        .collect::<String>()
+        .to_lowercase()
+}
+
+// Replaces string literals, quoted identifiers, dollar-quoted strings and comments with spaces,
+// unterminated literal removes the rest of query.
+fn strip_literals(query: &str) -> String {
+    let bytes = query.as_bytes();
+    let mut result = String::with_capacity(query.len());
+    let mut position = 0;
+    let mut start = 0;
+
+    // Bytes compared to keep slices at char boundaries, delimiters are ASCII
+    while position < bytes.len() {
+        let rest = &bytes[position..];
+        let end = if rest.starts_with(b"--") {
+            find_bytes(bytes, position, b"\n")
+        } else if rest.starts_with(b"/*") {
+            block_comment_end(bytes, position)
+        } else if bytes[position] == b'\'' {
+            let escapes = is_escape_string(bytes, position);
+            quoted_end(bytes, position, b'\'', escapes)
+        } else if bytes[position] == b'"' {
+            quoted_end(bytes, position, b'"', false)
+        } else if let Some(tag) = dollar_tag(bytes, position) {
+            find_bytes(bytes, position + tag.len(), tag).map(|index| index + tag.len())
+        } else {
+            position += 1;
+            continue;
+        };
+
+        result.push_str(&query[start..position]);
+        result.push(' ');
+        position = end.unwrap_or(bytes.len());
+        start = position;
+    }
+
+    result.push_str(&query[start..]);
+    result
+}
+
+// Comments of PostgreSQL can be nested
+fn block_comment_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut position = start;
+
+    while position < bytes.len() {
+        let rest = &bytes[position..];
+
+        if rest.starts_with(b"/*") {
+            depth += 1;
+            position += 2;
+        } else if rest.starts_with(b"*/") {
+            depth -= 1;
+            position += 2;
+
+            if depth == 0 {
+                return Some(position);
+            }
+        } else {
+            position += 1;
+        }
+    }
+
+    None
+}
+
+// Doubled quote is part of the literal, backslash escapes next symbol in `E'...'` strings
+fn quoted_end(bytes: &[u8], start: usize, quote: u8, escapes: bool) -> Option<usize> {
+    let mut position = start + 1;
+
+    while position < bytes.len() {
+        if escapes && bytes[position] == b'\\' {
+            position += 2;
+        } else if bytes[position] == quote {
+            if bytes.get(position + 1) == Some(&quote) {
+                position += 2;
+            } else {
+                return Some(position + 1);
+            }
+        } else {
+            position += 1;
+        }
+    }
+
+    None
+}
+
+fn is_escape_string(bytes: &[u8], quote: usize) -> bool {
+    quote > 0
+        && (bytes[quote - 1] == b'e' || bytes[quote - 1] == b'E')
+        && (quote == 1 || !is_identifier_byte(bytes[quote - 2]))
+}
+
+// Tag of dollar-quoted string, e.g. `$$` or `$body$`. Parameters like `$1` and dollar signs
+// inside identifiers are not tags.
+fn dollar_tag(bytes: &[u8], start: usize) -> Option<&[u8]> {
+    if bytes[start] != b'$'
+        || (start > 0 && is_identifier_byte(bytes[start - 1]))
+        || matches!(bytes.get(start + 1), Some(byte) if byte.is_ascii_digit())
+    {
+        return None;
+    }
+
+    let length = bytes[start + 1..]
+        .iter()
+        .position(|byte| !is_identifier_byte(*byte))?;
+
+    if bytes[start + 1 + length] == b'$' {
+        Some(&bytes[start..start + length + 2])
+    } else {
+        None
+    }
+}
+
+// Returns position of the first occurrence of pattern starting from `start`
+fn find_bytes(bytes: &[u8], start: usize, pattern: &[u8]) -> Option<usize> {
+    bytes[start..]
+        .windows(pattern.len())
+        .position(|window| window == pattern)
+        .map(|index| start + index)
+}
+
+fn is_identifier_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte >= 0x80
+}
+
+#[cfg(test)]
+mod tests {
+    use super::check_statement;
+
+    #[test]
+    fn accepts_select_and_with() {
+        assert!(check_statement("select id, name from road where id = any($1)").is_ok());
+        assert!(check_statement("  WITH ids AS (select 1) select * from ids;  ").is_ok());
+    }
+
+    #[test]
+    fn accepts_identifiers_starting_with_keywords() {
+        assert!(check_statement("select max(update_time) from road").is_ok());
+        assert!(check_statement("select count(delete_flag) from road").is_ok());
+        assert!(check_statement("select bool_or(insert_new) from road").is_ok());
+        assert!(check_statement("select (merge2) from road").is_ok());
+    }
+
+    #[test]
+    fn rejects_other_statements() {
+        assert!(check_statement("update road set name = 'x'").is_err());
+        assert!(check_statement("selectx from road").is_err());
+        assert!(check_statement("").is_err());
+    }
+
+    #[test]
+    fn rejects_multiple_statements() {
+        assert!(check_statement("select 1; select 2").is_err());
+        assert!(check_statement("select 1; drop table road;").is_err());
+    }
+
+    #[test]
+    fn ignores_semicolons_in_literals() {
+        assert!(check_statement("select ';' as a, \"x;y\" from road").is_ok());
+        assert!(check_statement("select 'it''s; fine'").is_ok());
+        assert!(check_statement("select E'\\'; still string' as a").is_ok());
+        assert!(check_statement("select $$; delete$$, $tag$ ; $$ $tag$").is_ok());
+    }
+
+    #[test]
+    fn handles_escape_strings() {
+        assert!(check_statement("select E'\\''; delete from road").is_err());
+        assert!(check_statement("select e'a\\'b' from road").is_ok());
+        assert!(check_statement("select name'x'; delete from road").is_err());
+    }
+
+    #[test]
+    fn ignores_comments() {
+        assert!(check_statement("-- delete from road;\nselect 1").is_ok());
+        assert!(check_statement("/* ; /* nested; */ ; */ select 1").is_ok());
+        assert!(check_statement("select 1 -- trailing; comment").is_ok());
+        assert!(check_statement("/* select */ delete from road").is_err());
+    }
+
+    #[test]
+    fn rejects_data_modifying_with() {
+        assert!(
+            check_statement("with d as (delete from road returning id) select * from d").is_err()
+        );
+        assert!(check_statement("with d as ( INSERT into road values (1)) select 1").is_err());
+        assert!(check_statement("with d as (update road set id = 1) select 1").is_err());
+        assert!(check_statement("with d as (merge into road using x on true) select 1").is_err());
+    }
+
+    #[test]
+    fn keeps_parameters_outside_dollar_quotes() {
+        assert!(check_statement("select $1::bigint[], a$b$ from road").is_ok());
+        assert!(check_statement("select $1; delete from road").is_err());
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct StyleConfig {
+    #[serde(flatten)]
+    properties: StyleProperties,
+    #[serde(default)]
+    rules: Vec<StyleRule>,
+}
+
+impl StyleConfig {
+    /// Returns style for object with given attributes. Every rule matching the attributes
+    /// overrides properties of the base style, rules applied in the order of declaration.
+    pub fn resolve(&self, attributes: &BTreeMap<String, String>) -> StyleProperties {
+        let mut result = self.properties.clone();
+
+        for rule in &self.rules {
+            if rule.matches(attributes) {
+                result.merge(&rule.properties);
+            }
+        }
+
+        result
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StyleRule {
+    attribute: String,
+    values: Vec<String>,
+    #[serde(flatten)]
+    properties: StyleProperties,
+}
+
+impl StyleRule {
+    pub fn matches(&self, attributes: &BTreeMap<String, String>) -> bool {
+        match attributes.get(&self.attribute) {
+            Some(value) => self.values.contains(value),
+            None => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct StyleProperties {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stroke_color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stroke_width: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    fill_color: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    opacity: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    dash: Option<Vec<f32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    label_field: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    arrows: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    endpoints: Option<bool>,
+}
+
+impl StyleProperties {
+    pub fn stroke_color(&self) -> Option<&str> {
+        self.stroke_color.as_deref()
+    }
+
+    pub fn stroke_width(&self) -> Option<f32> {
+        self.stroke_width
+    }
+
+    pub fn fill_color(&self) -> Option<&str> {
+        self.fill_color.as_deref()
+    }
+
+    pub fn opacity(&self) -> Option<f32> {
+        self.opacity
+    }
+
+    pub fn dash(&self) -> Option<&[f32]> {
+        self.dash.as_deref()
+    }
+
+    pub fn label_field(&self) -> Option<&str> {
+        self.label_field.as_deref()
+    }
+
+    pub fn arrows(&self) -> bool {
+        self.arrows.unwrap_or(false)
+    }
+
+    pub fn endpoints(&self) -> bool {
+        self.endpoints.unwrap_or(false)
+    }
+
+    fn merge(&mut self, other: &StyleProperties) {
+        if other.stroke_color.is_some() {
+            self.stroke_color = other.stroke_color.clone();
+        }
+
+        if other.stroke_width.is_some() {
+            self.stroke_width = other.stroke_width;
+        }
+
+        if other.fill_color.is_some() {
+            self.fill_color = other.fill_color.clone();
+        }
+
+        if other.opacity.is_some() {
+            self.opacity = other.opacity;
+        }
+
+        if other.dash.is_some() {
+            self.dash = other.dash.clone();
+        }
+
+        if other.label_field.is_some() {
+            self.label_field = other.label_field.clone();
+        }
+
+        if other.arrows.is_some() {
+            self.arrows = other.arrows;
+        }
+
+        if other.endpoints.is_some() {
+            self.endpoints = other.endpoints;
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+
+/// Template variable substituted into format queries as quoted identifier, e.g. `{{schema}}`.
+/// Only values from the allowlist can be used.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct VariableConfig {
+    values: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default: Option<String>,
+}
+
+impl VariableConfig {
+    pub fn default(&self) -> Option<&str> {
+        self.default.as_deref()
+    }
+
+    pub fn check(&self, name: &str, value: &str) -> Result<(), String> {
+        if self.values.iter().any(|allowed| allowed == value) {
+            Ok(())
+        } else {
+            Err(format!(
+                "Variable {} must be one of {}",
+                name,
+                self.values.join(", ")
+            ))
+        }
+    }
+}
+
+pub fn template_variables(query: &str) -> BTreeSet<String> {
+    let mut result = BTreeSet::new();
+
+    for_each_variable(query, |_, name| {
+        result.insert(name.to_string());
+    });
+
+    result
+}
+
+pub fn render_template(query: &str, values: &BTreeMap<String, String>) -> String {
+    let mut result = String::with_capacity(query.len());
+    let mut position = 0;
+
+    for_each_variable(query, |range, name| {
+        if let Some(value) = values.get(name) {
+            result.push_str(&query[position..range.0]);
+            result.push_str(&quote_identifier(value));
+            position = range.1;
+        }
+    });
+
+    result.push_str(&query[position..]);
+    result
+}
+
+pub fn variables_key(values: &BTreeMap<String, String>) -> String {
+    values
+        .iter()
+        .map(|(name, value)| format!("{{{{{}}}}}={}", name, value))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+pub fn check_variables(variables: &BTreeMap<String, VariableConfig>) -> Result<(), String> {
+    for (name, variable) in variables {
+        if !is_variable_name(name) {
+            return Err(format!("Invalid variable name {}", name));
+        }
+
+        if variable.values.is_empty() {
+            return Err(format!("Variable {} has no allowed values", name));
+        }
+
+        if let Some(ref default) = variable.default {
+            variable.check(name, default)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn quote_identifier(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+fn is_variable_name(name: &str) -> bool {
+    name.starts_with(|symbol: char| symbol.is_ascii_alphabetic() || symbol == '_')
+        && name
+            .chars()
+            .all(|symbol| symbol.is_ascii_alphanumeric() || symbol == '_')
+}
+
+// Calls function with byte range and name of every `{{name}}` in query. Braces around other
+// text (e.g. array literals `'{{1,2},{3,4}}'`) are not variables.
+fn for_each_variable<F>(query: &str, mut function: F)
+where
+    F: FnMut((usize, usize), &str),
+{
+    let mut position = 0;
+
+    while let Some(start) = query[position..].find("{{").map(|index| position + index) {
+        let end = match query[start + 2..].find("}}") {
+            Some(index) => start + 2 + index,
+            None => return,
+        };
+        let name = &query[start + 2..end];
+
+        if is_variable_name(name) {
+            function((start, end + 2), name);
+            position = end + 2;
+        } else {
+            position = start + 1;
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use super::client::connection_url;
+use super::DatabaseResult;
+use postgres::Connection;
+use postgres::TlsMode;
+use std::time::Duration;
+
+pub fn check_connection(
+    host: &str,
+    port: i16,
+    database: &str,
+    role: &str,
+    password: &str,
+    timeout: Duration,
+) -> DatabaseResult<()> {
+    let url = format!(
+        "{}?connect_timeout={}",
+        connection_url(host, port, database, role, password),
+        timeout.as_secs().max(1)
+    );
+    let connection = Connection::connect(url.as_ref(), TlsMode::None)?;
+    connection.execute("select 1", &[])?;
+
+    Ok(())
+}
@@ -1,21 +1,36 @@
+use super::shape;
+use super::shape::Shape;
+use super::stitch;
+use super::value::AttributeValue;
+use super::value::CoordinateValue;
+use super::Attributes;
 use super::DatabaseError;
 use super::DatabaseResult;
 use super::Geometry;
 use super::NamesGeometry;
 use super::Point;
+use super::QueryStats;
+use crate::config::render_template;
 use crate::config::FormatConfig;
 use crate::config::FormatType;
+use crate::config::PartOrder;
+use crate::config::QueryParameter;
+use postgres::rows::Rows;
+use postgres::transaction::Config as TransactionConfig;
+use postgres::transaction::Transaction;
+use postgres::types::FromSql;
+use postgres::types::ToSql;
 use postgres::Connection;
 use postgres::TlsMode;
+use std::collections::BTreeMap;
 use std::collections::HashMap;
-use std::collections::HashSet;
-use wkt::types::Coord as WktCoord;
-use wkt::Geometry as WktGeometry;
-use wkt::Wkt;
+use std::time::Instant;
 
 pub struct DatabaseClient<'a> {
     format: &'a FormatConfig,
     url: String,
+    parameters: Vec<QueryParameter>,
+    variables: BTreeMap<String, String>,
 }
 
 impl<'a> DatabaseClient<'a> {
@@ -29,53 +44,150 @@ impl<'a> DatabaseClient<'a> {
     ) -> DatabaseClient<'a> {
         DatabaseClient {
             format,
-            url: format!(
-                "postgresql://{3}:{4}@{0}:{1}/{2}",
-                host, port, database, role, password
-            ),
+            url: connection_url(host, port, database, role, password),
+            parameters: Vec::new(),
+            variables: BTreeMap::new(),
         }
     }
 
-    pub fn query(&self, ids: &Vec<i64>) -> DatabaseResult<HashMap<i64, NamesGeometry>> {
+    pub fn with_parameters(mut self, parameters: Vec<QueryParameter>) -> DatabaseClient<'a> {
+        self.parameters = parameters;
+        self
+    }
+
+    pub fn with_variables(mut self, variables: BTreeMap<String, String>) -> DatabaseClient<'a> {
+        self.variables = variables;
+        self
+    }
+
+    pub fn query(
+        &self,
+        ids: &Vec<i64>,
+    ) -> DatabaseResult<(HashMap<i64, NamesGeometry>, QueryStats)> {
         if ids.is_empty() {
             return Err(DatabaseError::no_data());
         }
 
-        let connection = Connection::connect(self.url.as_ref(), TlsMode::None)?;
-        let names = self.get_names(&connection, ids)?;
-        let geometry: HashMap<i64, Geometry> = match self.format.format_type() {
-            FormatType::PlainLines => self.get_geometry_lines(&connection, ids)?,
-            FormatType::PlainPolygons => self.get_geometry_polygons(&connection, ids)?,
-            FormatType::Wkt => self.get_geometry_wkt(&connection, ids)?,
+        let mut stats = QueryStats::default();
+        let connection = self.connect(&mut stats)?;
+        let transaction = read_only_transaction(&connection)?;
+        let names = self.get_names(&transaction, ids, &mut stats)?;
+        let geometry = self.get_geometry(
+            &transaction,
+            &render_template(self.format.geometry_query(), &self.variables),
+            &self.params(&[ids]),
+            &mut stats,
+        )?;
+
+        Ok((combine(names, geometry), stats))
+    }
+
+    /// Loads objects returned by tile query of the format, query parameters are west, south,
+    /// east and north bounds of the tile in degrees followed by format parameters.
+    pub fn query_tile(
+        &self,
+        bounds: [f64; 4],
+    ) -> DatabaseResult<(HashMap<i64, NamesGeometry>, QueryStats)> {
+        let query = match self.format.tile_query() {
+            Some(query) => render_template(query, &self.variables),
+            None => return Err(DatabaseError::no_data()),
+        };
+        let mut stats = QueryStats::default();
+        let connection = self.connect(&mut stats)?;
+        let transaction = read_only_transaction(&connection)?;
+        let [west, south, east, north] = bounds;
+        let geometry = self.get_geometry(
+            &transaction,
+            &query,
+            &self.params(&[&west, &south, &east, &north]),
+            &mut stats,
+        )?;
+        let ids: Vec<i64> = geometry.keys().cloned().collect();
+        let names = if ids.is_empty() {
+            HashMap::default()
+        } else {
+            self.get_names(&transaction, &ids, &mut stats)?
         };
-        let mut result = HashMap::default();
 
-        for (id, geometry) in geometry.into_iter() {
-            let names = match names.get(&id).cloned() {
-                Some(names) => names,
-                None => vec![format!("#{}", id)],
-            };
+        Ok((combine(names, geometry), stats))
+    }
 
-            result.insert(id, (names, geometry).into());
-        }
+    fn connect(&self, stats: &mut QueryStats) -> DatabaseResult<Connection> {
+        let started = Instant::now();
+        let connection = Connection::connect(self.url.as_ref(), TlsMode::None)?;
+        stats.set_connect_time(started.elapsed());
 
-        Ok(result)
+        Ok(connection)
+    }
+
+    fn params<'b>(&'b self, leading: &[&'b dyn ToSql]) -> Vec<&'b dyn ToSql> {
+        let mut params = leading.to_vec();
+        params.extend(
+            self.parameters
+                .iter()
+                .map(|parameter| parameter as &dyn ToSql),
+        );
+        params
+    }
+
+    fn get_geometry(
+        &self,
+        transaction: &Transaction,
+        query: &str,
+        params: &[&dyn ToSql],
+        stats: &mut QueryStats,
+    ) -> DatabaseResult<HashMap<i64, Geometry>> {
+        match self.format.format_type() {
+            FormatType::PlainLines => self.get_geometry_lines(transaction, query, params, stats),
+            FormatType::PlainPolygons => {
+                self.get_geometry_polygons(transaction, query, params, stats)
+            }
+            FormatType::Wkt => self.get_geometry_wkt(transaction, query, params, stats),
+        }
     }
 
     fn get_names(
         &self,
-        connection: &Connection,
+        transaction: &Transaction,
         ids: &Vec<i64>,
-    ) -> DatabaseResult<HashMap<i64, Vec<String>>> {
-        let query = self.format.names_query();
-        let rows = connection.query(query, &[ids])?;
+        stats: &mut QueryStats,
+    ) -> DatabaseResult<HashMap<i64, (Vec<String>, Attributes)>> {
+        let query = render_template(self.format.names_query(), &self.variables);
+        let started = Instant::now();
+        let rows = execute(transaction, &query, &self.params(&[ids]))?;
+        stats.add_query_time(started.elapsed());
+        stats.add_names_rows(rows.len());
+        // Columns of other types (e.g. arrays) can't be read as attributes and are skipped
+        let columns: Vec<(usize, String)> = rows
+            .columns()
+            .iter()
+            .enumerate()
+            .skip(2)
+            .filter(|(_, column)| AttributeValue::accepts(column.type_()))
+            .map(|(index, column)| (index, column.name().into()))
+            .collect();
         let mut result = HashMap::default();
 
         for row in rows.into_iter() {
             let id: i64 = row.get(0);
             let name: String = row.get(1);
+            let (names, attributes) = result
+                .entry(id)
+                .or_insert_with(|| (Vec::default(), Attributes::default()));
+
+            names.push(name);
 
-            result.entry(id).or_insert_with(Vec::default).push(name);
+            for (index, column) in &columns {
+                if attributes.contains_key(column) {
+                    continue;
+                }
+
+                let value: Option<AttributeValue> = row.get(*index);
+
+                if let Some(value) = value {
+                    attributes.insert(column.clone(), value.into_string());
+                }
+            }
         }
 
         Ok(result)
@@ -83,81 +195,108 @@ impl<'a> DatabaseClient<'a> {
 
     fn get_geometry_lines(
         &self,
-        connection: &Connection,
-        ids: &Vec<i64>,
+        transaction: &Transaction,
+        query: &str,
+        params: &[&dyn ToSql],
+        stats: &mut QueryStats,
     ) -> DatabaseResult<HashMap<i64, Geometry>> {
-        let query = self.format.geometry_query();
-        let rows = connection.query(query, &[ids])?;
-        let mut object_lines: HashMap<_, HashSet<_>> = HashMap::default();
-        let mut line_coords: HashMap<_, Vec<_>> = HashMap::default();
+        let object_lines = self.get_geometry_parts(transaction, query, params, stats)?;
+        let mut result = HashMap::new();
 
-        for row in rows.into_iter() {
-            let id: i64 = row.get(0);
-            let line_id: i64 = row.get(1);
-            let lat: f32 = row.get(2);
-            let lon: f32 = row.get(3);
-            let point = Point::new(lat, lon);
+        for (id, lines) in object_lines {
+            let lines = if self.format.stitch() {
+                stitch::stitch(lines, false)
+            } else {
+                lines
+            };
 
-            object_lines
-                .entry(id)
-                .or_insert_with(HashSet::default)
-                .insert(line_id);
-            line_coords
-                .entry(line_id)
-                .or_insert_with(Vec::default)
-                .push(point);
+            result.insert(id, Geometry::MultiLine(lines.into()));
         }
 
+        Ok(result)
+    }
+
+    fn get_geometry_polygons(
+        &self,
+        transaction: &Transaction,
+        query: &str,
+        params: &[&dyn ToSql],
+        stats: &mut QueryStats,
+    ) -> DatabaseResult<HashMap<i64, Geometry>> {
+        let object_polygons = self.get_geometry_parts(transaction, query, params, stats)?;
         let mut result = HashMap::new();
 
-        for (id, line_ids) in object_lines {
-            let lines: Vec<Vec<Point>> = line_ids
-                .iter()
-                .map(|line_id| line_coords[&line_id].clone())
-                .collect();
+        for (id, polygons) in object_polygons {
+            let polygons = if self.format.stitch() {
+                stitch::stitch(polygons, true)
+            } else {
+                polygons
+            };
 
-            result.insert(id, Geometry::MultiLine(lines.into()));
+            result.insert(id, Geometry::MultiPolygon(polygons.into()));
         }
 
         Ok(result)
     }
 
-    fn get_geometry_polygons(
+    // Collects points of object parts (lines or polygons). Parts ordered by first appearance
+    // in the result set or by part sequence number in fifth column.
+    fn get_geometry_parts(
         &self,
-        connection: &Connection,
-        ids: &Vec<i64>,
-    ) -> DatabaseResult<HashMap<i64, Geometry>> {
-        let query = self.format.geometry_query();
-        let rows = connection.query(query, &[ids])?;
-        let mut object_polygons: HashMap<_, HashSet<_>> = HashMap::default();
-        let mut polygon_coords: HashMap<_, Vec<_>> = HashMap::default();
+        transaction: &Transaction,
+        query: &str,
+        params: &[&dyn ToSql],
+        stats: &mut QueryStats,
+    ) -> DatabaseResult<HashMap<i64, Vec<Vec<Point>>>> {
+        let started = Instant::now();
+        let rows = execute(transaction, query, params)?;
+        stats.add_query_time(started.elapsed());
+        stats.add_geometry_rows(rows.len());
+        let mut object_parts: HashMap<_, Vec<_>> = HashMap::default();
+        let mut part_coords: HashMap<_, Vec<_>> = HashMap::default();
 
         for row in rows.into_iter() {
             let id: i64 = row.get(0);
-            let polygon_id: i64 = row.get(1);
-            let lat: f32 = row.get(2);
-            let lon: f32 = row.get(3);
-            let point = Point::new(lat, lon);
+            let part_id: i64 = row.get(1);
+            let lat: CoordinateValue = row.get(2);
+            let lon: CoordinateValue = row.get(3);
+            let point = Point::new(lat.into_f64(), lon.into_f64());
+            let sequence: i64 = match self.format.part_order() {
+                PartOrder::Appearance => 0,
+                PartOrder::Sequence => match row.get_opt(4) {
+                    Some(sequence) => sequence?,
+                    None => {
+                        return Err(DatabaseError::unsupported_format(format_args!(
+                            "geometry query has no part sequence column (fifth column)"
+                        )))
+                    }
+                },
+            };
 
-            object_polygons
-                .entry(id)
-                .or_insert_with(HashSet::default)
-                .insert(polygon_id);
-            polygon_coords
-                .entry(polygon_id)
-                .or_insert_with(Vec::default)
+            part_coords
+                .entry((id, part_id))
+                .or_insert_with(|| {
+                    object_parts
+                        .entry(id)
+                        .or_insert_with(Vec::default)
+                        .push((sequence, part_id));
+
+                    Vec::default()
+                })
                 .push(point);
         }
 
         let mut result = HashMap::new();
 
-        for (id, polygon_ids) in object_polygons {
-            let polygons: Vec<Vec<Point>> = polygon_ids
-                .iter()
-                .map(|polygon_id| polygon_coords[&polygon_id].clone())
+        for (id, mut part_ids) in object_parts {
+            part_ids.sort_by_key(|&(sequence, _)| sequence);
+
+            let parts: Vec<Vec<Point>> = part_ids
+                .into_iter()
+                .filter_map(|(_, part_id)| part_coords.remove(&(id, part_id)))
                 .collect();
 
-            result.insert(id, Geometry::MultiPolygon(polygons.into()));
+            result.insert(id, parts);
         }
 
         Ok(result)
@@ -165,54 +304,34 @@ impl<'a> DatabaseClient<'a> {
 
     fn get_geometry_wkt(
         &self,
-        connection: &Connection,
-        ids: &Vec<i64>,
+        transaction: &Transaction,
+        query: &str,
+        params: &[&dyn ToSql],
+        stats: &mut QueryStats,
     ) -> DatabaseResult<HashMap<i64, Geometry>> {
-        let query = self.format.geometry_query();
-        let rows = connection.query(query, &[ids])?;
+        let started = Instant::now();
+        let rows = execute(transaction, query, params)?;
+        stats.add_query_time(started.elapsed());
+        stats.add_geometry_rows(rows.len());
         let mut object_lines: HashMap<_, Vec<_>> = HashMap::default();
         let mut object_polygons: HashMap<_, Vec<_>> = HashMap::default();
 
         for row in rows.into_iter() {
             let id: i64 = row.get(0);
             let geometry_text: String = row.get(1);
-            let wkt = Wkt::from_str(&geometry_text).unwrap();
-
-            for geometry in wkt.items {
-                match geometry {
-                    WktGeometry::LineString(line) => {
-                        object_lines
-                            .entry(id)
-                            .or_insert_with(Vec::default)
-                            .push(wkt_line_to_points(&line.0));
-                    }
-                    WktGeometry::MultiLineString(lines) => {
-                        let object_lines = object_lines.entry(id).or_insert_with(Vec::default);
 
-                        for line in lines.0 {
-                            object_lines.push(wkt_line_to_points(&line.0));
-                        }
-                    }
-                    WktGeometry::Polygon(polygon) => {
-                        let object_polygons =
-                            object_polygons.entry(id).or_insert_with(Vec::default);
-
-                        object_polygons.push(wkt_line_to_points(&polygon.0[0].0));
-                    }
-                    WktGeometry::MultiPolygon(polygons) => {
-                        let object_polygons =
-                            object_polygons.entry(id).or_insert_with(Vec::default);
-
-                        for polygon in &polygons.0 {
-                            object_polygons.push(wkt_line_to_points(&polygon.0[0].0));
-                        }
-                    }
-                    _ => {
-                        return Err(DatabaseError::unsupported_format(format_args!(
-                            "{}",
-                            geometry
-                        )));
-                    }
+            for shape in shape::from_wkt(&geometry_text)? {
+                match shape {
+                    Shape::Line(points) => object_lines
+                        .entry(id)
+                        .or_insert_with(Vec::default)
+                        .push(points),
+                    Shape::Polygon(points) => object_polygons
+                        .entry(id)
+                        .or_insert_with(Vec::default)
+                        .push(points),
+                    // Points are read only from WKB
+                    Shape::Point(_) => (),
                 }
             }
         }
@@ -231,8 +350,43 @@ impl<'a> DatabaseClient<'a> {
     }
 }
 
-fn wkt_line_to_points(line: &Vec<WktCoord>) -> Vec<Point> {
-    line.iter()
-        .map(|coord| Point::new(coord.y as f32, coord.x as f32))
-        .collect()
+// Format queries can't modify data even if role of the connection has write privileges,
+// transaction is rolled back when dropped.
+fn read_only_transaction(connection: &Connection) -> DatabaseResult<Transaction<'_>> {
+    Ok(connection.transaction_with(TransactionConfig::new().read_only(true))?)
+}
+
+// Statements may use only leading parameters, format parameters not referenced by the query
+// are not bound.
+fn execute(transaction: &Transaction, query: &str, params: &[&dyn ToSql]) -> DatabaseResult<Rows> {
+    let statement = transaction.prepare(query)?;
+    let count = statement.param_types().len().min(params.len());
+
+    Ok(statement.query(&params[..count])?)
+}
+
+// Objects without names get their identifier as name.
+fn combine(
+    names: HashMap<i64, (Vec<String>, Attributes)>,
+    geometry: HashMap<i64, Geometry>,
+) -> HashMap<i64, NamesGeometry> {
+    let mut result = HashMap::default();
+
+    for (id, geometry) in geometry.into_iter() {
+        let (names, attributes) = match names.get(&id).cloned() {
+            Some(names_attributes) => names_attributes,
+            None => (vec![format!("#{}", id)], Attributes::default()),
+        };
+
+        result.insert(id, (names, attributes, geometry).into());
+    }
+
+    result
+}
+
+pub fn connection_url(host: &str, port: i16, database: &str, role: &str, password: &str) -> String {
+    format!(
+        "postgresql://{3}:{4}@{0}:{1}/{2}",
+        host, port, database, role, password
+    )
 }
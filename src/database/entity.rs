@@ -1,6 +1,14 @@
-#[derive(Debug, Clone)]
+use std::collections::BTreeMap;
+
+pub type Attributes = BTreeMap<String, String>;
+
+/// Maximal difference of coordinates in degrees for points considered equal.
+pub const EPSILON: f64 = 0.000001;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NamesGeometry {
     names: Vec<String>,
+    attributes: Attributes,
     geometry: Geometry,
 }
 
@@ -9,52 +17,51 @@ impl NamesGeometry {
         &self.names
     }
 
+    pub fn attributes(&self) -> &Attributes {
+        &self.attributes
+    }
+
     pub fn geometry(&self) -> &Geometry {
         &self.geometry
     }
 }
 
-impl From<(Vec<String>, Geometry)> for NamesGeometry {
-    fn from(names_geometry: (Vec<String>, Geometry)) -> Self {
+impl From<(Vec<String>, Attributes, Geometry)> for NamesGeometry {
+    fn from(names_geometry: (Vec<String>, Attributes, Geometry)) -> Self {
         NamesGeometry {
             names: names_geometry.0,
-            geometry: names_geometry.1,
+            attributes: names_geometry.1,
+            geometry: names_geometry.2,
         }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Point {
-    lat: f32,
-    lon: f32,
+    lat: f64,
+    lon: f64,
 }
 
 impl Point {
-    pub fn new(lat: f32, lon: f32) -> Point {
+    pub fn new(lat: f64, lon: f64) -> Point {
         Point { lat, lon }
     }
 
-    pub fn lat(&self) -> f32 {
+    pub fn lat(&self) -> f64 {
         self.lat
     }
 
-    pub fn lon(&self) -> f32 {
+    pub fn lon(&self) -> f64 {
         self.lon
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Line {
     points: Vec<Point>,
 }
 
 impl Line {
-    pub fn new(points: &[Point]) -> Line {
-        Line {
-            points: points.into(),
-        }
-    }
-
     pub fn points(&self) -> &[Point] {
         &self.points
     }
@@ -66,18 +73,12 @@ impl From<Vec<Point>> for Line {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MultiLine {
     lines: Vec<Line>,
 }
 
 impl MultiLine {
-    pub fn new(lines: &[Line]) -> MultiLine {
-        MultiLine {
-            lines: lines.into(),
-        }
-    }
-
     pub fn lines(&self) -> &[Line] {
         &self.lines
     }
@@ -91,18 +92,12 @@ impl From<Vec<Vec<Point>>> for MultiLine {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Polygon {
     points: Vec<Point>,
 }
 
 impl Polygon {
-    pub fn new(points: &[Point]) -> Polygon {
-        Polygon {
-            points: points.into(),
-        }
-    }
-
     pub fn points(&self) -> &[Point] {
         &self.points
     }
@@ -114,18 +109,12 @@ impl From<Vec<Point>> for Polygon {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MultiPolygon {
     polygons: Vec<Polygon>,
 }
 
 impl MultiPolygon {
-    pub fn new(polygons: &[Polygon]) -> MultiPolygon {
-        MultiPolygon {
-            polygons: polygons.into(),
-        }
-    }
-
     pub fn polygons(&self) -> &[Polygon] {
         &self.polygons
     }
@@ -139,7 +128,7 @@ impl From<Vec<Vec<Point>>> for MultiPolygon {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Geometry {
     MultiLine(MultiLine),
     MultiPolygon(MultiPolygon),
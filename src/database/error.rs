@@ -22,6 +22,15 @@ impl DatabaseError {
         DatabaseError::NoData
     }
 
+    pub fn kind(&self) -> &'static str {
+        match *self {
+            DatabaseError::PostgresError { .. } => "PostgresError",
+            DatabaseError::IoError { .. } => "IoError",
+            DatabaseError::NoData => "NoData",
+            DatabaseError::UnsupportedFormat { .. } => "UnsupportedFormat",
+        }
+    }
+
     pub fn unsupported_format(args: Arguments) -> DatabaseError {
         DatabaseError::UnsupportedFormat {
             message: format!("{}", args),
@@ -40,7 +49,7 @@ impl From<PgError> for DatabaseError {
             DatabaseError::PostgresError { message }
         } else if let Some(error) = error.as_io() {
             DatabaseError::PostgresError {
-                message: error.description().into(),
+                message: format!("{}", error),
             }
         } else {
             DatabaseError::PostgresError {
@@ -0,0 +1,132 @@
+use super::client::connection_url;
+use super::DatabaseResult;
+use postgres::Connection;
+use postgres::TlsMode;
+use std::collections::HashMap;
+
+const GEOMETRY_COLUMNS_QUERY: &str = "
+select f_table_schema::text, f_table_name::text, f_geometry_column::text, type::text, srid::int4,
+  false
+from geometry_columns
+union all
+select f_table_schema::text, f_table_name::text, f_geography_column::text, type::text, srid::int4,
+  true
+from geography_columns
+order by 1, 2, 3";
+
+// Single column primary keys only, objects are identified by one bigint value
+const PRIMARY_KEYS_QUERY: &str = "
+select namespace.nspname::text, class.relname::text, attribute.attname::text,
+  format_type(attribute.atttypid, attribute.atttypmod)::text
+from pg_index as key_index
+  inner join pg_class as class on ( class.oid = key_index.indrelid )
+  inner join pg_namespace as namespace on ( namespace.oid = class.relnamespace )
+  inner join pg_attribute as attribute
+    on ( attribute.attrelid = class.oid and attribute.attnum = key_index.indkey[0] )
+where key_index.indisprimary and key_index.indnatts = 1";
+
+const TEXT_COLUMNS_QUERY: &str = "
+select table_schema::text, table_name::text, column_name::text
+from information_schema.columns
+where data_type in ( 'text', 'character varying', 'character' )
+order by table_schema, table_name, ordinal_position";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GeometryTable {
+    schema: String,
+    table: String,
+    column: String,
+    geometry_type: String,
+    srid: i32,
+    geography: bool,
+    primary_key: Option<String>,
+    text_columns: Vec<String>,
+}
+
+impl GeometryTable {
+    pub fn schema(&self) -> &str {
+        &self.schema
+    }
+
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+
+    pub fn column(&self) -> &str {
+        &self.column
+    }
+
+    pub fn geometry_type(&self) -> &str {
+        &self.geometry_type
+    }
+
+    pub fn srid(&self) -> i32 {
+        self.srid
+    }
+
+    pub fn geography(&self) -> bool {
+        self.geography
+    }
+
+    pub fn primary_key(&self) -> Option<&str> {
+        self.primary_key.as_deref()
+    }
+
+    pub fn text_columns(&self) -> &[String] {
+        &self.text_columns
+    }
+}
+
+pub fn load_geometry_tables(
+    host: &str,
+    port: i16,
+    database: &str,
+    role: &str,
+    password: &str,
+) -> DatabaseResult<Vec<GeometryTable>> {
+    let url = connection_url(host, port, database, role, password);
+    let connection = Connection::connect(url.as_ref(), TlsMode::None)?;
+    let mut primary_keys = HashMap::new();
+    let mut text_columns: HashMap<_, Vec<String>> = HashMap::new();
+
+    for row in connection.query(PRIMARY_KEYS_QUERY, &[])?.into_iter() {
+        let key_type: String = row.get(3);
+
+        if ["smallint", "integer", "bigint"].contains(&key_type.as_str()) {
+            let schema: String = row.get(0);
+            let table: String = row.get(1);
+            let column: String = row.get(2);
+
+            primary_keys.insert((schema, table), column);
+        }
+    }
+
+    for row in connection.query(TEXT_COLUMNS_QUERY, &[])?.into_iter() {
+        let schema: String = row.get(0);
+        let table: String = row.get(1);
+
+        text_columns
+            .entry((schema, table))
+            .or_default()
+            .push(row.get(2));
+    }
+
+    let mut result = Vec::new();
+
+    for row in connection.query(GEOMETRY_COLUMNS_QUERY, &[])?.into_iter() {
+        let key = (row.get(0), row.get(1));
+
+        result.push(GeometryTable {
+            primary_key: primary_keys.get(&key).cloned(),
+            text_columns: text_columns.get(&key).cloned().unwrap_or_default(),
+            schema: key.0,
+            table: key.1,
+            column: row.get(2),
+            geometry_type: row.get(3),
+            srid: row.get(4),
+            geography: row.get(5),
+        });
+    }
+
+    Ok(result)
+}
@@ -1,14 +1,27 @@
+mod check;
 mod client;
 mod entity;
 mod error;
+mod introspect;
+mod shape;
+mod sql;
+mod stats;
+mod stitch;
+mod value;
 
+pub use self::check::check_connection;
 pub use self::client::DatabaseClient;
+pub use self::entity::Attributes;
 pub use self::entity::Geometry;
-pub use self::entity::Line;
 pub use self::entity::MultiLine;
 pub use self::entity::MultiPolygon;
 pub use self::entity::NamesGeometry;
 pub use self::entity::Point;
-pub use self::entity::Polygon;
+pub use self::entity::EPSILON;
 pub use self::error::DatabaseError;
 pub use self::error::DatabaseResult;
+pub use self::introspect::load_geometry_tables;
+pub use self::introspect::GeometryTable;
+pub use self::sql::run_sql;
+pub use self::sql::SqlColumns;
+pub use self::stats::QueryStats;
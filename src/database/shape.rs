@@ -0,0 +1,377 @@
+use super::DatabaseError;
+use super::DatabaseResult;
+use super::Point;
+use std::convert::TryInto;
+use wkt::types::Coord as WktCoord;
+use wkt::Geometry as WktGeometry;
+use wkt::Wkt;
+
+const WKB_POINT: u32 = 1;
+const WKB_LINE_STRING: u32 = 2;
+const WKB_POLYGON: u32 = 3;
+const WKB_MULTI_POINT: u32 = 4;
+const WKB_MULTI_LINE_STRING: u32 = 5;
+const WKB_MULTI_POLYGON: u32 = 6;
+const WKB_GEOMETRY_COLLECTION: u32 = 7;
+
+// Nesting of collections, geometry collections can contain other collections
+const WKB_MAX_DEPTH: usize = 32;
+
+// Flags of PostGIS extended WKB, ISO WKB adds 1000, 2000 or 3000 to type instead
+const EWKB_Z: u32 = 0x8000_0000;
+const EWKB_M: u32 = 0x4000_0000;
+const EWKB_SRID: u32 = 0x2000_0000;
+
+pub enum Shape {
+    Point(Point),
+    Line(Vec<Point>),
+    Polygon(Vec<Point>),
+}
+
+pub fn from_wkt(text: &str) -> DatabaseResult<Vec<Shape>> {
+    let wkt = Wkt::from_str(text)
+        .map_err(|error| DatabaseError::unsupported_format(format_args!("{}", error)))?;
+    let mut result = Vec::new();
+
+    for geometry in wkt.items {
+        match geometry {
+            WktGeometry::LineString(line) => result.push(Shape::Line(wkt_line_to_points(&line.0))),
+            WktGeometry::MultiLineString(lines) => {
+                for line in lines.0 {
+                    result.push(Shape::Line(wkt_line_to_points(&line.0)));
+                }
+            }
+            // Only outer ring is shown, empty polygons have no rings
+            WktGeometry::Polygon(polygon) => {
+                if let Some(ring) = polygon.0.first() {
+                    result.push(Shape::Polygon(wkt_line_to_points(&ring.0)));
+                }
+            }
+            WktGeometry::MultiPolygon(polygons) => {
+                for polygon in &polygons.0 {
+                    if let Some(ring) = polygon.0.first() {
+                        result.push(Shape::Polygon(wkt_line_to_points(&ring.0)));
+                    }
+                }
+            }
+            _ => {
+                return Err(DatabaseError::unsupported_format(format_args!(
+                    "{}",
+                    geometry
+                )));
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Reads points, lines and polygons of WKB or PostGIS extended WKB value. Coordinates expected to be
+/// degrees, SRID is ignored.
+pub fn from_wkb(raw: &[u8]) -> DatabaseResult<Vec<Shape>> {
+    let mut reader = WkbReader { raw, position: 0 };
+    let mut result = Vec::new();
+
+    reader
+        .read_geometry(&mut result, 0)
+        .map_err(|message| DatabaseError::unsupported_format(format_args!("{}", message)))?;
+
+    Ok(result)
+}
+
+struct WkbReader<'a> {
+    raw: &'a [u8],
+    position: usize,
+}
+
+impl<'a> WkbReader<'a> {
+    fn read_geometry(&mut self, result: &mut Vec<Shape>, depth: usize) -> Result<(), String> {
+        if depth > WKB_MAX_DEPTH {
+            return Err(format!(
+                "WKB geometry nested deeper than {} levels",
+                WKB_MAX_DEPTH
+            ));
+        }
+
+        let little_endian = match self.read_bytes(1)?[0] {
+            0 => false,
+            1 => true,
+            order => return Err(format!("invalid WKB byte order {}", order)),
+        };
+        let code = self.read_u32(little_endian)?;
+
+        if code & EWKB_SRID != 0 {
+            self.read_u32(little_endian)?;
+        }
+
+        let iso_dimensions = (code & 0xFFFF) / 1000;
+        let has_z = code & EWKB_Z != 0 || iso_dimensions == 1 || iso_dimensions == 3;
+        let has_m = code & EWKB_M != 0 || iso_dimensions == 2 || iso_dimensions == 3;
+        let dimensions = 2 + has_z as usize + has_m as usize;
+
+        match (code & 0xFFFF) % 1000 {
+            WKB_POINT => {
+                let lon = self.read_f64(little_endian)?;
+                let lat = self.read_f64(little_endian)?;
+
+                for _ in 2..dimensions {
+                    self.read_f64(little_endian)?;
+                }
+
+                // Empty point is written with NaN coordinates
+                if !lat.is_nan() && !lon.is_nan() {
+                    result.push(Shape::Point(Point::new(lat, lon)));
+                }
+            }
+            WKB_LINE_STRING => {
+                let points = self.read_points(little_endian, dimensions)?;
+                result.push(Shape::Line(points));
+            }
+            WKB_POLYGON => {
+                let rings = self.read_u32(little_endian)?;
+
+                // Only outer ring is shown, inner rings are skipped
+                for index in 0..rings {
+                    let points = self.read_points(little_endian, dimensions)?;
+
+                    if index == 0 {
+                        result.push(Shape::Polygon(points));
+                    }
+                }
+            }
+            WKB_MULTI_POINT
+            | WKB_MULTI_LINE_STRING
+            | WKB_MULTI_POLYGON
+            | WKB_GEOMETRY_COLLECTION => {
+                let count = self.read_u32(little_endian)?;
+
+                for _ in 0..count {
+                    self.read_geometry(result, depth + 1)?;
+                }
+            }
+            geometry_type => return Err(format!("WKB geometry type {}", geometry_type)),
+        }
+
+        Ok(())
+    }
+
+    fn read_points(
+        &mut self,
+        little_endian: bool,
+        dimensions: usize,
+    ) -> Result<Vec<Point>, String> {
+        let count = self.read_u32(little_endian)? as usize;
+        let mut points = Vec::with_capacity(count.min(self.raw.len() / 16));
+
+        for _ in 0..count {
+            let lon = self.read_f64(little_endian)?;
+            let lat = self.read_f64(little_endian)?;
+
+            for _ in 2..dimensions {
+                self.read_f64(little_endian)?;
+            }
+
+            points.push(Point::new(lat, lon));
+        }
+
+        Ok(points)
+    }
+
+    fn read_u32(&mut self, little_endian: bool) -> Result<u32, String> {
+        let bytes = self.read_bytes(4)?.try_into().unwrap();
+
+        Ok(if little_endian {
+            u32::from_le_bytes(bytes)
+        } else {
+            u32::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_f64(&mut self, little_endian: bool) -> Result<f64, String> {
+        let bytes = self.read_bytes(8)?.try_into().unwrap();
+
+        Ok(if little_endian {
+            f64::from_le_bytes(bytes)
+        } else {
+            f64::from_be_bytes(bytes)
+        })
+    }
+
+    fn read_bytes(&mut self, count: usize) -> Result<&'a [u8], String> {
+        let end = self.position + count;
+
+        if end > self.raw.len() {
+            return Err("unexpected end of WKB value".into());
+        }
+
+        let bytes = &self.raw[self.position..end];
+        self.position = end;
+
+        Ok(bytes)
+    }
+}
+
+fn wkt_line_to_points(line: &[WktCoord]) -> Vec<Point> {
+    line.iter()
+        .map(|coord| Point::new(coord.y, coord.x))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::from_wkb;
+    use super::Shape;
+    use super::EWKB_SRID;
+    use super::EWKB_Z;
+    use super::WKB_MAX_DEPTH;
+
+    // Little endian WKB of geometry type and values written as numbers of points or coordinates
+    struct Wkb(Vec<u8>);
+
+    impl Wkb {
+        fn new(code: u32) -> Wkb {
+            let mut result = Wkb(vec![1]);
+            result.0.extend_from_slice(&code.to_le_bytes());
+
+            result
+        }
+
+        fn count(mut self, value: u32) -> Wkb {
+            self.0.extend_from_slice(&value.to_le_bytes());
+            self
+        }
+
+        fn coordinates(mut self, values: &[f64]) -> Wkb {
+            for value in values {
+                self.0.extend_from_slice(&value.to_le_bytes());
+            }
+
+            self
+        }
+
+        fn geometry(mut self, geometry: Wkb) -> Wkb {
+            self.0.extend(geometry.0);
+            self
+        }
+    }
+
+    fn shapes(raw: &[u8]) -> Vec<(&'static str, Vec<(f64, f64)>)> {
+        let points = |points: &[super::Point]| {
+            points
+                .iter()
+                .map(|point| (point.lat(), point.lon()))
+                .collect()
+        };
+
+        from_wkb(raw)
+            .unwrap()
+            .iter()
+            .map(|shape| match shape {
+                Shape::Point(point) => ("point", points(std::slice::from_ref(point))),
+                Shape::Line(line) => ("line", points(line)),
+                Shape::Polygon(ring) => ("polygon", points(ring)),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn reads_ewkb_with_srid_and_z() {
+        let line = Wkb::new(2 | EWKB_Z | EWKB_SRID)
+            .count(4326)
+            .count(2)
+            .coordinates(&[37.0, 55.0, 100.0, 37.5, 55.5, 120.0]);
+
+        assert_eq!(
+            shapes(&line.0),
+            vec![("line", vec![(55.0, 37.0), (55.5, 37.5)])]
+        );
+    }
+
+    #[test]
+    fn reads_iso_z_and_m() {
+        let z = Wkb::new(1002).count(1).coordinates(&[37.0, 55.0, 100.0]);
+        let m = Wkb::new(2002).count(1).coordinates(&[37.0, 55.0, 1.0]);
+        let zm = Wkb::new(3003)
+            .count(1)
+            .count(1)
+            .coordinates(&[37.0, 55.0, 100.0, 1.0]);
+
+        assert_eq!(shapes(&z.0), vec![("line", vec![(55.0, 37.0)])]);
+        assert_eq!(shapes(&m.0), vec![("line", vec![(55.0, 37.0)])]);
+        assert_eq!(shapes(&zm.0), vec![("polygon", vec![(55.0, 37.0)])]);
+    }
+
+    #[test]
+    fn reads_points_and_skips_empty_points() {
+        let points = Wkb::new(4)
+            .count(3)
+            .geometry(Wkb::new(1).coordinates(&[37.0, 55.0]))
+            .geometry(Wkb::new(1).coordinates(&[f64::NAN, f64::NAN]))
+            .geometry(Wkb::new(1001).coordinates(&[37.5, 55.5, 100.0]));
+
+        assert_eq!(
+            shapes(&points.0),
+            vec![("point", vec![(55.0, 37.0)]), ("point", vec![(55.5, 37.5)])]
+        );
+    }
+
+    #[test]
+    fn reads_only_outer_rings_of_collections() {
+        let ring = &[37.0, 55.0, 37.1, 55.0, 37.1, 55.1, 37.0, 55.0];
+        let collection = Wkb::new(7)
+            .count(2)
+            .geometry(
+                Wkb::new(3)
+                    .count(2)
+                    .count(4)
+                    .coordinates(ring)
+                    .count(4)
+                    .coordinates(ring),
+            )
+            .geometry(
+                Wkb::new(5)
+                    .count(1)
+                    .geometry(Wkb::new(2).count(1).coordinates(&[37.0, 55.0])),
+            );
+
+        let result = shapes(&collection.0);
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0].0, "polygon");
+        assert_eq!(result[0].1.len(), 4);
+        assert_eq!(result[1], ("line", vec![(55.0, 37.0)]));
+    }
+
+    #[test]
+    fn rejects_truncated_input() {
+        let line = Wkb::new(2).count(2).coordinates(&[37.0, 55.0, 37.5, 55.5]);
+
+        assert!(from_wkb(&line.0).is_ok());
+
+        for length in 0..line.0.len() {
+            assert!(from_wkb(&line.0[..length]).is_err());
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_types_and_byte_orders() {
+        assert!(from_wkb(&Wkb::new(17).count(0).0).is_err());
+        assert!(from_wkb(&[2, 0, 0, 0, 2, 0, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn limits_depth_of_collections() {
+        let nested = |depth: usize| {
+            let mut geometry = Wkb::new(7).count(0);
+
+            for _ in 0..depth {
+                geometry = Wkb::new(7).count(1).geometry(geometry);
+            }
+
+            geometry
+        };
+
+        assert!(from_wkb(&nested(WKB_MAX_DEPTH).0).is_ok());
+        assert!(from_wkb(&nested(WKB_MAX_DEPTH + 1).0).is_err());
+    }
+}
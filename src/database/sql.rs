@@ -0,0 +1,351 @@
+use super::client::connection_url;
+use super::shape;
+use super::shape::Shape;
+use super::value::AttributeValue;
+use super::value::CoordinateValue;
+use super::value::IdValue;
+use super::value::WkbValue;
+use super::Attributes;
+use super::DatabaseError;
+use super::DatabaseResult;
+use super::Geometry;
+use super::NamesGeometry;
+use super::Point;
+use postgres::rows::Row;
+use postgres::rows::Rows;
+use postgres::stmt::Column;
+use postgres::transaction::Config as TransactionConfig;
+use postgres::types::FromSql;
+use postgres::types::Type;
+use postgres::Connection;
+use postgres::TlsMode;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const LATITUDE_COLUMNS: [&str; 2] = ["lat", "latitude"];
+const LONGITUDE_COLUMNS: [&str; 4] = ["lon", "lng", "long", "longitude"];
+const WKT_TYPES: [&str; 4] = ["LINESTRING", "POLYGON", "MULTI", "GEOMETRYCOLLECTION"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SqlColumns {
+    id: Option<String>,
+    name: Option<String>,
+    geometry: Vec<String>,
+    geometry_type: SqlGeometryType,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SqlGeometryType {
+    Wkb,
+    Wkt,
+    LatLon,
+}
+
+pub struct SqlResult {
+    objects: Vec<(i64, NamesGeometry)>,
+    columns: SqlColumns,
+    truncated: bool,
+}
+
+impl SqlResult {
+    pub fn objects(&self) -> &[(i64, NamesGeometry)] {
+        &self.objects
+    }
+
+    pub fn columns(&self) -> &SqlColumns {
+        &self.columns
+    }
+
+    pub fn truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+/// Runs query in read only transaction with statement timeout and returns objects of the first
+/// `max_rows` rows. Rows with the same identifier are joined to single object, rows of latitude
+/// and longitude columns become points of object line.
+#[allow(clippy::too_many_arguments)]
+pub fn run_sql(
+    host: &str,
+    port: i16,
+    database: &str,
+    role: &str,
+    password: &str,
+    query: &str,
+    timeout: Duration,
+    max_rows: usize,
+) -> DatabaseResult<SqlResult> {
+    let url = connection_url(host, port, database, role, password);
+    let connection = Connection::connect(url.as_ref(), TlsMode::None)?;
+    let transaction = connection.transaction_with(TransactionConfig::new().read_only(true))?;
+    transaction.batch_execute(&format!(
+        "set local statement_timeout = {}",
+        timeout.as_millis()
+    ))?;
+
+    // Subquery accepts single select statement only, new lines end trailing comments
+    let query = query.trim().trim_end_matches(';');
+    let rows = transaction.query(
+        &format!(
+            "select * from (\n{}\n) as query limit {}",
+            query,
+            max_rows + 1
+        ),
+        &[],
+    )?;
+    transaction.finish()?;
+
+    let layout = Layout::detect(&rows)?;
+    let mut order = Vec::new();
+    let mut objects: HashMap<i64, ObjectParts> = HashMap::new();
+
+    for (index, row) in rows.iter().take(max_rows).enumerate() {
+        let id = match layout.id {
+            Some(column) => match row.get::<_, Option<IdValue>>(column) {
+                Some(id) => id.into_i64(),
+                None => continue,
+            },
+            // Points of result without identifier are one line
+            None if layout.is_lat_lon() => 1,
+            None => index as i64 + 1,
+        };
+        let object = objects.entry(id).or_insert_with(|| {
+            order.push(id);
+
+            ObjectParts::default()
+        });
+
+        layout.read_row(&row, object)?;
+    }
+
+    let columns = layout.columns(rows.columns());
+    let objects = order
+        .into_iter()
+        .filter_map(|id| {
+            let object = objects.remove(&id)?;
+
+            object.into_names_geometry(id).map(|object| (id, object))
+        })
+        .collect();
+
+    Ok(SqlResult {
+        objects,
+        columns,
+        truncated: rows.len() > max_rows,
+    })
+}
+
+#[derive(Default)]
+struct ObjectParts {
+    names: Vec<String>,
+    attributes: Attributes,
+    points: Vec<Point>,
+    lines: Vec<Vec<Point>>,
+    polygons: Vec<Vec<Point>>,
+}
+
+impl ObjectParts {
+    // Polygons shown if object has both polygons and lines, like objects of `Wkt` format, points
+    // are joined to line in order of rows
+    fn into_names_geometry(self, id: i64) -> Option<NamesGeometry> {
+        let geometry = if !self.polygons.is_empty() {
+            Geometry::MultiPolygon(self.polygons.into())
+        } else if !self.lines.is_empty() {
+            Geometry::MultiLine(self.lines.into())
+        } else if !self.points.is_empty() {
+            Geometry::MultiLine(vec![self.points].into())
+        } else {
+            return None;
+        };
+        let names = if self.names.is_empty() {
+            vec![format!("#{}", id)]
+        } else {
+            self.names
+        };
+
+        Some((names, self.attributes, geometry).into())
+    }
+}
+
+#[derive(Clone, Copy)]
+enum GeometryColumns {
+    Wkb(usize),
+    Wkt(usize),
+    LatLon(usize, usize),
+}
+
+// Indices of columns used for object identifier, name, geometry and attributes
+struct Layout {
+    id: Option<usize>,
+    name: Option<usize>,
+    geometry: GeometryColumns,
+    attributes: Vec<usize>,
+}
+
+impl Layout {
+    fn detect(rows: &Rows) -> DatabaseResult<Layout> {
+        let columns = rows.columns();
+        let id =
+            find_column(columns, |name, ty| name == "id" && IdValue::accepts(ty)).or_else(|| {
+                find_column(columns, |name, ty| {
+                    name.ends_with("id") && IdValue::accepts(ty)
+                })
+            });
+        let geometry = match find_column(columns, |_, ty| WkbValue::accepts(ty)) {
+            Some(column) => GeometryColumns::Wkb(column),
+            None => match find_wkt_column(rows) {
+                Some(column) => GeometryColumns::Wkt(column),
+                None => {
+                    let latitude = find_column(columns, |name, ty| {
+                        LATITUDE_COLUMNS.contains(&name) && CoordinateValue::accepts(ty)
+                    });
+                    let longitude = find_column(columns, |name, ty| {
+                        LONGITUDE_COLUMNS.contains(&name) && CoordinateValue::accepts(ty)
+                    });
+
+                    match (latitude, longitude) {
+                        (Some(latitude), Some(longitude)) => {
+                            GeometryColumns::LatLon(latitude, longitude)
+                        }
+                        _ => {
+                            return Err(DatabaseError::unsupported_format(format_args!(
+                                "query result has no WKT, WKB or latitude and longitude columns"
+                            )))
+                        }
+                    }
+                }
+            },
+        };
+        let geometry_columns = match geometry {
+            GeometryColumns::Wkb(column) | GeometryColumns::Wkt(column) => vec![column],
+            GeometryColumns::LatLon(latitude, longitude) => vec![latitude, longitude],
+        };
+        let is_text = |index: usize| {
+            <String as FromSql>::accepts(columns[index].type_())
+                && !geometry_columns.contains(&index)
+        };
+        let name = find_column(columns, |name, _| name == "name")
+            .filter(|&index| is_text(index))
+            .or_else(|| (0..columns.len()).find(|&index| is_text(index)));
+        let attributes = (0..columns.len())
+            .filter(|index| {
+                Some(*index) != id
+                    && Some(*index) != name
+                    && !geometry_columns.contains(index)
+                    && AttributeValue::accepts(columns[*index].type_())
+            })
+            .collect();
+
+        Ok(Layout {
+            id,
+            name,
+            geometry,
+            attributes,
+        })
+    }
+
+    fn is_lat_lon(&self) -> bool {
+        matches!(self.geometry, GeometryColumns::LatLon(..))
+    }
+
+    fn read_row(&self, row: &Row, object: &mut ObjectParts) -> DatabaseResult<()> {
+        if let Some(column) = self.name {
+            if let Some(name) = row.get::<_, Option<String>>(column) {
+                if !object.names.contains(&name) {
+                    object.names.push(name);
+                }
+            }
+        }
+
+        for &column in &self.attributes {
+            let name = row.columns()[column].name();
+
+            if object.attributes.contains_key(name) {
+                continue;
+            }
+
+            if let Some(value) = row.get::<_, Option<AttributeValue>>(column) {
+                object.attributes.insert(name.into(), value.into_string());
+            }
+        }
+
+        let shapes = match self.geometry {
+            GeometryColumns::Wkb(column) => match row.get::<_, Option<WkbValue>>(column) {
+                Some(value) => shape::from_wkb(value.as_bytes())?,
+                None => Vec::new(),
+            },
+            GeometryColumns::Wkt(column) => match row.get::<_, Option<String>>(column) {
+                Some(value) => shape::from_wkt(&value)?,
+                None => Vec::new(),
+            },
+            GeometryColumns::LatLon(latitude, longitude) => {
+                let latitude: Option<CoordinateValue> = row.get(latitude);
+                let longitude: Option<CoordinateValue> = row.get(longitude);
+
+                if let (Some(latitude), Some(longitude)) = (latitude, longitude) {
+                    let point = Point::new(latitude.into_f64(), longitude.into_f64());
+                    object.points.push(point);
+                }
+
+                Vec::new()
+            }
+        };
+
+        for shape in shapes {
+            match shape {
+                Shape::Point(point) => object.points.push(point),
+                Shape::Line(points) => object.lines.push(points),
+                Shape::Polygon(points) => object.polygons.push(points),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn columns(&self, columns: &[Column]) -> SqlColumns {
+        let name = |index: usize| columns[index].name().to_string();
+        let (geometry, geometry_type) = match self.geometry {
+            GeometryColumns::Wkb(column) => (vec![name(column)], SqlGeometryType::Wkb),
+            GeometryColumns::Wkt(column) => (vec![name(column)], SqlGeometryType::Wkt),
+            GeometryColumns::LatLon(latitude, longitude) => (
+                vec![name(latitude), name(longitude)],
+                SqlGeometryType::LatLon,
+            ),
+        };
+
+        SqlColumns {
+            id: self.id.map(name),
+            name: self.name.map(name),
+            geometry,
+            geometry_type,
+        }
+    }
+}
+
+// Column names compared in lower case
+fn find_column<F>(columns: &[Column], predicate: F) -> Option<usize>
+where
+    F: Fn(&str, &Type) -> bool,
+{
+    columns
+        .iter()
+        .position(|column| predicate(&column.name().to_lowercase(), column.type_()))
+}
+
+// Text column with WKT of lines or polygons in the first not null value
+fn find_wkt_column(rows: &Rows) -> Option<usize> {
+    (0..rows.columns().len())
+        .filter(|&index| <String as FromSql>::accepts(rows.columns()[index].type_()))
+        .find(|&index| {
+            rows.iter()
+                .filter_map(|row| row.get::<_, Option<String>>(index))
+                .next()
+                .map(|value| {
+                    let value = value.trim_start().to_uppercase();
+
+                    WKT_TYPES.iter().any(|prefix| value.starts_with(prefix))
+                })
+                .unwrap_or(false)
+        })
+}
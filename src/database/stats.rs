@@ -0,0 +1,45 @@
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct QueryStats {
+    names_rows: usize,
+    geometry_rows: usize,
+    #[serde(skip)]
+    connect_time: Duration,
+    #[serde(skip)]
+    query_time: Duration,
+}
+
+impl QueryStats {
+    pub fn names_rows(&self) -> usize {
+        self.names_rows
+    }
+
+    pub fn geometry_rows(&self) -> usize {
+        self.geometry_rows
+    }
+
+    pub fn add_names_rows(&mut self, rows: usize) {
+        self.names_rows += rows;
+    }
+
+    pub fn add_geometry_rows(&mut self, rows: usize) {
+        self.geometry_rows += rows;
+    }
+
+    pub fn connect_time(&self) -> Duration {
+        self.connect_time
+    }
+
+    pub fn query_time(&self) -> Duration {
+        self.query_time
+    }
+
+    pub fn set_connect_time(&mut self, time: Duration) {
+        self.connect_time = time;
+    }
+
+    pub fn add_query_time(&mut self, time: Duration) {
+        self.query_time += time;
+    }
+}
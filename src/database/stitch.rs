@@ -0,0 +1,79 @@
+use super::Point;
+use super::EPSILON;
+
+// Joins parts sharing end points into continuous chains, parts reversed if required. When
+// `close_rings` is set chain growing stops as soon as chain becomes closed ring.
+pub fn stitch(parts: Vec<Vec<Point>>, close_rings: bool) -> Vec<Vec<Point>> {
+    let mut remaining: Vec<Option<Vec<Point>>> = parts
+        .into_iter()
+        .filter(|part| !part.is_empty())
+        .map(Some)
+        .collect();
+    let mut result = Vec::new();
+
+    for index in 0..remaining.len() {
+        let mut chain = match remaining[index].take() {
+            Some(chain) => chain,
+            None => continue,
+        };
+
+        while !(close_rings && is_closed(&chain)) {
+            if !append_next(&mut chain, &mut remaining) {
+                break;
+            }
+        }
+
+        result.push(chain);
+    }
+
+    result
+}
+
+fn append_next(chain: &mut Vec<Point>, remaining: &mut [Option<Vec<Point>>]) -> bool {
+    let chain_start = chain[0].clone();
+    let chain_end = chain[chain.len() - 1].clone();
+
+    for slot in remaining.iter_mut() {
+        let (start, end) = match slot {
+            Some(part) => (part[0].clone(), part[part.len() - 1].clone()),
+            None => continue,
+        };
+
+        if is_same(&chain_end, &start) {
+            let part = slot.take().unwrap();
+
+            chain.extend(part.into_iter().skip(1));
+        } else if is_same(&chain_end, &end) {
+            let part = slot.take().unwrap();
+
+            chain.extend(part.into_iter().rev().skip(1));
+        } else if is_same(&chain_start, &end) {
+            let mut part = slot.take().unwrap();
+
+            part.pop();
+            part.append(chain);
+            *chain = part;
+        } else if is_same(&chain_start, &start) {
+            let mut part = slot.take().unwrap();
+
+            part.reverse();
+            part.pop();
+            part.append(chain);
+            *chain = part;
+        } else {
+            continue;
+        }
+
+        return true;
+    }
+
+    false
+}
+
+fn is_closed(chain: &[Point]) -> bool {
+    chain.len() > 2 && is_same(&chain[0], &chain[chain.len() - 1])
+}
+
+fn is_same(a: &Point, b: &Point) -> bool {
+    (a.lat() - b.lat()).abs() + (a.lon() - b.lon()).abs() <= EPSILON
+}
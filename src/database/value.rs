@@ -0,0 +1,489 @@
+use crate::config::date_days;
+use crate::config::QueryParameter;
+use postgres::types::FromSql;
+use postgres::types::IsNull;
+use postgres::types::ToSql;
+use postgres::types::Type;
+use postgres::types::BOOL;
+use postgres::types::BYTEA;
+use postgres::types::DATE;
+use postgres::types::FLOAT4;
+use postgres::types::FLOAT8;
+use postgres::types::INT2;
+use postgres::types::INT4;
+use postgres::types::INT8;
+use postgres::types::NUMERIC;
+use postgres::types::TIMESTAMP;
+use postgres::types::TIMESTAMPTZ;
+use std::convert::TryFrom;
+use std::error::Error;
+
+pub struct AttributeValue(String);
+
+impl AttributeValue {
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl FromSql for AttributeValue {
+    fn from_sql(ty: &Type, raw: &[u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let value = if *ty == BOOL {
+            bool::from_sql(ty, raw)?.to_string()
+        } else if *ty == INT2 {
+            i16::from_sql(ty, raw)?.to_string()
+        } else if *ty == INT4 {
+            i32::from_sql(ty, raw)?.to_string()
+        } else if *ty == INT8 {
+            i64::from_sql(ty, raw)?.to_string()
+        } else if *ty == FLOAT4 {
+            f32::from_sql(ty, raw)?.to_string()
+        } else if *ty == FLOAT8 {
+            f64::from_sql(ty, raw)?.to_string()
+        } else if *ty == NUMERIC {
+            numeric_text(raw)?
+        } else if *ty == DATE {
+            date_text(i32::from_sql(ty, raw)?)
+        } else if *ty == TIMESTAMP {
+            timestamp_text(i64::from_sql(&INT8, raw)?, "")
+        } else if *ty == TIMESTAMPTZ {
+            timestamp_text(i64::from_sql(&INT8, raw)?, "+00")
+        } else {
+            String::from_sql(ty, raw)?
+        };
+
+        Ok(AttributeValue(value))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        [
+            BOOL,
+            INT2,
+            INT4,
+            INT8,
+            FLOAT4,
+            FLOAT8,
+            NUMERIC,
+            DATE,
+            TIMESTAMP,
+            TIMESTAMPTZ,
+        ]
+        .contains(ty)
+            || <String as FromSql>::accepts(ty)
+    }
+}
+
+pub struct CoordinateValue(f64);
+
+impl CoordinateValue {
+    pub fn into_f64(self) -> f64 {
+        self.0
+    }
+}
+
+impl FromSql for CoordinateValue {
+    fn from_sql(ty: &Type, raw: &[u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let value = if *ty == FLOAT4 {
+            f64::from(f32::from_sql(ty, raw)?)
+        } else if *ty == FLOAT8 {
+            f64::from_sql(ty, raw)?
+        } else {
+            numeric_from_sql(raw)?
+        };
+
+        Ok(CoordinateValue(value))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        [FLOAT4, FLOAT8, NUMERIC].contains(ty)
+    }
+}
+
+pub struct IdValue(i64);
+
+impl IdValue {
+    pub fn into_i64(self) -> i64 {
+        self.0
+    }
+}
+
+impl FromSql for IdValue {
+    fn from_sql(ty: &Type, raw: &[u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        let value = if *ty == INT2 {
+            i64::from(i16::from_sql(ty, raw)?)
+        } else if *ty == INT4 {
+            i64::from(i32::from_sql(ty, raw)?)
+        } else {
+            i64::from_sql(ty, raw)?
+        };
+
+        Ok(IdValue(value))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        [INT2, INT4, INT8].contains(ty)
+    }
+}
+
+pub struct WkbValue(Vec<u8>);
+
+impl WkbValue {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl FromSql for WkbValue {
+    fn from_sql(_: &Type, raw: &[u8]) -> Result<Self, Box<dyn Error + Sync + Send>> {
+        Ok(WkbValue(raw.to_vec()))
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        *ty == BYTEA || ["geometry", "geography"].contains(&ty.name())
+    }
+}
+
+impl ToSql for QueryParameter {
+    fn to_sql(&self, ty: &Type, out: &mut Vec<u8>) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        match *self {
+            QueryParameter::Text(ref value) => value.to_sql(ty, out),
+            QueryParameter::Int(value) if *ty == INT2 => i16::try_from(value)?.to_sql(ty, out),
+            QueryParameter::Int(value) if *ty == INT4 => i32::try_from(value)?.to_sql(ty, out),
+            QueryParameter::Int(value) => value.to_sql(ty, out),
+            QueryParameter::Date(ref value) if *ty == DATE => {
+                let days = date_days(value).ok_or("invalid date value")?;
+                out.extend_from_slice(&days.to_be_bytes());
+
+                Ok(IsNull::No)
+            }
+            QueryParameter::Date(ref value) => value.to_sql(ty, out),
+        }
+    }
+
+    fn accepts(ty: &Type) -> bool {
+        [INT2, INT4, INT8, DATE].contains(ty) || <String as ToSql>::accepts(ty)
+    }
+
+    // Accepted type depends on the variant
+    fn to_sql_checked(
+        &self,
+        ty: &Type,
+        out: &mut Vec<u8>,
+    ) -> Result<IsNull, Box<dyn Error + Sync + Send>> {
+        let accepted = match *self {
+            QueryParameter::Text(_) => <String as ToSql>::accepts(ty),
+            QueryParameter::Int(_) => [INT2, INT4, INT8].contains(ty),
+            QueryParameter::Date(_) => *ty == DATE || <String as ToSql>::accepts(ty),
+        };
+
+        if !accepted {
+            return Err(format!("parameter value {} can not be used as {}", self, ty).into());
+        }
+
+        self.to_sql(ty, out)
+    }
+}
+
+const NUMERIC_NEGATIVE: u16 = 0x4000;
+const NUMERIC_NAN: u16 = 0xC000;
+
+struct Numeric {
+    weight: i16,
+    sign: u16,
+    scale: u16,
+    digits: Vec<u16>,
+}
+
+// Binary `numeric` value is a header (digits count, weight of the first digit, sign, display
+// scale) followed by base 10000 digits.
+fn read_numeric(raw: &[u8]) -> Result<Numeric, Box<dyn Error + Sync + Send>> {
+    if raw.len() < 8 {
+        return Err("invalid numeric value".into());
+    }
+
+    let read = |offset: usize| u16::from_be_bytes([raw[offset], raw[offset + 1]]);
+    let count = read(0) as usize;
+
+    if raw.len() < 8 + count * 2 {
+        return Err("invalid numeric value".into());
+    }
+
+    Ok(Numeric {
+        weight: read(2) as i16,
+        sign: read(4),
+        scale: read(6),
+        digits: (0..count).map(|index| read(8 + index * 2)).collect(),
+    })
+}
+
+fn numeric_from_sql(raw: &[u8]) -> Result<f64, Box<dyn Error + Sync + Send>> {
+    let numeric = read_numeric(raw)?;
+
+    if numeric.sign == NUMERIC_NAN {
+        return Err("numeric NaN can not be used as coordinate".into());
+    }
+
+    let mut result = 0.0;
+
+    for (index, digit) in numeric.digits.iter().enumerate() {
+        result += f64::from(*digit) * 10000f64.powi(i32::from(numeric.weight) - index as i32);
+    }
+
+    if numeric.sign == NUMERIC_NEGATIVE {
+        result = -result;
+    }
+
+    Ok(result)
+}
+
+// Text of `numeric` value has all digits of display scale, as PostgreSQL outputs it
+fn numeric_text(raw: &[u8]) -> Result<String, Box<dyn Error + Sync + Send>> {
+    let numeric = read_numeric(raw)?;
+
+    if numeric.sign == NUMERIC_NAN {
+        return Ok("NaN".into());
+    }
+
+    let weight = i32::from(numeric.weight);
+    let digit = |index: i32| {
+        usize::try_from(index)
+            .ok()
+            .and_then(|index| numeric.digits.get(index))
+            .cloned()
+            .unwrap_or(0)
+    };
+    let mut result = String::new();
+
+    if numeric.sign == NUMERIC_NEGATIVE {
+        result.push('-');
+    }
+
+    if weight < 0 {
+        result.push('0');
+    } else {
+        result.push_str(&digit(0).to_string());
+
+        for index in 1..=weight {
+            result.push_str(&format!("{:04}", digit(index)));
+        }
+    }
+
+    if numeric.scale > 0 {
+        let mut fraction = String::new();
+        let mut index = weight + 1;
+
+        while fraction.len() < usize::from(numeric.scale) {
+            fraction.push_str(&format!("{:04}", digit(index)));
+            index += 1;
+        }
+
+        fraction.truncate(usize::from(numeric.scale));
+        result.push('.');
+        result.push_str(&fraction);
+    }
+
+    Ok(result)
+}
+
+// Dates and timestamps are counted from 2000-01-01, extreme values are infinities
+fn date_text(days: i32) -> String {
+    match days {
+        i32::MAX => "infinity".into(),
+        i32::MIN => "-infinity".into(),
+        days => civil_date(i64::from(days)),
+    }
+}
+
+// Time zone of `timestamptz` values is UTC
+fn timestamp_text(microseconds: i64, zone: &str) -> String {
+    const DAY_MICROSECONDS: i64 = 86_400_000_000;
+
+    match microseconds {
+        i64::MAX => "infinity".into(),
+        i64::MIN => "-infinity".into(),
+        microseconds => {
+            let days = microseconds.div_euclid(DAY_MICROSECONDS);
+            let time = microseconds.rem_euclid(DAY_MICROSECONDS);
+            let seconds = time / 1_000_000;
+            let fraction = format!(".{:06}", time % 1_000_000);
+
+            format!(
+                "{} {:02}:{:02}:{:02}{}{}",
+                civil_date(days),
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60,
+                fraction.trim_end_matches('0').trim_end_matches('.'),
+                zone
+            )
+        }
+    }
+}
+
+// Civil date from days, inverse of `date_days`
+fn civil_date(days: i64) -> String {
+    let unix_days = days + 10_957 + 719_468;
+    let era = (if unix_days >= 0 {
+        unix_days
+    } else {
+        unix_days - 146_096
+    }) / 146_097;
+    let day_of_era = unix_days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::date_text;
+    use super::numeric_from_sql;
+    use super::numeric_text;
+    use super::timestamp_text;
+    use super::NUMERIC_NAN;
+    use super::NUMERIC_NEGATIVE;
+    use crate::config::date_days;
+    use crate::config::QueryParameter;
+    use postgres::types::ToSql;
+    use postgres::types::Type;
+    use postgres::types::DATE;
+    use postgres::types::INT2;
+    use postgres::types::INT4;
+    use postgres::types::INT8;
+    use postgres::types::TEXT;
+
+    fn numeric(weight: i16, sign: u16, scale: u16, digits: &[u16]) -> Vec<u8> {
+        let mut result = Vec::new();
+
+        for value in [digits.len() as u16, weight as u16, sign, scale]
+            .iter()
+            .chain(digits)
+        {
+            result.extend_from_slice(&value.to_be_bytes());
+        }
+
+        result
+    }
+
+    #[test]
+    fn reads_numeric_coordinates() {
+        let value = |raw: Vec<u8>| numeric_from_sql(&raw).unwrap();
+
+        assert_eq!(value(numeric(0, 0, 0, &[])), 0.0);
+        assert_eq!(value(numeric(0, 0, 0, &[55])), 55.0);
+        assert_eq!(value(numeric(0, NUMERIC_NEGATIVE, 0, &[37])), -37.0);
+        assert!((value(numeric(0, 0, 4, &[55, 7512])) - 55.7512).abs() < 1e-12);
+        assert!(
+            (value(numeric(0, NUMERIC_NEGATIVE, 6, &[37, 6173, 5000])) + 37.61735).abs() < 1e-12
+        );
+        assert!((value(numeric(-1, 0, 6, &[12, 3400])) - 0.001234).abs() < 1e-15);
+        assert!((value(numeric(-2, NUMERIC_NEGATIVE, 8, &[5])) + 0.000_000_05).abs() < 1e-20);
+        assert_eq!(value(numeric(1, 0, 0, &[1])), 10000.0);
+    }
+
+    #[test]
+    fn rejects_numeric_nan_and_invalid_values() {
+        assert!(numeric_from_sql(&numeric(0, NUMERIC_NAN, 0, &[])).is_err());
+        assert!(numeric_from_sql(&[0, 0, 0, 0]).is_err());
+        assert!(numeric_from_sql(&[0, 2, 0, 0, 0, 0, 0, 0, 0, 1]).is_err());
+    }
+
+    #[test]
+    fn writes_numeric_digits_of_scale() {
+        let text = |raw: Vec<u8>| numeric_text(&raw).unwrap();
+
+        assert_eq!(text(numeric(2, 0, 0, &[1, 2345, 6789])), "123456789");
+        assert_eq!(
+            text(numeric(1, NUMERIC_NEGATIVE, 5, &[1, 2345, 67])),
+            "-12345.00670"
+        );
+        assert_eq!(text(numeric(-1, 0, 5, &[1, 2000])), "0.00012");
+        assert_eq!(text(numeric(0, 0, 2, &[])), "0.00");
+        assert_eq!(text(numeric(1, 0, 0, &[1])), "10000");
+        assert_eq!(text(numeric(0, NUMERIC_NAN, 0, &[])), "NaN");
+        assert!(numeric_text(&[0, 1, 0, 0, 0, 0, 0, 0]).is_err());
+    }
+
+    #[test]
+    fn writes_dates_and_timestamps() {
+        assert_eq!(date_text(0), "2000-01-01");
+        assert_eq!(date_text(-1), "1999-12-31");
+        assert_eq!(date_text(59), "2000-02-29");
+        assert_eq!(date_text(8825), "2024-02-29");
+        assert_eq!(date_text(i32::MAX), "infinity");
+        assert_eq!(timestamp_text(-1, ""), "1999-12-31 23:59:59.999999");
+        assert_eq!(
+            timestamp_text(36_997_567_500_000, "+00"),
+            "2001-03-04 05:06:07.5+00"
+        );
+        assert_eq!(timestamp_text(i64::MIN, ""), "-infinity");
+    }
+
+    fn write(parameter: QueryParameter, ty: &Type) -> Option<Vec<u8>> {
+        let mut out = Vec::new();
+
+        parameter.to_sql_checked(ty, &mut out).ok().map(|_| out)
+    }
+
+    #[test]
+    fn writes_date_parameters_as_days() {
+        let date = |text: &str| write(QueryParameter::Date(text.into()), &DATE);
+
+        assert_eq!(date("2000-01-01"), Some(0i32.to_be_bytes().to_vec()));
+        assert_eq!(date("1999-12-31"), Some((-1i32).to_be_bytes().to_vec()));
+        assert_eq!(date("2001-01-01"), Some(366i32.to_be_bytes().to_vec()));
+        assert_eq!(date("2024-02-29"), Some(8825i32.to_be_bytes().to_vec()));
+        assert_eq!(date("2023-02-29"), None);
+        assert_eq!(date("2000-13-01"), None);
+        assert_eq!(
+            write(QueryParameter::Date("2000-01-01".into()), &TEXT),
+            Some(b"2000-01-01".to_vec())
+        );
+    }
+
+    #[test]
+    fn writes_parameters_of_accepted_types() {
+        assert_eq!(
+            write(QueryParameter::Int(7), &INT2),
+            Some(7i16.to_be_bytes().to_vec())
+        );
+        assert_eq!(
+            write(QueryParameter::Int(7), &INT4),
+            Some(7i32.to_be_bytes().to_vec())
+        );
+        assert_eq!(
+            write(QueryParameter::Int(7), &INT8),
+            Some(7i64.to_be_bytes().to_vec())
+        );
+        assert_eq!(write(QueryParameter::Int(70_000), &INT2), None);
+        assert_eq!(write(QueryParameter::Int(7), &TEXT), None);
+        assert_eq!(write(QueryParameter::Int(7), &DATE), None);
+        assert_eq!(write(QueryParameter::Text("7".into()), &INT4), None);
+        assert_eq!(
+            write(QueryParameter::Text("2000-01-01".into()), &DATE),
+            None
+        );
+        assert_eq!(
+            write(QueryParameter::Text("x".into()), &TEXT),
+            Some(b"x".to_vec())
+        );
+    }
+
+    // Dates from 0001-01-01 to 9999-12-31
+    #[test]
+    fn writes_dates_inverse_of_parsing() {
+        for days in (-730_119..=2_921_939).step_by(7) {
+            assert_eq!(date_days(&date_text(days)), Some(days));
+        }
+    }
+}
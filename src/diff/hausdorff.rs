@@ -0,0 +1,95 @@
+use crate::database::Geometry;
+use crate::database::Point;
+
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+/// Hausdorff distance between geometries in meters: the largest distance from a vertex of one
+/// geometry to the nearest segment of the other one. Coordinates projected to plane around
+/// middle latitude, so distance is approximate for large objects.
+pub fn hausdorff_distance(first: &Geometry, second: &Geometry) -> Option<f64> {
+    let first = parts(first);
+    let second = parts(second);
+    let latitudes = first
+        .iter()
+        .chain(second.iter())
+        .flat_map(|part| part.iter())
+        .map(|point| point.lat());
+    let (min_lat, max_lat) = latitudes.fold(None, |bounds, lat| match bounds {
+        Some((min, max)) => Some((lat.min(min), lat.max(max))),
+        None => Some((lat, lat)),
+    })?;
+    let scale = ((min_lat + max_lat) / 2.0).to_radians().cos();
+    let project = |parts: Vec<&[Point]>| -> Vec<Vec<(f64, f64)>> {
+        parts
+            .into_iter()
+            .map(|part| {
+                part.iter()
+                    .map(|point| {
+                        (
+                            point.lon().to_radians() * scale * EARTH_RADIUS,
+                            point.lat().to_radians() * EARTH_RADIUS,
+                        )
+                    })
+                    .collect()
+            })
+            .collect()
+    };
+    let first = project(first);
+    let second = project(second);
+
+    if first.iter().all(Vec::is_empty) || second.iter().all(Vec::is_empty) {
+        return None;
+    }
+
+    Some(directed(&first, &second).max(directed(&second, &first)))
+}
+
+fn parts(geometry: &Geometry) -> Vec<&[Point]> {
+    match geometry {
+        Geometry::MultiLine(multi_line) => multi_line
+            .lines()
+            .iter()
+            .map(|line| line.points())
+            .collect(),
+        Geometry::MultiPolygon(multi_polygon) => multi_polygon
+            .polygons()
+            .iter()
+            .map(|polygon| polygon.points())
+            .collect(),
+    }
+}
+
+fn directed(from: &[Vec<(f64, f64)>], to: &[Vec<(f64, f64)>]) -> f64 {
+    let mut result: f64 = 0.0;
+
+    for &point in from.iter().flatten() {
+        let mut nearest = f64::INFINITY;
+
+        for part in to {
+            if part.len() == 1 {
+                nearest = nearest.min(segment_distance(point, part[0], part[0]));
+            }
+
+            for segment in part.windows(2) {
+                nearest = nearest.min(segment_distance(point, segment[0], segment[1]));
+            }
+        }
+
+        result = result.max(nearest);
+    }
+
+    result
+}
+
+fn segment_distance(point: (f64, f64), start: (f64, f64), end: (f64, f64)) -> f64 {
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let length = dx * dx + dy * dy;
+    let position = if length > 0.0 {
+        (((point.0 - start.0) * dx + (point.1 - start.1) * dy) / length).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let (x, y) = (start.0 + position * dx, start.1 + position * dy);
+
+    ((point.0 - x).powi(2) + (point.1 - y).powi(2)).sqrt()
+}
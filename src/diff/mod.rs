@@ -0,0 +1,93 @@
+mod hausdorff;
+
+use self::hausdorff::hausdorff_distance;
+use crate::database::Geometry;
+use crate::database::NamesGeometry;
+use crate::database::Point;
+use crate::database::EPSILON;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffStatus {
+    Added,
+    Removed,
+    Unchanged,
+    Changed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ObjectDiff {
+    status: DiffStatus,
+    names_changed: bool,
+    geometry_changed: bool,
+    // Hausdorff distance between versions in meters
+    #[serde(skip_serializing_if = "Option::is_none")]
+    distance: Option<f64>,
+}
+
+/// Compares versions of the object. Names compared ignoring order, geometry compared point by
+/// point, so the same shape with different vertices is changed with zero distance.
+pub fn compare(before: Option<&NamesGeometry>, after: Option<&NamesGeometry>) -> ObjectDiff {
+    let (before, after) = match (before, after) {
+        (Some(before), Some(after)) => (before, after),
+        (before, _) => {
+            return ObjectDiff {
+                status: if before.is_some() {
+                    DiffStatus::Removed
+                } else {
+                    DiffStatus::Added
+                },
+                names_changed: false,
+                geometry_changed: false,
+                distance: None,
+            }
+        }
+    };
+    let mut before_names = before.names().to_vec();
+    let mut after_names = after.names().to_vec();
+    before_names.sort();
+    after_names.sort();
+
+    let names_changed = before_names != after_names;
+    let geometry_changed = !is_same_geometry(before.geometry(), after.geometry());
+    let status = if names_changed || geometry_changed {
+        DiffStatus::Changed
+    } else {
+        DiffStatus::Unchanged
+    };
+
+    ObjectDiff {
+        status,
+        names_changed,
+        geometry_changed,
+        distance: hausdorff_distance(before.geometry(), after.geometry()),
+    }
+}
+
+fn is_same_geometry(first: &Geometry, second: &Geometry) -> bool {
+    match (first, second) {
+        (Geometry::MultiLine(first), Geometry::MultiLine(second)) => is_same_parts(
+            first.lines().iter().map(|line| line.points()),
+            second.lines().iter().map(|line| line.points()),
+        ),
+        (Geometry::MultiPolygon(first), Geometry::MultiPolygon(second)) => is_same_parts(
+            first.polygons().iter().map(|polygon| polygon.points()),
+            second.polygons().iter().map(|polygon| polygon.points()),
+        ),
+        _ => false,
+    }
+}
+
+fn is_same_parts<'a>(
+    first: impl ExactSizeIterator<Item = &'a [Point]>,
+    second: impl ExactSizeIterator<Item = &'a [Point]>,
+) -> bool {
+    first.len() == second.len()
+        && first.zip(second).all(|(first, second)| {
+            first.len() == second.len()
+                && first.iter().zip(second).all(|(first, second)| {
+                    (first.lat() - second.lat()).abs() <= EPSILON
+                        && (first.lon() - second.lon()).abs() <= EPSILON
+                })
+        })
+}
@@ -0,0 +1,110 @@
+const PRECISION: f64 = 1e7;
+
+pub fn encode_delta<I>(coordinates: I) -> String
+where
+    I: IntoIterator<Item = (f64, f64)>,
+{
+    let mut bytes = Vec::new();
+    let mut last_lat = 0;
+    let mut last_lon = 0;
+
+    for (lat, lon) in coordinates {
+        let lat = (lat * PRECISION).round() as i64;
+        let lon = (lon * PRECISION).round() as i64;
+
+        write_varint(&mut bytes, zigzag(lat - last_lat));
+        write_varint(&mut bytes, zigzag(lon - last_lon));
+
+        last_lat = lat;
+        last_lon = lon;
+    }
+
+    base64::encode(&bytes)
+}
+
+fn zigzag(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn write_varint(bytes: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        bytes.push((value as u8 & 0x7f) | 0x80);
+        value >>= 7;
+    }
+
+    bytes.push(value as u8);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode_delta;
+    use super::PRECISION;
+
+    // Same as `decodeDelta` of geometryDecoder.js
+    fn decode(text: &str) -> Vec<(f64, f64)> {
+        let mut values = Vec::new();
+        let mut bytes = base64::decode(text).unwrap().into_iter();
+
+        while let Some(mut byte) = bytes.next() {
+            let mut result = 0u64;
+            let mut shift = 0;
+
+            loop {
+                result |= u64::from(byte & 0x7f) << shift;
+                shift += 7;
+
+                if byte < 0x80 {
+                    break;
+                }
+
+                byte = bytes.next().unwrap();
+            }
+
+            values.push((result >> 1) as i64 ^ -((result & 1) as i64));
+        }
+
+        let mut lat = 0;
+        let mut lon = 0;
+
+        values
+            .chunks(2)
+            .map(|delta| {
+                lat += delta[0];
+                lon += delta[1];
+
+                (lat as f64 / PRECISION, lon as f64 / PRECISION)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn encodes_zigzag_varints() {
+        let points = vec![(38.5, -120.2), (40.7, -120.95), (43.252, -126.453)];
+
+        assert_eq!(
+            encode_delta(points.clone()),
+            "gImV7wL/waj6CIDG/RS/w5MHgJ6rGN/DvTQ="
+        );
+        assert_eq!(decode("gImV7wL/waj6CIDG/RS/w5MHgJ6rGN/DvTQ="), points);
+        assert_eq!(encode_delta(Vec::new()), "");
+    }
+
+    #[test]
+    fn round_trips_coordinates() {
+        let points = vec![
+            (55.7512345, 37.6173456),
+            (-90.0, 180.0),
+            (-0.00000004, 0.00000006),
+        ];
+
+        assert_eq!(
+            encode_delta(points.clone()),
+            "strXkwSgzt/mArH+/u0K4PnuzQqApKfaBv3HzrQN"
+        );
+
+        for (point, decoded) in points.iter().zip(decode(&encode_delta(points.clone()))) {
+            assert!((point.0 - decoded.0).abs() <= 0.5 / PRECISION);
+            assert!((point.1 - decoded.1).abs() <= 0.5 / PRECISION);
+        }
+    }
+}
@@ -0,0 +1,21 @@
+mod delta;
+mod polyline;
+
+pub use self::delta::encode_delta;
+pub use self::polyline::encode_polyline;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GeometryEncoding {
+    #[default]
+    Points,
+    Flat,
+    Polyline,
+    Delta,
+}
+
+impl GeometryEncoding {
+    pub fn is_points(&self) -> bool {
+        *self == GeometryEncoding::Points
+    }
+}
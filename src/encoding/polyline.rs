@@ -0,0 +1,114 @@
+const PRECISION: f64 = 1e6;
+
+pub fn encode_polyline<I>(coordinates: I) -> String
+where
+    I: IntoIterator<Item = (f64, f64)>,
+{
+    let mut result = String::new();
+    let mut last_lat = 0;
+    let mut last_lon = 0;
+
+    for (lat, lon) in coordinates {
+        let lat = (lat * PRECISION).round() as i64;
+        let lon = (lon * PRECISION).round() as i64;
+
+        encode_value(&mut result, lat - last_lat);
+        encode_value(&mut result, lon - last_lon);
+
+        last_lat = lat;
+        last_lon = lon;
+    }
+
+    result
+}
+
+fn encode_value(result: &mut String, value: i64) {
+    let mut value = if value < 0 { !(value << 1) } else { value << 1 };
+
+    while value >= 0x20 {
+        result.push((((0x20 | (value & 0x1f)) + 63) as u8) as char);
+        value >>= 5;
+    }
+
+    result.push(((value + 63) as u8) as char);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode_polyline;
+    use super::PRECISION;
+
+    // Same as `decodePolyline` of geometryDecoder.js
+    fn decode(text: &str) -> Vec<(f64, f64)> {
+        let mut values = Vec::new();
+        let mut bytes = text.bytes();
+
+        while let Some(mut byte) = bytes.next() {
+            let mut result = 0i64;
+            let mut shift = 0;
+
+            loop {
+                let value = i64::from(byte) - 63;
+                result += (value & 0x1f) << shift;
+                shift += 5;
+
+                if value < 0x20 {
+                    break;
+                }
+
+                byte = bytes.next().unwrap();
+            }
+
+            values.push(if result % 2 == 1 {
+                -(result + 1) / 2
+            } else {
+                result / 2
+            });
+        }
+
+        let mut lat = 0;
+        let mut lon = 0;
+
+        values
+            .chunks(2)
+            .map(|delta| {
+                lat += delta[0];
+                lon += delta[1];
+
+                (lat as f64 / PRECISION, lon as f64 / PRECISION)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn encodes_with_precision_of_6_digits() {
+        let points = vec![(38.5, -120.2), (40.7, -120.95), (43.252, -126.453)];
+
+        assert_eq!(
+            encode_polyline(points.clone()),
+            "_izlhA~rlgdF_{geC~ywl@_kwzCn`{nI"
+        );
+        assert_eq!(decode("_izlhA~rlgdF_{geC~ywl@_kwzCn`{nI"), points);
+        assert_eq!(encode_polyline(Vec::new()), "");
+    }
+
+    #[test]
+    fn round_trips_coordinates() {
+        let points = vec![
+            (55.7512345, 37.6173456),
+            (55.7512345, 37.6173456),
+            (-89.9999994, 179.9999996),
+            (-0.0000004, 0.0000006),
+        ];
+
+        assert_eq!(
+            encode_polyline(points.clone()),
+            "ecxiiBck~vfA??bk}~tG{bjqnG}fdtjD|niivI"
+        );
+
+        for (point, decoded) in points.iter().zip(decode(&encode_polyline(points.clone()))) {
+            assert!((point.0 - decoded.0).abs() <= 0.5 / PRECISION);
+            assert!((point.1 - decoded.1).abs() <= 0.5 / PRECISION);
+        }
+    }
+}
@@ -9,6 +9,7 @@ pub type ApplicationResult = Result<(), ApplicationError>;
 #[derive(Debug)]
 pub enum ApplicationError {
     ConfigError { message: String },
+    QueryError { message: String },
 }
 
 impl ApplicationError {
@@ -20,6 +21,12 @@ impl ApplicationError {
             message: format!("{}", error),
         }
     }
+
+    pub fn query_error(message: &str) -> ApplicationError {
+        ApplicationError::QueryError {
+            message: message.into(),
+        }
+    }
 }
 
 impl Error for ApplicationError {}
@@ -28,6 +35,7 @@ impl Display for ApplicationError {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
         match self {
             ApplicationError::ConfigError { message } => write!(f, "{}", message),
+            ApplicationError::QueryError { message } => write!(f, "{}", message),
         }
     }
 }
@@ -0,0 +1,51 @@
+use super::wkt::geometry_wkt;
+use super::ExportObjects;
+use super::ExportResult;
+use std::collections::BTreeSet;
+
+// Columns are identifier, names joined with comma, geometry as WKT and all attributes found
+// in objects, attribute missing in object written as empty value.
+pub fn write(objects: &ExportObjects) -> ExportResult<Vec<u8>> {
+    let attributes: BTreeSet<&String> = objects
+        .values()
+        .flat_map(|object| object.attributes().keys())
+        .collect();
+    let mut result = String::new();
+
+    let mut header = vec!["id", "names", "wkt"];
+    header.extend(attributes.iter().map(|name| name.as_str()));
+    write_row(&mut result, header.into_iter());
+
+    for (id, object) in objects {
+        let id = id.to_string();
+        let names = object.names().join(", ");
+        let wkt = geometry_wkt(object);
+        let mut row = vec![id.as_str(), names.as_str(), wkt.as_str()];
+        row.extend(attributes.iter().map(|name| {
+            object
+                .attributes()
+                .get(*name)
+                .map(String::as_str)
+                .unwrap_or("")
+        }));
+        write_row(&mut result, row.into_iter());
+    }
+
+    Ok(result.into_bytes())
+}
+
+// Values containing separators, quotes or line breaks quoted, quotes doubled.
+fn write_row<'a>(result: &mut String, values: impl Iterator<Item = &'a str>) {
+    let values: Vec<String> = values
+        .map(|value| {
+            if value.contains(&[',', '"', '\n', '\r'][..]) {
+                format!("\"{}\"", value.replace('"', "\"\""))
+            } else {
+                value.into()
+            }
+        })
+        .collect();
+
+    result.push_str(&values.join(","));
+    result.push_str("\r\n");
+}
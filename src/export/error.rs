@@ -0,0 +1,54 @@
+use serde_json::Error as JsonError;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::io::Error as IoError;
+use zip::result::ZipError;
+
+pub type ExportResult<T> = Result<T, ExportError>;
+
+#[derive(Debug)]
+pub struct ExportError {
+    message: String,
+}
+
+impl ExportError {
+    pub fn new(message: &str) -> ExportError {
+        ExportError {
+            message: message.into(),
+        }
+    }
+}
+
+impl From<IoError> for ExportError {
+    fn from(error: IoError) -> ExportError {
+        ExportError {
+            message: format!("{}", error),
+        }
+    }
+}
+
+impl From<JsonError> for ExportError {
+    fn from(error: JsonError) -> ExportError {
+        ExportError {
+            message: format!("{}", error),
+        }
+    }
+}
+
+impl From<ZipError> for ExportError {
+    fn from(error: ZipError) -> ExportError {
+        ExportError {
+            message: format!("{}", error),
+        }
+    }
+}
+
+impl Error for ExportError {}
+
+impl Display for ExportError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.message)
+    }
+}
@@ -0,0 +1,57 @@
+use super::closed_ring;
+use super::parts;
+use super::ExportObjects;
+use super::ExportResult;
+use crate::database::Geometry;
+use crate::database::Point;
+use serde_json::json;
+use serde_json::Map;
+use serde_json::Value;
+
+pub fn write(objects: &ExportObjects) -> ExportResult<Vec<u8>> {
+    let mut features = Vec::new();
+
+    for (id, object) in objects {
+        let mut properties = Map::new();
+        properties.insert("names".into(), json!(object.names()));
+
+        for (name, value) in object.attributes() {
+            properties.insert(name.clone(), json!(value));
+        }
+
+        let geometry = match object.geometry() {
+            Geometry::MultiLine(_) => json!({
+                "type": "MultiLineString",
+                "coordinates": parts(object)
+                    .into_iter()
+                    .map(|points| points.iter().map(coordinate).collect::<Vec<_>>())
+                    .collect::<Vec<_>>(),
+            }),
+            Geometry::MultiPolygon(_) => json!({
+                "type": "MultiPolygon",
+                "coordinates": parts(object)
+                    .into_iter()
+                    .map(|points| vec![closed_ring(points).into_iter().map(coordinate).collect::<Vec<_>>()])
+                    .collect::<Vec<_>>(),
+            }),
+        };
+
+        features.push(json!({
+            "type": "Feature",
+            "id": id,
+            "properties": Value::Object(properties),
+            "geometry": geometry,
+        }));
+    }
+
+    let collection = json!({
+        "type": "FeatureCollection",
+        "features": features,
+    });
+
+    Ok(serde_json::to_vec_pretty(&collection)?)
+}
+
+fn coordinate(point: &Point) -> [f64; 2] {
+    [point.lon(), point.lat()]
+}
@@ -0,0 +1,64 @@
+use super::escape_xml;
+use super::parts;
+use super::ExportError;
+use super::ExportObjects;
+use super::ExportResult;
+use crate::database::Geometry;
+use std::fmt::Write;
+
+// Every object written as track, object lines are track segments. Attributes written to
+// track description as `name=value` pairs.
+pub fn write(objects: &ExportObjects) -> ExportResult<Vec<u8>> {
+    let mut result = String::new();
+
+    result.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    result.push_str(
+        "<gpx version=\"1.1\" creator=\"show-on-map\" xmlns=\"http://www.topografix.com/GPX/1/1\">\n",
+    );
+
+    for (id, object) in objects {
+        if let Geometry::MultiPolygon(_) = object.geometry() {
+            return Err(ExportError::new("GPX export supports only lines"));
+        }
+
+        let description = object
+            .attributes()
+            .iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect::<Vec<_>>()
+            .join("; ");
+
+        result.push_str("<trk>\n");
+        let _ = writeln!(
+            result,
+            "<name>{}</name>",
+            escape_xml(&object.names().join(", "))
+        );
+        let _ = writeln!(result, "<cmt>{}</cmt>", id);
+
+        if !description.is_empty() {
+            let _ = writeln!(result, "<desc>{}</desc>", escape_xml(&description));
+        }
+
+        for points in parts(object) {
+            result.push_str("<trkseg>\n");
+
+            for point in points {
+                let _ = writeln!(
+                    result,
+                    "<trkpt lat=\"{}\" lon=\"{}\"/>",
+                    point.lat(),
+                    point.lon()
+                );
+            }
+
+            result.push_str("</trkseg>\n");
+        }
+
+        result.push_str("</trk>\n");
+    }
+
+    result.push_str("</gpx>\n");
+
+    Ok(result.into_bytes())
+}
@@ -0,0 +1,73 @@
+use super::closed_ring;
+use super::escape_xml;
+use super::parts;
+use super::ExportObjects;
+use super::ExportResult;
+use crate::database::Geometry;
+use crate::database::Point;
+use std::fmt::Write;
+
+pub fn write(name: &str, objects: &ExportObjects) -> ExportResult<Vec<u8>> {
+    let mut result = String::new();
+
+    result.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    result.push_str("<kml xmlns=\"http://www.opengis.net/kml/2.2\">\n<Document>\n");
+    let _ = writeln!(result, "<name>{}</name>", escape_xml(name));
+
+    for (id, object) in objects {
+        result.push_str("<Placemark>\n");
+        let _ = writeln!(
+            result,
+            "<name>{}</name>",
+            escape_xml(&object.names().join(", "))
+        );
+        result.push_str("<ExtendedData>\n");
+        let _ = writeln!(result, "<Data name=\"id\"><value>{}</value></Data>", id);
+
+        for (name, value) in object.attributes() {
+            let _ = writeln!(
+                result,
+                "<Data name=\"{}\"><value>{}</value></Data>",
+                escape_xml(name),
+                escape_xml(value)
+            );
+        }
+
+        result.push_str("</ExtendedData>\n<MultiGeometry>\n");
+
+        for points in parts(object) {
+            match object.geometry() {
+                Geometry::MultiLine(_) => {
+                    let _ = writeln!(
+                        result,
+                        "<LineString><coordinates>{}</coordinates></LineString>",
+                        coordinates(points.iter())
+                    );
+                }
+                Geometry::MultiPolygon(_) => {
+                    let _ = writeln!(
+                        result,
+                        "<Polygon><outerBoundaryIs><LinearRing><coordinates>{}</coordinates></LinearRing></outerBoundaryIs></Polygon>",
+                        coordinates(closed_ring(points).into_iter())
+                    );
+                }
+            }
+        }
+
+        result.push_str("</MultiGeometry>\n</Placemark>\n");
+    }
+
+    result.push_str("</Document>\n</kml>\n");
+
+    Ok(result.into_bytes())
+}
+
+fn coordinates<'a, I>(points: I) -> String
+where
+    I: Iterator<Item = &'a Point>,
+{
+    points
+        .map(|point| format!("{},{}", point.lon(), point.lat()))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,135 @@
+mod csv;
+mod error;
+mod geojson;
+mod gpx;
+mod kml;
+mod shapefile;
+mod wkt;
+
+pub use self::error::ExportError;
+pub use self::error::ExportResult;
+
+use crate::database::Geometry;
+use crate::database::NamesGeometry;
+use crate::database::Point;
+use serde::de::value::Error as ValueError;
+use serde::de::value::StrDeserializer;
+use serde::de::IntoDeserializer;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+pub type ExportObjects = BTreeMap<i64, NamesGeometry>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    GeoJson,
+    Kml,
+    Gpx,
+    Shapefile,
+    Wkt,
+    Csv,
+}
+
+impl FromStr for ExportFormat {
+    type Err = ExportError;
+
+    // Command line values are the same as values of requests
+    fn from_str(value: &str) -> ExportResult<ExportFormat> {
+        let deserializer: StrDeserializer<ValueError> = value.into_deserializer();
+
+        ExportFormat::deserialize(deserializer)
+            .map_err(|_| ExportError::new(&format!("Unknown export format {}", value)))
+    }
+}
+
+pub struct ExportFile {
+    file_name: String,
+    content_type: &'static str,
+    content: Vec<u8>,
+}
+
+impl ExportFile {
+    pub fn file_name(&self) -> &str {
+        &self.file_name
+    }
+
+    pub fn content_type(&self) -> &str {
+        self.content_type
+    }
+
+    pub fn into_content(self) -> Vec<u8> {
+        self.content
+    }
+}
+
+pub fn export(
+    format: ExportFormat,
+    name: &str,
+    objects: &ExportObjects,
+) -> ExportResult<ExportFile> {
+    let (extension, content_type, content) = match format {
+        ExportFormat::GeoJson => ("geojson", "application/geo+json", geojson::write(objects)?),
+        ExportFormat::Kml => (
+            "kml",
+            "application/vnd.google-earth.kml+xml",
+            kml::write(name, objects)?,
+        ),
+        ExportFormat::Gpx => ("gpx", "application/gpx+xml", gpx::write(objects)?),
+        ExportFormat::Shapefile => ("zip", "application/zip", shapefile::write(name, objects)?),
+        ExportFormat::Wkt => ("txt", "text/plain; charset=utf-8", wkt::write(objects)?),
+        ExportFormat::Csv => ("csv", "text/csv; charset=utf-8", csv::write(objects)?),
+    };
+
+    Ok(ExportFile {
+        file_name: format!("{}.{}", name, extension),
+        content_type,
+        content,
+    })
+}
+
+pub fn parts(object: &NamesGeometry) -> Vec<&[Point]> {
+    match object.geometry() {
+        Geometry::MultiLine(multi_line) => multi_line
+            .lines()
+            .iter()
+            .map(|line| line.points())
+            .collect(),
+        Geometry::MultiPolygon(multi_polygon) => multi_polygon
+            .polygons()
+            .iter()
+            .map(|polygon| polygon.points())
+            .collect(),
+    }
+}
+
+// Returns points of polygon ring with the first point repeated at the end if required.
+fn closed_ring(points: &[Point]) -> Vec<&Point> {
+    let mut result: Vec<&Point> = points.iter().collect();
+
+    if let (Some(first), Some(last)) = (points.first(), points.last()) {
+        if points.len() > 1 && (first.lat() != last.lat() || first.lon() != last.lon()) {
+            result.push(first);
+        }
+    }
+
+    result
+}
+
+pub fn escape_xml(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+
+    for symbol in text.chars() {
+        match symbol {
+            '&' => result.push_str("&amp;"),
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            _ => result.push(symbol),
+        }
+    }
+
+    result
+}
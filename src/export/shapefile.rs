@@ -0,0 +1,508 @@
+use super::closed_ring;
+use super::parts;
+use super::ExportError;
+use super::ExportObjects;
+use super::ExportResult;
+use crate::database::Geometry;
+use crate::database::NamesGeometry;
+use crate::database::Point;
+use std::collections::BTreeSet;
+use std::io::Cursor;
+use std::io::Write;
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+const FILE_CODE: i32 = 9994;
+const VERSION: i32 = 1000;
+const HEADER_SIZE: usize = 100;
+
+const SHAPE_NULL: i32 = 0;
+const SHAPE_POLYLINE: i32 = 3;
+const SHAPE_POLYGON: i32 = 5;
+
+const ID_FIELD_SIZE: u8 = 20;
+const TEXT_FIELD_SIZE: u8 = 254;
+const FIELD_NAME_SIZE: usize = 10;
+
+const PROJECTION: &str = "GEOGCS[\"GCS_WGS_1984\",DATUM[\"D_WGS_1984\",SPHEROID[\"WGS_1984\",\
+                          6378137.0,298.257223563]],PRIMEM[\"Greenwich\",0.0],\
+                          UNIT[\"Degree\",0.0174532925199433]]";
+
+pub fn write(name: &str, objects: &ExportObjects) -> ExportResult<Vec<u8>> {
+    let shape_type = shape_type(objects)?;
+    let (shp, shx) = write_shapes(shape_type, objects);
+    let dbf = write_table(objects);
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default();
+
+    for (extension, content) in &[
+        ("shp", shp),
+        ("shx", shx),
+        ("dbf", dbf),
+        ("prj", PROJECTION.as_bytes().to_vec()),
+        ("cpg", b"UTF-8".to_vec()),
+    ] {
+        zip.start_file(format!("{}.{}", name, extension), options)?;
+        zip.write_all(content)?;
+    }
+
+    Ok(zip.finish()?.into_inner())
+}
+
+fn shape_type(objects: &ExportObjects) -> ExportResult<i32> {
+    let types: BTreeSet<i32> = objects
+        .values()
+        .map(|object| match object.geometry() {
+            Geometry::MultiLine(_) => SHAPE_POLYLINE,
+            Geometry::MultiPolygon(_) => SHAPE_POLYGON,
+        })
+        .collect();
+
+    match types.len() {
+        0 => Ok(SHAPE_NULL),
+        1 => Ok(types.into_iter().next().unwrap_or(SHAPE_NULL)),
+        _ => Err(ExportError::new(
+            "Shapefile can not contain both lines and polygons",
+        )),
+    }
+}
+
+// Returns content of `.shp` and `.shx` files.
+fn write_shapes(shape_type: i32, objects: &ExportObjects) -> (Vec<u8>, Vec<u8>) {
+    let records: Vec<Vec<u8>> = objects
+        .values()
+        .map(|object| write_record(shape_type, object))
+        .collect();
+    let bounds = Bounds::of(objects.values().flat_map(parts).flatten());
+    let shp_size = HEADER_SIZE + records.iter().map(|record| 8 + record.len()).sum::<usize>();
+    let shx_size = HEADER_SIZE + records.len() * 8;
+
+    let mut shp = write_header(shape_type, shp_size, &bounds);
+    let mut shx = write_header(shape_type, shx_size, &bounds);
+
+    for (index, record) in records.iter().enumerate() {
+        shx.extend_from_slice(&((shp.len() / 2) as i32).to_be_bytes());
+        shx.extend_from_slice(&((record.len() / 2) as i32).to_be_bytes());
+
+        shp.extend_from_slice(&(index as i32 + 1).to_be_bytes());
+        shp.extend_from_slice(&((record.len() / 2) as i32).to_be_bytes());
+        shp.extend_from_slice(record);
+    }
+
+    (shp, shx)
+}
+
+// Sizes in header measured in 16-bit words.
+fn write_header(shape_type: i32, size: usize, bounds: &Bounds) -> Vec<u8> {
+    let mut result = Vec::with_capacity(size);
+
+    result.extend_from_slice(&FILE_CODE.to_be_bytes());
+    result.extend_from_slice(&[0; 20]);
+    result.extend_from_slice(&((size / 2) as i32).to_be_bytes());
+    result.extend_from_slice(&VERSION.to_le_bytes());
+    result.extend_from_slice(&shape_type.to_le_bytes());
+    bounds.write(&mut result);
+    result.extend_from_slice(&[0; 32]);
+
+    result
+}
+
+// Polygon rings written closed and clockwise as required for outer rings.
+fn write_record(shape_type: i32, object: &NamesGeometry) -> Vec<u8> {
+    let object_parts: Vec<Vec<&Point>> = parts(object)
+        .into_iter()
+        .filter(|points| !points.is_empty())
+        .map(|points| match object.geometry() {
+            Geometry::MultiLine(_) => points.iter().collect(),
+            Geometry::MultiPolygon(_) => {
+                let mut ring = closed_ring(points);
+
+                if signed_area(&ring) > 0.0 {
+                    ring.reverse();
+                }
+
+                ring
+            }
+        })
+        .collect();
+
+    let mut result = Vec::new();
+
+    if object_parts.is_empty() {
+        result.extend_from_slice(&SHAPE_NULL.to_le_bytes());
+
+        return result;
+    }
+
+    let bounds = Bounds::of(object_parts.iter().flatten().cloned());
+    let points_count: usize = object_parts.iter().map(Vec::len).sum();
+
+    result.extend_from_slice(&shape_type.to_le_bytes());
+    bounds.write(&mut result);
+    result.extend_from_slice(&(object_parts.len() as i32).to_le_bytes());
+    result.extend_from_slice(&(points_count as i32).to_le_bytes());
+
+    let mut start = 0;
+
+    for points in &object_parts {
+        result.extend_from_slice(&(start as i32).to_le_bytes());
+        start += points.len();
+    }
+
+    for point in object_parts.iter().flatten() {
+        result.extend_from_slice(&point.lon().to_le_bytes());
+        result.extend_from_slice(&point.lat().to_le_bytes());
+    }
+
+    result
+}
+
+// Positive for counterclockwise rings.
+fn signed_area(ring: &[&Point]) -> f64 {
+    ring.windows(2)
+        .map(|pair| pair[0].lon() * pair[1].lat() - pair[1].lon() * pair[0].lat())
+        .sum::<f64>()
+        / 2.0
+}
+
+struct Bounds {
+    min_x: f64,
+    min_y: f64,
+    max_x: f64,
+    max_y: f64,
+}
+
+impl Bounds {
+    fn of<'a, I>(points: I) -> Bounds
+    where
+        I: Iterator<Item = &'a Point>,
+    {
+        let mut result: Option<Bounds> = None;
+
+        for point in points {
+            let bounds = result.get_or_insert(Bounds {
+                min_x: point.lon(),
+                min_y: point.lat(),
+                max_x: point.lon(),
+                max_y: point.lat(),
+            });
+
+            bounds.min_x = bounds.min_x.min(point.lon());
+            bounds.min_y = bounds.min_y.min(point.lat());
+            bounds.max_x = bounds.max_x.max(point.lon());
+            bounds.max_y = bounds.max_y.max(point.lat());
+        }
+
+        result.unwrap_or(Bounds {
+            min_x: 0.0,
+            min_y: 0.0,
+            max_x: 0.0,
+            max_y: 0.0,
+        })
+    }
+
+    fn write(&self, result: &mut Vec<u8>) {
+        for value in &[self.min_x, self.min_y, self.max_x, self.max_y] {
+            result.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+}
+
+// Writes dBase III table with `ID`, `NAMES` and attribute columns. Attribute names cut to
+// 10 bytes allowed by the format and made unique.
+fn write_table(objects: &ExportObjects) -> Vec<u8> {
+    let attributes: BTreeSet<&String> = objects
+        .values()
+        .flat_map(|object| object.attributes().keys())
+        .collect();
+    let mut fields = vec![
+        ("ID".to_string(), b'N', ID_FIELD_SIZE),
+        ("NAMES".to_string(), b'C', TEXT_FIELD_SIZE),
+    ];
+    let mut used_names: BTreeSet<String> = fields.iter().map(|field| field.0.clone()).collect();
+
+    for attribute in &attributes {
+        let name = field_name(attribute, &used_names);
+        used_names.insert(name.clone());
+        fields.push((name, b'C', TEXT_FIELD_SIZE));
+    }
+
+    let header_size = 32 + fields.len() * 32 + 1;
+    let record_size = 1 + fields.iter().map(|field| field.2 as usize).sum::<usize>();
+    let today = time::now_utc();
+    let mut result = vec![
+        0x03,
+        today.tm_year as u8,
+        (today.tm_mon + 1) as u8,
+        today.tm_mday as u8,
+    ];
+    result.extend_from_slice(&(objects.len() as u32).to_le_bytes());
+    result.extend_from_slice(&(header_size as u16).to_le_bytes());
+    result.extend_from_slice(&(record_size as u16).to_le_bytes());
+    result.extend_from_slice(&[0; 20]);
+
+    for (name, field_type, size) in &fields {
+        let mut descriptor = [0u8; 32];
+        descriptor[..name.len()].copy_from_slice(name.as_bytes());
+        descriptor[11] = *field_type;
+        descriptor[16] = *size;
+        result.extend_from_slice(&descriptor);
+    }
+
+    result.push(0x0D);
+
+    for (id, object) in objects {
+        result.push(b' ');
+        result.extend_from_slice(
+            format!("{:>width$}", id, width = ID_FIELD_SIZE as usize).as_bytes(),
+        );
+        write_text(&mut result, &object.names().join(", "));
+
+        for attribute in &attributes {
+            let value = object
+                .attributes()
+                .get(*attribute)
+                .map(String::as_str)
+                .unwrap_or_default();
+            write_text(&mut result, value);
+        }
+    }
+
+    result.push(0x1A);
+
+    result
+}
+
+fn field_name(attribute: &str, used_names: &BTreeSet<String>) -> String {
+    let base: String = attribute
+        .chars()
+        .map(|symbol| {
+            if symbol.is_ascii_alphanumeric() {
+                symbol.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .take(FIELD_NAME_SIZE)
+        .collect();
+    let mut name = base.clone();
+    let mut counter = 1;
+
+    while used_names.contains(&name) {
+        let suffix = counter.to_string();
+        let prefix: String = base.chars().take(FIELD_NAME_SIZE - suffix.len()).collect();
+        name = format!("{}{}", prefix, suffix);
+        counter += 1;
+    }
+
+    name
+}
+
+// Text cut on character boundary to fit the field and padded with spaces.
+fn write_text(result: &mut Vec<u8>, text: &str) {
+    let mut end = text.len().min(TEXT_FIELD_SIZE as usize);
+
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+
+    result.extend_from_slice(&text.as_bytes()[..end]);
+    result.resize(result.len() + TEXT_FIELD_SIZE as usize - end, b' ');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::shape_type;
+    use super::write_shapes;
+    use super::write_table;
+    use super::ExportObjects;
+    use super::HEADER_SIZE;
+    use super::SHAPE_POLYGON;
+    use super::SHAPE_POLYLINE;
+    use crate::database::Attributes;
+    use crate::database::Geometry;
+    use crate::database::Point;
+    use std::convert::TryInto;
+
+    fn points(coordinates: &[(f64, f64)]) -> Vec<Point> {
+        coordinates
+            .iter()
+            .map(|&(lat, lon)| Point::new(lat, lon))
+            .collect()
+    }
+
+    fn objects(geometries: Vec<(Vec<&str>, Geometry)>) -> ExportObjects {
+        geometries
+            .into_iter()
+            .enumerate()
+            .map(|(index, (names, geometry))| {
+                let names = names.into_iter().map(String::from).collect();
+                let mut attributes = Attributes::new();
+
+                if index == 0 {
+                    attributes.insert("road class".into(), "primary".into());
+                }
+
+                (index as i64 + 1, (names, attributes, geometry).into())
+            })
+            .collect()
+    }
+
+    fn lines() -> ExportObjects {
+        objects(vec![
+            (
+                vec!["A"],
+                Geometry::MultiLine(
+                    vec![
+                        points(&[(55.0, 37.0), (55.1, 37.1)]),
+                        points(&[(55.2, 37.2), (55.3, 37.3), (55.4, 37.4)]),
+                    ]
+                    .into(),
+                ),
+            ),
+            (
+                vec!["B", "C"],
+                Geometry::MultiLine(vec![points(&[(54.0, 36.0), (54.5, 36.5)])].into()),
+            ),
+        ])
+    }
+
+    fn be_i32(bytes: &[u8], offset: usize) -> i32 {
+        i32::from_be_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn le_i32(bytes: &[u8], offset: usize) -> i32 {
+        i32::from_le_bytes(bytes[offset..offset + 4].try_into().unwrap())
+    }
+
+    fn le_f64(bytes: &[u8], offset: usize) -> f64 {
+        f64::from_le_bytes(bytes[offset..offset + 8].try_into().unwrap())
+    }
+
+    #[test]
+    fn writes_shape_headers_and_index() {
+        let objects = lines();
+        let (shp, shx) = write_shapes(shape_type(&objects).unwrap(), &objects);
+        // Type, bounds, parts and points counts, part starts and points
+        let first_size = 4 + 32 + 8 + 2 * 4 + 5 * 16;
+        let second_size = 4 + 32 + 8 + 4 + 2 * 16;
+
+        assert_eq!(shp.len(), HEADER_SIZE + 8 + first_size + 8 + second_size);
+        assert_eq!(shx.len(), HEADER_SIZE + 2 * 8);
+
+        for file in &[&shp, &shx] {
+            assert_eq!(be_i32(file, 0), 9994);
+            assert_eq!(be_i32(file, 24) as usize * 2, file.len());
+            assert_eq!(le_i32(file, 28), 1000);
+            assert_eq!(le_i32(file, 32), SHAPE_POLYLINE);
+            assert_eq!(le_f64(file, 36), 36.0);
+            assert_eq!(le_f64(file, 44), 54.0);
+            assert_eq!(le_f64(file, 52), 37.4);
+            assert_eq!(le_f64(file, 60), 55.4);
+        }
+
+        let records = [(1, first_size), (2, second_size)];
+        let mut offset = HEADER_SIZE;
+
+        for (index, &(number, size)) in records.iter().enumerate() {
+            let entry = HEADER_SIZE + index * 8;
+
+            assert_eq!(be_i32(&shx, entry) as usize * 2, offset);
+            assert_eq!(be_i32(&shx, entry + 4) as usize * 2, size);
+            assert_eq!(be_i32(&shp, offset), number);
+            assert_eq!(be_i32(&shp, offset + 4) as usize * 2, size);
+            assert_eq!(le_i32(&shp, offset + 8), SHAPE_POLYLINE);
+
+            offset += 8 + size;
+        }
+
+        // Parts of the first record start at points 0 and 2, first point is longitude first
+        assert_eq!(le_i32(&shp, HEADER_SIZE + 8 + 36), 2);
+        assert_eq!(le_i32(&shp, HEADER_SIZE + 8 + 40), 5);
+        assert_eq!(le_i32(&shp, HEADER_SIZE + 8 + 44), 0);
+        assert_eq!(le_i32(&shp, HEADER_SIZE + 8 + 48), 2);
+        assert_eq!(le_f64(&shp, HEADER_SIZE + 8 + 52), 37.0);
+        assert_eq!(le_f64(&shp, HEADER_SIZE + 8 + 60), 55.0);
+    }
+
+    #[test]
+    fn writes_polygon_rings_closed_and_clockwise() {
+        let objects = objects(vec![(
+            vec!["A"],
+            Geometry::MultiPolygon(
+                vec![points(&[(55.0, 37.0), (55.0, 37.1), (55.1, 37.1)])].into(),
+            ),
+        )]);
+        let (shp, _) = write_shapes(shape_type(&objects).unwrap(), &objects);
+        let record = HEADER_SIZE + 8;
+        let coordinates: Vec<(f64, f64)> = (0..4)
+            .map(|index| {
+                let offset = record + 48 + index * 16;
+                (le_f64(&shp, offset + 8), le_f64(&shp, offset))
+            })
+            .collect();
+
+        assert_eq!(le_i32(&shp, record), SHAPE_POLYGON);
+        assert_eq!(le_i32(&shp, record + 40), 4);
+        assert_eq!(
+            coordinates,
+            vec![(55.0, 37.0), (55.1, 37.1), (55.0, 37.1), (55.0, 37.0)]
+        );
+    }
+
+    #[test]
+    fn rejects_lines_with_polygons() {
+        let objects = objects(vec![
+            (
+                vec!["A"],
+                Geometry::MultiLine(vec![points(&[(55.0, 37.0), (55.1, 37.1)])].into()),
+            ),
+            (
+                vec!["B"],
+                Geometry::MultiPolygon(
+                    vec![points(&[(55.0, 37.0), (55.0, 37.1), (55.1, 37.1)])].into(),
+                ),
+            ),
+        ]);
+
+        assert!(shape_type(&objects).is_err());
+    }
+
+    #[test]
+    fn writes_table_fields_of_fixed_width() {
+        let table = write_table(&lines());
+        let header_size = 32 + 3 * 32 + 1;
+        let record_size = 1 + 20 + 254 + 254;
+        let field = |index: usize| &table[32 + index * 32..64 + index * 32];
+
+        assert_eq!(table[0], 0x03);
+        assert_eq!(&table[4..8], &2u32.to_le_bytes());
+        assert_eq!(&table[8..10], &(header_size as u16).to_le_bytes());
+        assert_eq!(&table[10..12], &(record_size as u16).to_le_bytes());
+        assert_eq!(table.len(), header_size + 2 * record_size + 1);
+        assert_eq!(table[header_size - 1], 0x0D);
+        assert_eq!(table[table.len() - 1], 0x1A);
+
+        for (index, &(name, field_type, width)) in [
+            ("ID", b'N', 20),
+            ("NAMES", b'C', 254),
+            ("ROAD_CLASS", b'C', 254),
+        ]
+        .iter()
+        .enumerate()
+        {
+            assert_eq!(&field(index)[..name.len()], name.as_bytes());
+            assert!(field(index)[name.len()..11].iter().all(|&byte| byte == 0));
+            assert_eq!(field(index)[11], field_type);
+            assert_eq!(field(index)[16], width);
+        }
+
+        let second = &table[header_size + record_size..header_size + 2 * record_size];
+
+        assert_eq!(second[0], b' ');
+        assert_eq!(&second[1..21], format!("{:>20}", 2).as_bytes());
+        assert_eq!(&second[21..25], b"B, C");
+        assert!(second[25..].iter().all(|&byte| byte == b' '));
+    }
+}
@@ -0,0 +1,49 @@
+use super::closed_ring;
+use super::parts;
+use super::ExportObjects;
+use super::ExportResult;
+use crate::database::Geometry;
+use crate::database::NamesGeometry;
+use crate::database::Point;
+use std::fmt::Write;
+
+// One object per line - identifier and geometry separated with tab.
+pub fn write(objects: &ExportObjects) -> ExportResult<Vec<u8>> {
+    let mut result = String::new();
+
+    for (id, object) in objects {
+        let _ = writeln!(result, "{}\t{}", id, geometry_wkt(object));
+    }
+
+    Ok(result.into_bytes())
+}
+
+pub fn geometry_wkt(object: &NamesGeometry) -> String {
+    let parts = parts(object);
+
+    match object.geometry() {
+        Geometry::MultiLine(_) => format!(
+            "MULTILINESTRING ({})",
+            parts
+                .into_iter()
+                .map(|points| format!("({})", coordinates(points.iter())))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Geometry::MultiPolygon(_) => format!(
+            "MULTIPOLYGON ({})",
+            parts
+                .into_iter()
+                .map(|points| format!("(({}))", coordinates(closed_ring(points).into_iter())))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+fn coordinates<'a>(points: impl Iterator<Item = &'a Point>) -> String {
+    points
+        .map(|point| format!("{} {}", point.lon(), point.lat()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,202 @@
+use crate::config::quote_identifier;
+use crate::database::GeometryTable;
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+
+const UNKNOWN_SRID: i32 = 0;
+const WGS84_SRID: i32 = 4326;
+
+// Preferred names of name columns, other columns containing `name` used next
+const NAME_COLUMNS: [&str; 4] = ["name", "title", "label", "description"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FormatProposal {
+    format_type: &'static str,
+    names_query: String,
+    geometry_query: String,
+    tile_query: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TableProposal {
+    #[serde(flatten)]
+    table: GeometryTable,
+    name: String,
+    name_column: Option<String>,
+    format: Option<FormatProposal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<&'static str>,
+}
+
+pub fn propose_formats(tables: Vec<GeometryTable>) -> Vec<TableProposal> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for table in &tables {
+        *counts.entry(format_name(table)).or_default() += 1;
+    }
+
+    tables
+        .into_iter()
+        .map(|table| {
+            let mut proposal = TableProposal::new(table);
+
+            if counts[&proposal.name] > 1 {
+                proposal.name = format!("{}.{}", proposal.name, proposal.table.column());
+            }
+
+            proposal
+        })
+        .collect()
+}
+
+impl TableProposal {
+    fn new(table: GeometryTable) -> TableProposal {
+        let name_column = guess_name_column(&table).map(String::from);
+        let (format, reason) = match table.primary_key() {
+            _ if table.geometry_type().to_uppercase().contains("POINT") => {
+                (None, Some("Points can't be shown by Wkt format"))
+            }
+            Some(key) => (
+                Some(propose_format(&table, key, name_column.as_deref())),
+                None,
+            ),
+            None => (None, Some("Table has no integer primary key")),
+        };
+
+        TableProposal {
+            name: format_name(&table),
+            table,
+            name_column,
+            format,
+            reason,
+        }
+    }
+
+    pub fn table(&self) -> &GeometryTable {
+        &self.table
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn format(&self) -> Option<&FormatProposal> {
+        self.format.as_ref()
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        self.reason
+    }
+
+    pub fn reference(&self) -> String {
+        format!(
+            "{}.{}.{}",
+            self.table.schema(),
+            self.table.table(),
+            self.table.column()
+        )
+    }
+
+    pub fn matches(&self, reference: &str) -> bool {
+        let table = &self.table;
+
+        reference == self.reference()
+            || reference == format!("{}.{}", table.schema(), table.table())
+            || reference == table.table()
+    }
+}
+
+pub fn formats_yaml(proposals: &[&TableProposal]) -> Result<String, String> {
+    let formats: BTreeMap<_, _> = proposals
+        .iter()
+        .filter_map(|proposal| Some((proposal.name(), proposal.format()?)))
+        .collect();
+    let mut sections = BTreeMap::new();
+    sections.insert("formats", formats);
+
+    serde_yaml::to_string(&sections).map_err(|error| format!("{}", error))
+}
+
+fn propose_format(table: &GeometryTable, key: &str, name_column: Option<&str>) -> FormatProposal {
+    let relation = format!(
+        "{}.{}",
+        quote_identifier(table.schema()),
+        quote_identifier(table.table())
+    );
+    let key = quote_identifier(key);
+    let column = quote_identifier(table.column());
+    let name = match name_column {
+        Some(name_column) => quote_identifier(name_column),
+        None => key.clone(),
+    };
+    let (geometry, envelope) = if table.geography() {
+        (
+            format!("{}::geometry", column),
+            "ST_MakeEnvelope( $1, $2, $3, $4, 4326 )::geography".to_string(),
+        )
+    } else if table.srid() == UNKNOWN_SRID {
+        // Coordinates of geometry without SRID expected to be degrees
+        (
+            column.clone(),
+            "ST_MakeEnvelope( $1, $2, $3, $4 )".to_string(),
+        )
+    } else if table.srid() == WGS84_SRID {
+        (
+            column.clone(),
+            "ST_MakeEnvelope( $1, $2, $3, $4, 4326 )".to_string(),
+        )
+    } else {
+        (
+            format!("ST_Transform( {}, 4326 )", column),
+            format!(
+                "ST_Transform( ST_MakeEnvelope( $1, $2, $3, $4, 4326 ), {} )",
+                table.srid()
+            ),
+        )
+    };
+
+    FormatProposal {
+        format_type: "Wkt",
+        names_query: format!(
+            "select {0}::bigint, {1}::text from {2} where {0}::bigint = any( $1 ) ;",
+            key, name, relation
+        ),
+        geometry_query: format!(
+            "select {0}::bigint, ST_AsText( {1} ) from {2} where {0}::bigint = any( $1 ) ;",
+            key, geometry, relation
+        ),
+        tile_query: format!(
+            "select {0}::bigint, ST_AsText( {1} ) from {2} where {3} && {4} ;",
+            key, geometry, relation, column, envelope
+        ),
+    }
+}
+
+// Tables of public schema named by table name only
+fn format_name(table: &GeometryTable) -> String {
+    if table.schema() == "public" {
+        table.table().to_string()
+    } else {
+        format!("{}.{}", table.schema(), table.table())
+    }
+}
+
+// Text columns only, geometry column stored as text is not a name
+fn guess_name_column(table: &GeometryTable) -> Option<&str> {
+    let columns: Vec<_> = table
+        .text_columns()
+        .iter()
+        .filter(|column| *column != table.column())
+        .collect();
+
+    NAME_COLUMNS
+        .iter()
+        .find_map(|name| columns.iter().find(|column| column.to_lowercase() == *name))
+        .or_else(|| {
+            columns
+                .iter()
+                .find(|column| column.to_lowercase().contains("name"))
+        })
+        .or_else(|| columns.first())
+        .map(|column| column.as_str())
+}
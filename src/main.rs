@@ -3,15 +3,34 @@ extern crate log;
 #[macro_use]
 extern crate serde_derive;
 
+mod audit;
+mod auth;
 mod backend;
+mod cache;
 mod config;
 mod database;
+mod diff;
+mod encoding;
 mod error;
+mod export;
+mod introspect;
+mod metrics;
+mod mvt;
+mod query;
+mod render;
 mod settings;
+mod share;
+mod tiles;
+mod validation;
 
+use crate::auth::Auth;
 use crate::backend::start_backend;
 use crate::error::ApplicationError;
 use crate::error::ApplicationResult;
+use crate::query::run_introspect;
+use crate::query::run_query;
+use crate::query::run_render;
+use crate::settings::Command;
 use crate::settings::Settings;
 
 fn main() -> ApplicationResult {
@@ -21,7 +40,17 @@ fn main() -> ApplicationResult {
     let config_path = settings.config_path();
     let config = config::load(config_path).map_err(ApplicationError::load_config_error)?;
 
-    start_backend(config, settings.bind_address(), settings.bind_port());
+    match settings.command() {
+        Command::Serve => {
+            let auth =
+                Auth::from_config(config.clone()).map_err(ApplicationError::load_config_error)?;
 
-    Ok(())
+            start_backend(config, auth, settings.bind_address(), settings.bind_port());
+
+            Ok(())
+        }
+        Command::Query(query) => run_query(&config, query),
+        Command::Render(query) => run_render(&config, query),
+        Command::Introspect(introspect) => run_introspect(&config, introspect),
+    }
 }
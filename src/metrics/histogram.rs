@@ -0,0 +1,55 @@
+use std::fmt::Result as FmtResult;
+use std::fmt::Write;
+
+const BUCKETS: [f64; 12] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0,
+];
+
+#[derive(Debug, Clone, Default)]
+pub struct Histogram {
+    counts: [u64; BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Histogram {
+    pub fn observe(&mut self, value: f64) {
+        for (bucket, count) in BUCKETS.iter().zip(self.counts.iter_mut()) {
+            if value <= *bucket {
+                *count += 1;
+            }
+        }
+
+        self.count += 1;
+        self.sum += value;
+    }
+
+    pub fn write<W>(&self, writer: &mut W, name: &str, labels: &str) -> FmtResult
+    where
+        W: Write,
+    {
+        let separator = if labels.is_empty() { "" } else { "," };
+
+        for (bucket, count) in BUCKETS.iter().zip(self.counts.iter()) {
+            writeln!(
+                writer,
+                "{}_bucket{{{}{}le=\"{}\"}} {}",
+                name, labels, separator, bucket, count
+            )?;
+        }
+
+        writeln!(
+            writer,
+            "{}_bucket{{{}{}le=\"+Inf\"}} {}",
+            name, labels, separator, self.count
+        )?;
+        let labels = if labels.is_empty() {
+            String::new()
+        } else {
+            format!("{{{}}}", labels)
+        };
+
+        writeln!(writer, "{}_sum{} {}", name, labels, self.sum)?;
+        writeln!(writer, "{}_count{} {}", name, labels, self.count)
+    }
+}
@@ -0,0 +1,46 @@
+use super::MetricsRef;
+use iron::Handler;
+use iron::IronResult;
+use iron::Request;
+use iron::Response;
+use std::time::Instant;
+
+pub struct Instrumented<H> {
+    name: &'static str,
+    handler: H,
+    metrics: MetricsRef,
+}
+
+impl<H> Instrumented<H>
+where
+    H: Handler,
+{
+    pub fn new(name: &'static str, handler: H, metrics: MetricsRef) -> Instrumented<H> {
+        Instrumented {
+            name,
+            handler,
+            metrics,
+        }
+    }
+}
+
+impl<H> Handler for Instrumented<H>
+where
+    H: Handler,
+{
+    fn handle(&self, request: &mut Request) -> IronResult<Response> {
+        let started = Instant::now();
+        self.metrics.request_started();
+
+        let result = self.handler.handle(request);
+        let status = match result {
+            Ok(ref response) => response.status,
+            Err(ref error) => error.response.status,
+        };
+        let status = status.map_or(200, |status| status.to_u16());
+        self.metrics
+            .request_finished(self.name, status, started.elapsed());
+
+        result
+    }
+}
@@ -0,0 +1,323 @@
+mod histogram;
+mod instrumented;
+
+use self::histogram::Histogram;
+pub use self::instrumented::Instrumented;
+
+use std::collections::BTreeMap;
+use std::fmt::Result as FmtResult;
+use std::fmt::Write;
+use std::sync::atomic::AtomicI64;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub type MetricsRef = Arc<Metrics>;
+
+#[derive(Default)]
+struct MetricsData {
+    requests: BTreeMap<(String, u16), u64>,
+    request_durations: BTreeMap<String, Histogram>,
+    format_requests: BTreeMap<(String, bool), u64>,
+    format_durations: BTreeMap<String, Histogram>,
+    database_connect: Histogram,
+    database_query: Histogram,
+    rows: BTreeMap<(String, &'static str), u64>,
+    points: BTreeMap<String, u64>,
+    database_errors: BTreeMap<&'static str, u64>,
+    cache_hits: BTreeMap<String, u64>,
+    cache_misses: BTreeMap<String, u64>,
+}
+
+#[derive(Default)]
+pub struct Metrics {
+    data: Mutex<MetricsData>,
+    active_requests: AtomicI64,
+    active_connections: AtomicI64,
+}
+
+impl Metrics {
+    pub fn new() -> Metrics {
+        Metrics::default()
+    }
+
+    pub fn request_started(&self) {
+        self.active_requests.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn request_finished(&self, handler: &str, status: u16, duration: Duration) {
+        self.active_requests.fetch_sub(1, Ordering::SeqCst);
+
+        let mut data = self.data.lock().unwrap();
+        *data.requests.entry((handler.into(), status)).or_insert(0) += 1;
+        data.request_durations
+            .entry(handler.into())
+            .or_default()
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn format_request(&self, format: &str, success: bool, duration: Duration) {
+        let mut data = self.data.lock().unwrap();
+        *data
+            .format_requests
+            .entry((format.into(), success))
+            .or_insert(0) += 1;
+        data.format_durations
+            .entry(format.into())
+            .or_default()
+            .observe(duration.as_secs_f64());
+    }
+
+    pub fn connection_opened(&self) {
+        self.active_connections.fetch_add(1, Ordering::SeqCst);
+    }
+
+    pub fn connection_closed(&self) {
+        self.active_connections.fetch_sub(1, Ordering::SeqCst);
+    }
+
+    pub fn database_query(
+        &self,
+        format: &str,
+        connect_time: Duration,
+        query_time: Duration,
+        names_rows: usize,
+        geometry_rows: usize,
+    ) {
+        let mut data = self.data.lock().unwrap();
+        data.database_connect.observe(connect_time.as_secs_f64());
+        data.database_query.observe(query_time.as_secs_f64());
+        *data.rows.entry((format.into(), "names")).or_insert(0) += names_rows as u64;
+        *data.rows.entry((format.into(), "geometry")).or_insert(0) += geometry_rows as u64;
+    }
+
+    pub fn points_serialized(&self, format: &str, points: usize) {
+        let mut data = self.data.lock().unwrap();
+        *data.points.entry(format.into()).or_insert(0) += points as u64;
+    }
+
+    pub fn database_error(&self, kind: &'static str) {
+        let mut data = self.data.lock().unwrap();
+        *data.database_errors.entry(kind).or_insert(0) += 1;
+    }
+
+    pub fn cache_lookup(&self, format: &str, hits: usize, misses: usize) {
+        let mut data = self.data.lock().unwrap();
+        *data.cache_hits.entry(format.into()).or_insert(0) += hits as u64;
+        *data.cache_misses.entry(format.into()).or_insert(0) += misses as u64;
+    }
+
+    // Renders all metrics in Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut result = String::new();
+
+        if let Err(error) = self.write(&mut result) {
+            warn!("Failed to render metrics - {}", error);
+        }
+
+        result
+    }
+
+    fn write<W>(&self, writer: &mut W) -> FmtResult
+    where
+        W: Write,
+    {
+        let data = self.data.lock().unwrap();
+
+        writeln!(
+            writer,
+            "# HELP show_on_map_requests_total HTTP requests by handler and status."
+        )?;
+        writeln!(writer, "# TYPE show_on_map_requests_total counter")?;
+
+        for ((handler, status), count) in &data.requests {
+            writeln!(
+                writer,
+                "show_on_map_requests_total{{handler=\"{}\",status=\"{}\"}} {}",
+                handler, status, count
+            )?;
+        }
+
+        writeln!(
+            writer,
+            "# HELP show_on_map_request_duration_seconds HTTP request duration by handler."
+        )?;
+        writeln!(
+            writer,
+            "# TYPE show_on_map_request_duration_seconds histogram"
+        )?;
+
+        for (handler, histogram) in &data.request_durations {
+            histogram.write(
+                writer,
+                "show_on_map_request_duration_seconds",
+                &format!("handler=\"{}\"", handler),
+            )?;
+        }
+
+        writeln!(
+            writer,
+            "# HELP show_on_map_format_requests_total Object requests by format and outcome."
+        )?;
+        writeln!(writer, "# TYPE show_on_map_format_requests_total counter")?;
+
+        for ((format, success), count) in &data.format_requests {
+            let outcome = if *success { "success" } else { "error" };
+
+            writeln!(
+                writer,
+                "show_on_map_format_requests_total{{format=\"{}\",outcome=\"{}\"}} {}",
+                escape(format),
+                outcome,
+                count
+            )?;
+        }
+
+        writeln!(
+            writer,
+            "# HELP show_on_map_format_request_duration_seconds Object request duration by format."
+        )?;
+        writeln!(
+            writer,
+            "# TYPE show_on_map_format_request_duration_seconds histogram"
+        )?;
+
+        for (format, histogram) in &data.format_durations {
+            histogram.write(
+                writer,
+                "show_on_map_format_request_duration_seconds",
+                &format!("format=\"{}\"", escape(format)),
+            )?;
+        }
+
+        writeln!(
+            writer,
+            "# HELP show_on_map_database_connect_seconds Database connection duration."
+        )?;
+        writeln!(
+            writer,
+            "# TYPE show_on_map_database_connect_seconds histogram"
+        )?;
+        data.database_connect
+            .write(writer, "show_on_map_database_connect_seconds", "")?;
+
+        writeln!(
+            writer,
+            "# HELP show_on_map_database_query_seconds Database queries duration."
+        )?;
+        writeln!(
+            writer,
+            "# TYPE show_on_map_database_query_seconds histogram"
+        )?;
+        data.database_query
+            .write(writer, "show_on_map_database_query_seconds", "")?;
+
+        writeln!(
+            writer,
+            "# HELP show_on_map_rows_fetched_total Rows fetched from database by format and query."
+        )?;
+        writeln!(writer, "# TYPE show_on_map_rows_fetched_total counter")?;
+
+        for ((format, query), count) in &data.rows {
+            writeln!(
+                writer,
+                "show_on_map_rows_fetched_total{{format=\"{}\",query=\"{}\"}} {}",
+                escape(format),
+                query,
+                count
+            )?;
+        }
+
+        writeln!(
+            writer,
+            "# HELP show_on_map_points_serialized_total Geometry points sent to clients by format."
+        )?;
+        writeln!(writer, "# TYPE show_on_map_points_serialized_total counter")?;
+
+        for (format, count) in &data.points {
+            writeln!(
+                writer,
+                "show_on_map_points_serialized_total{{format=\"{}\"}} {}",
+                escape(format),
+                count
+            )?;
+        }
+
+        writeln!(
+            writer,
+            "# HELP show_on_map_database_errors_total Database errors by kind."
+        )?;
+        writeln!(writer, "# TYPE show_on_map_database_errors_total counter")?;
+
+        for (kind, count) in &data.database_errors {
+            writeln!(
+                writer,
+                "show_on_map_database_errors_total{{kind=\"{}\"}} {}",
+                kind, count
+            )?;
+        }
+
+        writeln!(
+            writer,
+            "# HELP show_on_map_cache_hits_total Objects taken from cache by format."
+        )?;
+        writeln!(writer, "# TYPE show_on_map_cache_hits_total counter")?;
+
+        for (format, count) in &data.cache_hits {
+            writeln!(
+                writer,
+                "show_on_map_cache_hits_total{{format=\"{}\"}} {}",
+                escape(format),
+                count
+            )?;
+        }
+
+        writeln!(
+            writer,
+            "# HELP show_on_map_cache_misses_total Objects not found in cache by format."
+        )?;
+        writeln!(writer, "# TYPE show_on_map_cache_misses_total counter")?;
+
+        for (format, count) in &data.cache_misses {
+            writeln!(
+                writer,
+                "show_on_map_cache_misses_total{{format=\"{}\"}} {}",
+                escape(format),
+                count
+            )?;
+        }
+
+        writeln!(
+            writer,
+            "# HELP show_on_map_active_requests HTTP requests in progress."
+        )?;
+        writeln!(writer, "# TYPE show_on_map_active_requests gauge")?;
+        writeln!(
+            writer,
+            "show_on_map_active_requests {}",
+            self.active_requests.load(Ordering::SeqCst)
+        )?;
+
+        writeln!(
+            writer,
+            "# HELP show_on_map_active_database_connections Open database connections."
+        )?;
+        writeln!(
+            writer,
+            "# TYPE show_on_map_active_database_connections gauge"
+        )?;
+        writeln!(
+            writer,
+            "show_on_map_active_database_connections {}",
+            self.active_connections.load(Ordering::SeqCst)
+        )
+    }
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -0,0 +1,623 @@
+mod protobuf;
+
+use self::protobuf::Writer;
+use crate::database::Geometry;
+use crate::database::NamesGeometry;
+use crate::database::Point;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+
+const MAX_ZOOM: u32 = 24;
+
+const VERSION: u64 = 2;
+const EXTENT: u32 = 4096;
+// Geometry clipped to tile extent with buffer to hide clipping edges of lines and polygons
+const BUFFER: f64 = 64.0;
+const MAX_LATITUDE: f64 = 85.051_128_78;
+
+// Fields of Tile, Layer, Feature and Value messages
+const TILE_LAYERS: u32 = 3;
+const LAYER_NAME: u32 = 1;
+const LAYER_FEATURES: u32 = 2;
+const LAYER_KEYS: u32 = 3;
+const LAYER_VALUES: u32 = 4;
+const LAYER_EXTENT: u32 = 5;
+const LAYER_VERSION: u32 = 15;
+const FEATURE_ID: u32 = 1;
+const FEATURE_TAGS: u32 = 2;
+const FEATURE_TYPE: u32 = 3;
+const FEATURE_GEOMETRY: u32 = 4;
+const VALUE_STRING: u32 = 1;
+const VALUE_INT: u32 = 4;
+
+const TYPE_LINESTRING: u64 = 2;
+const TYPE_POLYGON: u64 = 3;
+
+const COMMAND_MOVE_TO: u32 = 1;
+const COMMAND_LINE_TO: u32 = 2;
+const COMMAND_CLOSE_PATH: u32 = 7;
+
+// Property keys, values of feature stored in the same order
+const KEYS: [&str; 2] = ["id", "names"];
+
+/// Tile in XYZ scheme, `y` grows to the south.
+#[derive(Debug, Clone, Copy)]
+pub struct TileCoordinates {
+    z: u32,
+    x: u32,
+    y: u32,
+}
+
+impl TileCoordinates {
+    pub fn new(z: u32, x: u32, y: u32) -> Option<TileCoordinates> {
+        if z > MAX_ZOOM || x >= 1 << z || y >= 1 << z {
+            None
+        } else {
+            Some(TileCoordinates { z, x, y })
+        }
+    }
+
+    pub fn z(&self) -> u32 {
+        self.z
+    }
+
+    pub fn x(&self) -> u32 {
+        self.x
+    }
+
+    pub fn y(&self) -> u32 {
+        self.y
+    }
+
+    pub fn bounds(&self) -> [f64; 4] {
+        let size = f64::from(1u32 << self.z);
+        let lon = |x: u32| f64::from(x) / size * 360.0 - 180.0;
+        let lat = |y: u32| {
+            (PI * (1.0 - 2.0 * f64::from(y) / size))
+                .sinh()
+                .atan()
+                .to_degrees()
+        };
+
+        [lon(self.x), lat(self.y + 1), lon(self.x + 1), lat(self.y)]
+    }
+
+    // Point in tile coordinates, origin in the top left corner
+    fn project(&self, point: &Point) -> (f64, f64) {
+        let size = f64::from(1u32 << self.z);
+        let lat = point.lat().clamp(-MAX_LATITUDE, MAX_LATITUDE).to_radians();
+        let x = (point.lon() + 180.0) / 360.0 * size - f64::from(self.x);
+        let y = (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0 * size - f64::from(self.y);
+        let extent = f64::from(EXTENT);
+
+        (x * extent, y * extent)
+    }
+}
+
+/// Encodes objects to Mapbox Vector Tile with single layer. Features have `id` and `names`
+/// (joined with comma) properties, objects with negative ids have no feature id.
+pub fn encode(
+    layer: &str,
+    tile: TileCoordinates,
+    objects: &HashMap<i64, NamesGeometry>,
+) -> Vec<u8> {
+    let mut ids: Vec<i64> = objects.keys().cloned().collect();
+    ids.sort_unstable();
+
+    let mut features = Vec::new();
+    let mut values = Vec::new();
+
+    for id in ids {
+        let object = &objects[&id];
+        let (geometry_type, geometry) = match object.geometry() {
+            Geometry::MultiLine(lines) => (
+                TYPE_LINESTRING,
+                encode_parts(&tile, lines.lines().iter().map(|line| line.points()), false),
+            ),
+            Geometry::MultiPolygon(polygons) => (
+                TYPE_POLYGON,
+                encode_parts(
+                    &tile,
+                    polygons.polygons().iter().map(|polygon| polygon.points()),
+                    true,
+                ),
+            ),
+        };
+
+        // Objects without visible parts skipped
+        if geometry.is_empty() {
+            continue;
+        }
+
+        let value_index = values.len() as u32;
+        let mut feature = Writer::default();
+
+        if id >= 0 {
+            feature.uint(FEATURE_ID, id as u64);
+        }
+
+        feature.packed(FEATURE_TAGS, &[0, value_index, 1, value_index + 1]);
+        feature.uint(FEATURE_TYPE, geometry_type);
+        feature.packed(FEATURE_GEOMETRY, &geometry);
+        features.push(feature);
+
+        let mut id_value = Writer::default();
+        id_value.int(VALUE_INT, id);
+        values.push(id_value);
+
+        let mut names_value = Writer::default();
+        names_value.string(VALUE_STRING, &object.names().join(", "));
+        values.push(names_value);
+    }
+
+    let mut layer_writer = Writer::default();
+    layer_writer.uint(LAYER_VERSION, VERSION);
+    layer_writer.string(LAYER_NAME, layer);
+
+    for feature in features {
+        layer_writer.message(LAYER_FEATURES, feature);
+    }
+
+    for key in KEYS.iter() {
+        layer_writer.string(LAYER_KEYS, key);
+    }
+
+    for value in values {
+        layer_writer.message(LAYER_VALUES, value);
+    }
+
+    layer_writer.uint(LAYER_EXTENT, u64::from(EXTENT));
+
+    let mut writer = Writer::default();
+    writer.message(TILE_LAYERS, layer_writer);
+
+    writer.into_bytes()
+}
+
+// Geometry commands of lines or polygon rings. Repeated points removed, lines need at least two
+// points and rings at least three points. Rings oriented clockwise as required for exterior rings.
+fn encode_parts<'a>(
+    tile: &TileCoordinates,
+    parts: impl Iterator<Item = &'a [Point]>,
+    rings: bool,
+) -> Vec<u32> {
+    let mut result = Vec::new();
+    let mut cursor = (0, 0);
+
+    let clipped = parts.flat_map(|points| {
+        let projected = points.iter().map(|point| tile.project(point)).collect();
+
+        if rings {
+            vec![clip_ring(projected)]
+        } else {
+            clip_line(&projected)
+        }
+    });
+
+    for points in clipped {
+        let mut coordinates: Vec<(i32, i32)> = Vec::with_capacity(points.len());
+
+        // Clipped coordinates are within buffered extent and fit in i32
+        for (x, y) in points {
+            let coordinate = (x.round() as i32, y.round() as i32);
+
+            if coordinates.last() != Some(&coordinate) {
+                coordinates.push(coordinate);
+            }
+        }
+
+        if rings {
+            if coordinates.len() > 1 && coordinates.first() == coordinates.last() {
+                coordinates.pop();
+            }
+
+            let area = ring_area(&coordinates);
+
+            if coordinates.len() < 3 || area == 0 {
+                continue;
+            } else if area < 0 {
+                coordinates.reverse();
+            }
+        } else if coordinates.len() < 2 {
+            continue;
+        }
+
+        result.push(command(COMMAND_MOVE_TO, 1));
+
+        for (index, &(x, y)) in coordinates.iter().enumerate() {
+            if index == 1 {
+                result.push(command(COMMAND_LINE_TO, coordinates.len() as u32 - 1));
+            }
+
+            result.push(zigzag(x - cursor.0));
+            result.push(zigzag(y - cursor.1));
+            cursor = (x, y);
+        }
+
+        if rings {
+            result.push(command(COMMAND_CLOSE_PATH, 1));
+        }
+    }
+
+    result
+}
+
+// Parts of the line inside buffered extent, line leaving and entering extent again is split
+fn clip_line(points: &[(f64, f64)]) -> Vec<Vec<(f64, f64)>> {
+    let mut result = Vec::new();
+    let mut line = Vec::new();
+
+    for segment in points.windows(2) {
+        let (start, end) = match clip_segment(segment[0], segment[1]) {
+            Some(clipped) => clipped,
+            None => continue,
+        };
+
+        if line.last() != Some(&start) {
+            if line.len() > 1 {
+                result.push(line);
+            }
+
+            line = vec![start];
+        }
+
+        line.push(end);
+    }
+
+    if line.len() > 1 {
+        result.push(line);
+    }
+
+    result
+}
+
+// Liang-Barsky clipping, points inside extent kept as they are
+fn clip_segment(start: (f64, f64), end: (f64, f64)) -> Option<((f64, f64), (f64, f64))> {
+    let (min, max) = (-BUFFER, f64::from(EXTENT) + BUFFER);
+    let (dx, dy) = (end.0 - start.0, end.1 - start.1);
+    let mut t0: f64 = 0.0;
+    let mut t1: f64 = 1.0;
+
+    for &(p, q) in &[
+        (-dx, start.0 - min),
+        (dx, max - start.0),
+        (-dy, start.1 - min),
+        (dy, max - start.1),
+    ] {
+        if p == 0.0 {
+            if q < 0.0 {
+                return None;
+            }
+        } else if p < 0.0 {
+            t0 = t0.max(q / p);
+        } else {
+            t1 = t1.min(q / p);
+        }
+    }
+
+    if t0 > t1 {
+        return None;
+    }
+
+    let point = |t: f64| (start.0 + t * dx, start.1 + t * dy);
+    let clipped_start = if t0 > 0.0 { point(t0) } else { start };
+    let clipped_end = if t1 < 1.0 { point(t1) } else { end };
+
+    Some((clipped_start, clipped_end))
+}
+
+// Sutherland-Hodgman clipping by every edge of buffered extent
+fn clip_ring(points: Vec<(f64, f64)>) -> Vec<(f64, f64)> {
+    let (min, max) = (-BUFFER, f64::from(EXTENT) + BUFFER);
+    // Edges given by coordinate (x or y), bound and side of points inside
+    let edges = [
+        (0, min, true),
+        (0, max, false),
+        (1, min, true),
+        (1, max, false),
+    ];
+    let mut ring = points;
+
+    for &(axis, bound, lower) in edges.iter() {
+        let coordinate = |point: &(f64, f64)| if axis == 0 { point.0 } else { point.1 };
+        let inside = |point: &(f64, f64)| {
+            if lower {
+                coordinate(point) >= bound
+            } else {
+                coordinate(point) <= bound
+            }
+        };
+        let mut clipped = Vec::with_capacity(ring.len());
+
+        for (index, current) in ring.iter().enumerate() {
+            let previous = &ring[(index + ring.len() - 1) % ring.len()];
+
+            if inside(current) != inside(previous) {
+                let t =
+                    (bound - coordinate(previous)) / (coordinate(current) - coordinate(previous));
+
+                clipped.push((
+                    previous.0 + t * (current.0 - previous.0),
+                    previous.1 + t * (current.1 - previous.1),
+                ));
+            }
+
+            if inside(current) {
+                clipped.push(*current);
+            }
+        }
+
+        ring = clipped;
+    }
+
+    ring
+}
+
+// Doubled area by surveyor's formula, positive for clockwise rings in tile coordinates
+fn ring_area(coordinates: &[(i32, i32)]) -> i64 {
+    let mut area = 0;
+
+    for (index, &(x1, y1)) in coordinates.iter().enumerate() {
+        let (x2, y2) = coordinates[(index + 1) % coordinates.len()];
+
+        area += i64::from(x1) * i64::from(y2) - i64::from(x2) * i64::from(y1);
+    }
+
+    area
+}
+
+fn command(id: u32, count: u32) -> u32 {
+    id | count << 3
+}
+
+fn zigzag(value: i32) -> u32 {
+    ((value << 1) ^ (value >> 31)) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::clip_line;
+    use super::clip_ring;
+    use super::command;
+    use super::encode;
+    use super::encode_parts;
+    use super::ring_area;
+    use super::zigzag;
+    use super::TileCoordinates;
+    use super::BUFFER;
+    use super::EXTENT;
+    use crate::database::Attributes;
+    use crate::database::Geometry;
+    use crate::database::Point;
+    use std::collections::HashMap;
+
+    // Fields of protobuf message as (field, varint value or bytes)
+    fn fields(mut bytes: &[u8]) -> Vec<(u64, u64, Vec<u8>)> {
+        let mut result = Vec::new();
+
+        while !bytes.is_empty() {
+            let key = varint(&mut bytes);
+
+            if key & 7 == 0 {
+                result.push((key >> 3, varint(&mut bytes), Vec::new()));
+            } else {
+                let length = varint(&mut bytes) as usize;
+                result.push((key >> 3, 0, bytes[..length].to_vec()));
+                bytes = &bytes[length..];
+            }
+        }
+
+        result
+    }
+
+    fn varint(bytes: &mut &[u8]) -> u64 {
+        let mut result = 0;
+        let mut shift = 0;
+
+        loop {
+            let byte = bytes[0];
+            *bytes = &bytes[1..];
+            result |= u64::from(byte & 0x7f) << shift;
+            shift += 7;
+
+            if byte < 0x80 {
+                return result;
+            }
+        }
+    }
+
+    fn packed(mut bytes: &[u8]) -> Vec<u32> {
+        let mut result = Vec::new();
+
+        while !bytes.is_empty() {
+            result.push(varint(&mut bytes) as u32);
+        }
+
+        result
+    }
+
+    // Absolute coordinates of every part of geometry commands
+    fn decode_parts(commands: &[u32]) -> Vec<Vec<(i32, i32)>> {
+        let mut result: Vec<Vec<(i32, i32)>> = Vec::new();
+        let mut cursor = (0, 0);
+        let mut index = 0;
+        let unzigzag = |value: u32| (value >> 1) as i32 ^ -((value & 1) as i32);
+
+        while index < commands.len() {
+            let (id, count) = (commands[index] & 7, commands[index] >> 3);
+            index += 1;
+
+            if id == 1 {
+                result.push(Vec::new());
+            }
+
+            if id == 7 {
+                continue;
+            }
+
+            for _ in 0..count {
+                cursor.0 += unzigzag(commands[index]);
+                cursor.1 += unzigzag(commands[index + 1]);
+                index += 2;
+                result.last_mut().unwrap().push(cursor);
+            }
+        }
+
+        result
+    }
+
+    fn points(coordinates: &[(f64, f64)]) -> Vec<Point> {
+        coordinates
+            .iter()
+            .map(|&(lat, lon)| Point::new(lat, lon))
+            .collect()
+    }
+
+    #[test]
+    fn encodes_commands_and_parameters() {
+        assert_eq!(command(1, 1), 9);
+        assert_eq!(command(2, 3), 26);
+        assert_eq!(command(7, 1), 15);
+        assert_eq!(
+            [0, -1, 1, -2, 2048]
+                .iter()
+                .map(|&value| zigzag(value))
+                .collect::<Vec<_>>(),
+            vec![0, 1, 2, 3, 4096]
+        );
+    }
+
+    #[test]
+    fn encodes_lines_relative_to_cursor() {
+        let tile = TileCoordinates::new(0, 0, 0).unwrap();
+        let first = points(&[(0.0, 0.0), (0.0, 90.0), (0.0, 90.0), (0.0, -90.0)]);
+        let second = points(&[(0.0, 45.0), (0.0, 45.0)]);
+        let third = points(&[(0.0, -180.0), (0.0, -135.0)]);
+        let parts = vec![&first[..], &second[..], &third[..]];
+
+        assert_eq!(
+            encode_parts(&tile, parts.into_iter(), false),
+            vec![
+                command(1, 1),
+                zigzag(2048),
+                zigzag(2048),
+                command(2, 2),
+                zigzag(1024),
+                zigzag(0),
+                zigzag(-2048),
+                zigzag(0),
+                command(1, 1),
+                zigzag(-1024),
+                zigzag(0),
+                command(2, 1),
+                zigzag(512),
+                zigzag(0),
+            ]
+        );
+    }
+
+    #[test]
+    fn splits_line_leaving_and_entering_tile() {
+        let max = f64::from(EXTENT) + BUFFER;
+        let line = [
+            (100.0, 100.0),
+            (5000.0, 100.0),
+            (5000.0, 200.0),
+            (100.0, 200.0),
+            (100.0, -1000.0),
+        ];
+
+        assert_eq!(
+            clip_line(&line),
+            vec![
+                vec![(100.0, 100.0), (max, 100.0)],
+                vec![(max, 200.0), (100.0, 200.0), (100.0, -BUFFER)],
+            ]
+        );
+        assert!(clip_line(&[(5000.0, 0.0), (6000.0, 0.0)]).is_empty());
+    }
+
+    #[test]
+    fn clips_ring_to_buffered_extent() {
+        let max = f64::from(EXTENT) + BUFFER;
+        let ring = clip_ring(vec![(-1000.0, 100.0), (100.0, -1000.0), (5000.0, 5000.0)]);
+
+        assert!(ring
+            .iter()
+            .all(|&(x, y)| (-BUFFER..=max).contains(&x) && (-BUFFER..=max).contains(&y)));
+        assert!(clip_ring(vec![(5000.0, 0.0), (6000.0, 0.0), (6000.0, 100.0)]).is_empty());
+    }
+
+    #[test]
+    fn orients_rings_clockwise() {
+        let tile = TileCoordinates::new(1, 0, 0).unwrap();
+        let ring = points(&[(40.0, -150.0), (40.0, -30.0), (70.0, -90.0), (40.0, -150.0)]);
+        let reversed: Vec<Point> = ring.iter().rev().cloned().collect();
+
+        for part in &[&ring[..], &reversed[..]] {
+            let commands = encode_parts(&tile, vec![*part].into_iter(), true);
+            let rings = decode_parts(&commands);
+
+            assert_eq!(commands[0], command(1, 1));
+            assert_eq!(commands[3], command(2, 2));
+            assert_eq!(commands.last(), Some(&command(7, 1)));
+            assert_eq!(rings.len(), 1);
+            assert_eq!(rings[0].len(), 3);
+            assert!(ring_area(&rings[0]) > 0);
+        }
+    }
+
+    #[test]
+    fn encodes_visible_features_of_layer() {
+        let tile = TileCoordinates::new(1, 0, 0).unwrap();
+        let line =
+            |coordinates: &[(f64, f64)]| Geometry::MultiLine(vec![points(coordinates)].into());
+        let mut objects = HashMap::new();
+        objects.insert(
+            1,
+            (
+                vec!["A".to_string(), "B".to_string()],
+                Attributes::new(),
+                line(&[(40.0, -150.0), (40.0, -30.0)]),
+            )
+                .into(),
+        );
+        objects.insert(
+            2,
+            (
+                vec!["C".to_string()],
+                Attributes::new(),
+                line(&[(-40.0, 30.0), (-40.0, 150.0)]),
+            )
+                .into(),
+        );
+
+        let tile_fields = fields(&encode("roads", tile, &objects));
+        assert_eq!(tile_fields.len(), 1);
+        assert_eq!(tile_fields[0].0, 3);
+
+        let layer = fields(&tile_fields[0].2);
+        let field = |number: u64| layer.iter().filter(move |field| field.0 == number);
+
+        assert_eq!(field(15).next().unwrap().1, 2);
+        assert_eq!(field(1).next().unwrap().2, b"roads");
+        assert_eq!(field(5).next().unwrap().1, u64::from(EXTENT));
+        assert_eq!(
+            field(3).map(|key| key.2.clone()).collect::<Vec<_>>(),
+            vec![b"id".to_vec(), b"names".to_vec()]
+        );
+        assert_eq!(
+            field(4).map(|value| fields(&value.2)).collect::<Vec<_>>(),
+            vec![vec![(4, 1, Vec::new())], vec![(1, 0, b"A, B".to_vec())]]
+        );
+
+        let features: Vec<_> = field(2).map(|feature| fields(&feature.2)).collect();
+        let feature = &features[0];
+
+        assert_eq!(features.len(), 1);
+        assert_eq!(feature[0], (1, 1, Vec::new()));
+        assert_eq!(packed(&feature[1].2), vec![0, 0, 1, 1]);
+        assert_eq!(feature[2], (3, 2, Vec::new()));
+        assert_eq!(decode_parts(&packed(&feature[3].2)).len(), 1);
+    }
+}
@@ -0,0 +1,59 @@
+const WIRE_VARINT: u32 = 0;
+const WIRE_LENGTH: u32 = 2;
+
+#[derive(Default)]
+pub struct Writer {
+    buffer: Vec<u8>,
+}
+
+impl Writer {
+    pub fn uint(&mut self, field: u32, value: u64) {
+        self.key(field, WIRE_VARINT);
+        self.varint(value);
+    }
+
+    pub fn int(&mut self, field: u32, value: i64) {
+        self.uint(field, value as u64);
+    }
+
+    pub fn string(&mut self, field: u32, value: &str) {
+        self.bytes(field, value.as_bytes());
+    }
+
+    pub fn message(&mut self, field: u32, message: Writer) {
+        self.bytes(field, &message.buffer);
+    }
+
+    pub fn packed(&mut self, field: u32, values: &[u32]) {
+        let mut packed = Writer::default();
+
+        for &value in values {
+            packed.varint(u64::from(value));
+        }
+
+        self.message(field, packed);
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buffer
+    }
+
+    fn bytes(&mut self, field: u32, value: &[u8]) {
+        self.key(field, WIRE_LENGTH);
+        self.varint(value.len() as u64);
+        self.buffer.extend_from_slice(value);
+    }
+
+    fn key(&mut self, field: u32, wire_type: u32) {
+        self.varint(u64::from(field << 3 | wire_type));
+    }
+
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.buffer.push((value as u8 & 0x7f) | 0x80);
+            value >>= 7;
+        }
+
+        self.buffer.push(value as u8);
+    }
+}
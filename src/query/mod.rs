@@ -0,0 +1,248 @@
+use crate::config::Config;
+use crate::config::ConnectionConfig;
+use crate::config::FormatConfig;
+use crate::config::ParameterValue;
+use crate::database;
+use crate::database::DatabaseClient;
+use crate::error::ApplicationError;
+use crate::error::ApplicationResult;
+use crate::export;
+use crate::export::ExportObjects;
+use crate::introspect;
+use crate::render;
+use crate::render::RenderObject;
+use crate::render::RenderOptions;
+use crate::settings::IntrospectSettings;
+use crate::settings::QuerySettings;
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt::Write as FmtWrite;
+use std::io::stdin;
+use std::io::stdout;
+use std::io::Read;
+use std::io::Write;
+
+/// Loads objects with connection and format from configuration and writes them to stdout
+/// without starting the server. Password taken from connection or `PGPASSWORD` variable.
+pub fn run_query(config: &Config, settings: &QuerySettings) -> ApplicationResult {
+    let (_, objects) = load_objects(config, settings)?;
+    let file = export::export(settings.output(), settings.format(), &objects)
+        .map_err(|error| ApplicationError::query_error(&format!("Export error - {}", error)))?;
+
+    write_output(&file.into_content())
+}
+
+pub fn run_render(config: &Config, settings: &QuerySettings) -> ApplicationResult {
+    let options = RenderOptions::from_config(
+        config.render(),
+        settings.width(),
+        settings.height(),
+        settings.base_map(),
+    )
+    .map_err(|error| ApplicationError::query_error(&format!("{}", error)))?;
+    let (format, objects) = load_objects(config, settings)?;
+    let objects = RenderObject::styled(format, &objects);
+    let image = render::render(settings.image(), &objects, &options)
+        .map_err(|error| ApplicationError::query_error(&format!("Render error - {}", error)))?;
+
+    write_output(&image.into_content())
+}
+
+pub fn run_introspect(config: &Config, settings: &IntrospectSettings) -> ApplicationResult {
+    let (connection, role, password) =
+        connection_settings(config, settings.connection(), settings.role())?;
+    let tables = database::load_geometry_tables(
+        connection.host(),
+        connection.port(),
+        connection.database(),
+        role,
+        &password,
+    )
+    .map_err(|error| ApplicationError::query_error(&format!("Database error - {}", error)))?;
+    let proposals = introspect::propose_formats(tables);
+
+    if settings.tables().is_empty() {
+        let mut output = String::new();
+
+        for proposal in &proposals {
+            let table = proposal.table();
+            let _ = writeln!(
+                output,
+                "{}\t{}\t{}\t{}",
+                proposal.reference(),
+                table.geometry_type(),
+                table.srid(),
+                proposal.reason().unwrap_or_else(|| proposal.name())
+            );
+        }
+
+        return write_output(output.as_bytes());
+    }
+
+    let mut chosen = Vec::new();
+
+    for reference in settings.tables() {
+        let proposal = proposals
+            .iter()
+            .find(|proposal| proposal.matches(reference))
+            .ok_or_else(|| {
+                ApplicationError::query_error(&format!("Unknown geometry table {}", reference))
+            })?;
+
+        if let Some(reason) = proposal.reason() {
+            return Err(ApplicationError::query_error(&format!(
+                "Table {} can't be used - {}",
+                reference, reason
+            )));
+        }
+
+        chosen.push(proposal);
+    }
+
+    let yaml = introspect::formats_yaml(&chosen)
+        .map_err(|message| ApplicationError::query_error(&message))?;
+
+    write_output(yaml.as_bytes())
+}
+
+// Connection from configuration with role and password. Password taken from connection or
+// `PGPASSWORD` variable.
+fn connection_settings<'a>(
+    config: &'a Config,
+    name: &str,
+    role: Option<&'a str>,
+) -> Result<(&'a ConnectionConfig, &'a str, String), ApplicationError> {
+    let connection = config
+        .connections()
+        .get(name)
+        .ok_or_else(|| ApplicationError::query_error(&format!("Unknown connection {}", name)))?;
+    let role = role.or_else(|| connection.role()).ok_or_else(|| {
+        ApplicationError::query_error(&format!(
+            "Connection {} has no role, use --role option",
+            name
+        ))
+    })?;
+    let password = match connection.password() {
+        "" => env::var("PGPASSWORD").unwrap_or_default(),
+        password => password.into(),
+    };
+
+    Ok((connection, role, password))
+}
+
+fn load_objects<'a>(
+    config: &'a Config,
+    settings: &QuerySettings,
+) -> Result<(&'a FormatConfig, ExportObjects), ApplicationError> {
+    let (connection, role, password) =
+        connection_settings(config, settings.connection(), settings.role())?;
+    let format = config.formats().get(settings.format()).ok_or_else(|| {
+        ApplicationError::query_error(&format!("Unknown format {}", settings.format()))
+    })?;
+    let ids = read_ids(settings.ids())?;
+    let parameters = read_values("parameter", settings.parameters())?
+        .into_iter()
+        .map(|(name, value)| (name, ParameterValue::Text(value)))
+        .collect();
+    let parameters = format
+        .resolve_parameters(&parameters)
+        .map_err(|message| ApplicationError::query_error(&message))?;
+    let variables = config
+        .resolve_variables(
+            format,
+            connection.host(),
+            connection.port(),
+            connection.database(),
+            &read_values("variable", settings.variables())?,
+        )
+        .map_err(|message| ApplicationError::query_error(&message))?;
+
+    let client = DatabaseClient::new(
+        format,
+        connection.host(),
+        connection.port(),
+        connection.database(),
+        role,
+        &password,
+    )
+    .with_parameters(parameters)
+    .with_variables(variables);
+    let (objects, _) = client
+        .query(&ids)
+        .map_err(|error| ApplicationError::query_error(&format!("Database error - {}", error)))?;
+
+    for id in &ids {
+        if !objects.contains_key(id) {
+            eprintln!("Object with id {} was not found", id);
+        }
+    }
+
+    Ok((format, objects.into_iter().collect()))
+}
+
+// Parameters and variables given as `name=value`, parameter values are converted to parameter
+// types later.
+fn read_values(
+    kind: &str,
+    values: &[String],
+) -> Result<BTreeMap<String, String>, ApplicationError> {
+    let mut result = BTreeMap::new();
+
+    for value in values {
+        match value.find('=') {
+            Some(index) => {
+                result.insert(value[..index].to_string(), value[index + 1..].to_string());
+            }
+            None => {
+                return Err(ApplicationError::query_error(&format!(
+                    "Invalid {} {}, expected name=value",
+                    kind, value
+                )))
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+fn write_output(content: &[u8]) -> ApplicationResult {
+    stdout()
+        .write_all(content)
+        .map_err(|error| ApplicationError::query_error(&format!("Output error - {}", error)))
+}
+
+// Identifiers separated with commas, semicolons or whitespace, `-` reads them from stdin.
+fn read_ids(value: &str) -> Result<Vec<i64>, ApplicationError> {
+    let mut text = String::new();
+    let text = if value == "-" {
+        stdin()
+            .read_to_string(&mut text)
+            .map_err(|error| ApplicationError::query_error(&format!("Input error - {}", error)))?;
+
+        &text
+    } else {
+        value
+    };
+    let mut result = Vec::new();
+
+    for item in text.split(|symbol: char| symbol == ',' || symbol == ';' || symbol.is_whitespace())
+    {
+        if item.is_empty() {
+            continue;
+        }
+
+        let id = item
+            .parse()
+            .map_err(|_| ApplicationError::query_error(&format!("Invalid identifier {}", item)))?;
+
+        if !result.contains(&id) {
+            result.push(id);
+        }
+    }
+
+    if result.is_empty() {
+        return Err(ApplicationError::query_error("No identifiers given"));
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,36 @@
+use resvg::usvg::Error as SvgError;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+
+pub type RenderResult<T> = Result<T, RenderError>;
+
+#[derive(Debug)]
+pub struct RenderError {
+    message: String,
+}
+
+impl RenderError {
+    pub fn new(message: &str) -> RenderError {
+        RenderError {
+            message: message.into(),
+        }
+    }
+}
+
+impl From<SvgError> for RenderError {
+    fn from(error: SvgError) -> RenderError {
+        RenderError {
+            message: format!("{}", error),
+        }
+    }
+}
+
+impl Error for RenderError {}
+
+impl Display for RenderError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.message)
+    }
+}
@@ -0,0 +1,144 @@
+mod error;
+mod png;
+mod svg;
+mod tiles;
+mod viewport;
+
+pub use self::error::RenderError;
+pub use self::error::RenderResult;
+
+use self::viewport::Viewport;
+use crate::config::FormatConfig;
+use crate::config::RenderConfig;
+use crate::config::StyleProperties;
+use crate::database::NamesGeometry;
+use crate::export;
+use crate::export::ExportObjects;
+use serde::de::value::Error as ValueError;
+use serde::de::value::StrDeserializer;
+use serde::de::IntoDeserializer;
+use serde::Deserialize;
+use std::str::FromStr;
+
+const PADDING: f64 = 20.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RenderFormat {
+    #[default]
+    Svg,
+    Png,
+}
+
+impl FromStr for RenderFormat {
+    type Err = RenderError;
+
+    // Command line values are the same as values of requests
+    fn from_str(value: &str) -> RenderResult<RenderFormat> {
+        let deserializer: StrDeserializer<ValueError> = value.into_deserializer();
+
+        RenderFormat::deserialize(deserializer)
+            .map_err(|_| RenderError::new(&format!("Unknown image format {}", value)))
+    }
+}
+
+pub struct RenderObject<'a> {
+    id: i64,
+    object: &'a NamesGeometry,
+    style: StyleProperties,
+}
+
+impl<'a> RenderObject<'a> {
+    pub fn styled(format: &FormatConfig, objects: &'a ExportObjects) -> Vec<RenderObject<'a>> {
+        objects
+            .iter()
+            .map(|(&id, object)| RenderObject {
+                id,
+                object,
+                style: format.style().resolve(object.attributes()),
+            })
+            .collect()
+    }
+}
+
+pub struct RenderOptions<'a> {
+    width: u32,
+    height: u32,
+    // Path template of local base map tiles
+    tiles: Option<&'a str>,
+}
+
+impl<'a> RenderOptions<'a> {
+    pub fn from_config(
+        config: &'a RenderConfig,
+        width: Option<u32>,
+        height: Option<u32>,
+        base_map: bool,
+    ) -> RenderResult<RenderOptions<'a>> {
+        let width = width.unwrap_or_else(|| config.width());
+        let height = height.unwrap_or_else(|| config.height());
+
+        if width == 0 || height == 0 || width > config.max_size() || height > config.max_size() {
+            return Err(RenderError::new(&format!(
+                "Image size must be from 1 to {} pixels",
+                config.max_size()
+            )));
+        }
+
+        Ok(RenderOptions {
+            width,
+            height,
+            tiles: if base_map { config.tiles() } else { None },
+        })
+    }
+}
+
+pub struct RenderedImage {
+    extension: &'static str,
+    content_type: &'static str,
+    content: Vec<u8>,
+}
+
+impl RenderedImage {
+    pub fn extension(&self) -> &str {
+        self.extension
+    }
+
+    pub fn content_type(&self) -> &str {
+        self.content_type
+    }
+
+    pub fn into_content(self) -> Vec<u8> {
+        self.content
+    }
+}
+
+pub fn render(
+    format: RenderFormat,
+    objects: &[RenderObject],
+    options: &RenderOptions,
+) -> RenderResult<RenderedImage> {
+    let points = objects
+        .iter()
+        .flat_map(|object| export::parts(object.object))
+        .flatten();
+    let viewport = Viewport::fit(points, options.width, options.height, PADDING);
+    let tiles = match options.tiles {
+        Some(template) => tiles::load_tiles(template, &viewport),
+        None => Vec::new(),
+    };
+    let svg = svg::write(objects, &viewport, &tiles);
+
+    match format {
+        RenderFormat::Svg => Ok(RenderedImage {
+            extension: "svg",
+            content_type: "image/svg+xml",
+            content: svg.into_bytes(),
+        }),
+        RenderFormat::Png => Ok(RenderedImage {
+            extension: "png",
+            content_type: "image/png",
+            content: png::write(&svg, options.width, options.height)?,
+        }),
+    }
+}
@@ -0,0 +1,38 @@
+use super::RenderError;
+use super::RenderResult;
+use resvg::tiny_skia::Pixmap;
+use resvg::tiny_skia::Transform;
+use resvg::usvg::fontdb::Database;
+use resvg::usvg::Options;
+use resvg::usvg::PostProcessingSteps;
+use resvg::usvg::Tree;
+use resvg::usvg::TreeParsing;
+use resvg::usvg::TreePostProc;
+use std::sync::OnceLock;
+
+// System fonts loaded once, labels are not rendered if no fonts found
+static FONTS: OnceLock<Database> = OnceLock::new();
+
+pub fn write(svg: &str, width: u32, height: u32) -> RenderResult<Vec<u8>> {
+    let fonts = FONTS.get_or_init(|| {
+        let mut fonts = Database::new();
+        fonts.load_system_fonts();
+
+        fonts
+    });
+    let mut tree = Tree::from_str(svg, &Options::default())?;
+    tree.postprocess(
+        PostProcessingSteps {
+            convert_text_into_paths: true,
+        },
+        fonts,
+    );
+
+    let mut pixmap =
+        Pixmap::new(width, height).ok_or_else(|| RenderError::new("Invalid image size"))?;
+    resvg::render(&tree, Transform::default(), &mut pixmap.as_mut());
+
+    pixmap
+        .encode_png()
+        .map_err(|error| RenderError::new(&format!("PNG error - {}", error)))
+}
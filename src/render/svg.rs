@@ -0,0 +1,269 @@
+use super::tiles::TileImage;
+use super::RenderObject;
+use super::Viewport;
+use crate::database::Geometry;
+use crate::database::Point;
+use crate::export::escape_xml;
+use std::fmt::Write;
+
+const ARROW_SPACING: f64 = 80.0;
+const ARROW_SIZE: f64 = 6.0;
+// Tiles slightly enlarged to hide antialiasing seams between them
+const TILE_OVERLAP: f64 = 0.5;
+const FONT_FAMILY: &str = "DejaVu Sans, Arial, sans-serif";
+
+pub fn write(objects: &[RenderObject], viewport: &Viewport, tiles: &[TileImage]) -> String {
+    let mut result = String::new();
+    let _ = writeln!(
+        result,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" xmlns:xlink=\"http://www.w3.org/1999/xlink\" \
+         width=\"{0}\" height=\"{1}\" viewBox=\"0 0 {0} {1}\">",
+        viewport.width(),
+        viewport.height()
+    );
+    result.push_str("<rect width=\"100%\" height=\"100%\" fill=\"#ffffff\"/>\n");
+
+    for tile in tiles {
+        let _ = writeln!(
+            result,
+            "<image x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" xlink:href=\"data:{};base64,{}\"/>",
+            tile.x(),
+            tile.y(),
+            tile.size() + TILE_OVERLAP,
+            tile.size() + TILE_OVERLAP,
+            tile.content_type(),
+            base64::encode(tile.content())
+        );
+    }
+
+    for object in objects {
+        write_object(&mut result, object, viewport);
+    }
+
+    for object in objects {
+        write_label(&mut result, object, viewport);
+    }
+
+    result.push_str("</svg>\n");
+
+    result
+}
+
+fn write_object(result: &mut String, object: &RenderObject, viewport: &Viewport) {
+    let style = &object.style;
+    let (color_r, color_g, color_b) = default_color(object);
+    let _ = write!(result, "<g id=\"object-{}\"", object.id);
+
+    if let Some(opacity) = style.opacity() {
+        let _ = write!(result, " opacity=\"{}\"", opacity);
+    }
+
+    result.push_str(">\n");
+
+    match object.object.geometry() {
+        Geometry::MultiLine(multi_line) => {
+            let stroke = style
+                .stroke_color()
+                .map(escape_xml)
+                .unwrap_or_else(|| format!("rgb({},{},{})", 255 - color_r, 128 + color_g, color_b));
+
+            for line in multi_line.lines() {
+                let points = project(line.points(), viewport);
+
+                let _ = write!(
+                    result,
+                    "<path d=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"{}\" \
+                     stroke-linejoin=\"round\" stroke-linecap=\"round\"",
+                    path_data(&points, false),
+                    stroke,
+                    style.stroke_width().unwrap_or(2.0)
+                );
+                write_dash(result, style.dash());
+                result.push_str("/>\n");
+
+                if style.arrows() {
+                    write_arrows(result, &points, &stroke);
+                }
+
+                if style.endpoints() {
+                    write_endpoints(result, &points, &stroke);
+                }
+            }
+        }
+        Geometry::MultiPolygon(multi_polygon) => {
+            let stroke = style
+                .stroke_color()
+                .map(escape_xml)
+                .unwrap_or_else(|| format!("rgb({},{},{})", color_r, 128 + color_g, 255 - color_b));
+            // Without fill color polygon filled with transparent stroke color like on the map
+            let fill = match style.fill_color() {
+                Some(fill_color) => format!("fill=\"{}\"", escape_xml(fill_color)),
+                None => format!("fill=\"{}\" fill-opacity=\"0.1\"", stroke),
+            };
+
+            for polygon in multi_polygon.polygons() {
+                let points = project(polygon.points(), viewport);
+
+                let _ = write!(
+                    result,
+                    "<path d=\"{}\" {} stroke=\"{}\" stroke-width=\"{}\" stroke-linejoin=\"round\"",
+                    path_data(&points, true),
+                    fill,
+                    stroke,
+                    style.stroke_width().unwrap_or(1.0)
+                );
+                write_dash(result, style.dash());
+                result.push_str("/>\n");
+            }
+        }
+    }
+
+    result.push_str("</g>\n");
+}
+
+// Label placed at the center of polygon bounds or the middle vertex of the longest line.
+fn write_label(result: &mut String, object: &RenderObject, viewport: &Viewport) {
+    let label = match object.style.label_field() {
+        Some("id") => object.id.to_string(),
+        Some("names") => object.object.names().join(", "),
+        Some(field) => match object.object.attributes().get(field) {
+            Some(value) => value.clone(),
+            None => return,
+        },
+        None => return,
+    };
+    let position = match object.object.geometry() {
+        Geometry::MultiLine(multi_line) => multi_line
+            .lines()
+            .iter()
+            .max_by_key(|line| line.points().len())
+            .and_then(|line| line.points().get(line.points().len() / 2))
+            .map(|point| viewport.project(point)),
+        Geometry::MultiPolygon(multi_polygon) => {
+            let points: Vec<(f64, f64)> = multi_polygon
+                .polygons()
+                .iter()
+                .flat_map(|polygon| polygon.points())
+                .map(|point| viewport.project(point))
+                .collect();
+
+            center(&points)
+        }
+    };
+
+    if let Some((x, y)) = position {
+        let _ = writeln!(
+            result,
+            "<text x=\"{:.1}\" y=\"{:.1}\" font-family=\"{}\" font-size=\"12\" text-anchor=\"middle\" \
+             fill=\"#333333\" stroke=\"#ffffff\" stroke-width=\"3\" paint-order=\"stroke\">{}</text>",
+            x,
+            y,
+            FONT_FAMILY,
+            escape_xml(&label)
+        );
+    }
+}
+
+// Arrows placed along the line every `ARROW_SPACING` pixels and directed along segments.
+fn write_arrows(result: &mut String, points: &[(f64, f64)], color: &str) {
+    let mut distance = ARROW_SPACING / 2.0;
+
+    for segment in points.windows(2) {
+        let (x1, y1) = segment[0];
+        let (x2, y2) = segment[1];
+        let length = ((x2 - x1).powi(2) + (y2 - y1).powi(2)).sqrt();
+
+        if length == 0.0 {
+            continue;
+        }
+
+        let (dx, dy) = ((x2 - x1) / length, (y2 - y1) / length);
+        let mut position = distance;
+
+        while position <= length {
+            let (x, y) = (x1 + dx * position, y1 + dy * position);
+            let back = (x - dx * ARROW_SIZE, y - dy * ARROW_SIZE);
+            let _ = writeln!(
+                result,
+                "<path d=\"M {:.1} {:.1} L {:.1} {:.1} L {:.1} {:.1} Z\" fill=\"{}\"/>",
+                x,
+                y,
+                back.0 - dy * ARROW_SIZE / 2.0,
+                back.1 + dx * ARROW_SIZE / 2.0,
+                back.0 + dy * ARROW_SIZE / 2.0,
+                back.1 - dx * ARROW_SIZE / 2.0,
+                color
+            );
+            position += ARROW_SPACING;
+        }
+
+        distance = position - length;
+    }
+}
+
+// Start of line marked with circle and end with square.
+fn write_endpoints(result: &mut String, points: &[(f64, f64)], color: &str) {
+    if let (Some(first), Some(last)) = (points.first(), points.last()) {
+        let _ = writeln!(
+            result,
+            "<circle cx=\"{:.1}\" cy=\"{:.1}\" r=\"4\" fill=\"#ffffff\" stroke=\"{}\" stroke-width=\"2\"/>",
+            first.0, first.1, color
+        );
+        let _ = writeln!(
+            result,
+            "<rect x=\"{:.1}\" y=\"{:.1}\" width=\"8\" height=\"8\" fill=\"#ffffff\" stroke=\"{}\" stroke-width=\"2\"/>",
+            last.0 - 4.0,
+            last.1 - 4.0,
+            color
+        );
+    }
+}
+
+fn write_dash(result: &mut String, dash: Option<&[f32]>) {
+    if let Some(dash) = dash {
+        if !dash.is_empty() {
+            let values: Vec<String> = dash.iter().map(|value| value.to_string()).collect();
+            let _ = write!(result, " stroke-dasharray=\"{}\"", values.join(" "));
+        }
+    }
+}
+
+fn project(points: &[Point], viewport: &Viewport) -> Vec<(f64, f64)> {
+    points.iter().map(|point| viewport.project(point)).collect()
+}
+
+fn path_data(points: &[(f64, f64)], closed: bool) -> String {
+    let mut result = String::new();
+
+    for (index, (x, y)) in points.iter().enumerate() {
+        let command = if index == 0 { "M" } else { "L" };
+        let _ = write!(result, "{} {:.1} {:.1} ", command, x, y);
+    }
+
+    if closed {
+        result.push('Z');
+    }
+
+    result.trim_end().into()
+}
+
+fn center(points: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let (first_x, first_y) = *points.first()?;
+    let (min_x, min_y, max_x, max_y) = points.iter().fold(
+        (first_x, first_y, first_x, first_y),
+        |(min_x, min_y, max_x, max_y), &(x, y)| {
+            (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+        },
+    );
+
+    Some(((min_x + max_x) / 2.0, (min_y + max_y) / 2.0))
+}
+
+// Color offsets derived from identifier, so the same object always gets the same color. Ranges
+// match random colors of the web interface.
+fn default_color(object: &RenderObject) -> (u8, u8, u8) {
+    let hash = (object.id as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    let component = |shift: u32| ((hash >> shift) % 64) as u8;
+
+    (component(8), component(24), component(40))
+}
@@ -0,0 +1,84 @@
+use super::Viewport;
+use std::fs;
+
+const MAX_ZOOM: f64 = 19.0;
+const MAX_TILES: usize = 256;
+
+pub struct TileImage {
+    x: f64,
+    y: f64,
+    size: f64,
+    content_type: &'static str,
+    content: Vec<u8>,
+}
+
+impl TileImage {
+    pub fn x(&self) -> f64 {
+        self.x
+    }
+
+    pub fn y(&self) -> f64 {
+        self.y
+    }
+
+    pub fn size(&self) -> f64 {
+        self.size
+    }
+
+    pub fn content_type(&self) -> &str {
+        self.content_type
+    }
+
+    pub fn content(&self) -> &[u8] {
+        &self.content
+    }
+}
+
+pub fn load_tiles(template: &str, viewport: &Viewport) -> Vec<TileImage> {
+    let zoom = viewport.zoom().round().clamp(0.0, MAX_ZOOM);
+    let count = 2f64.powf(zoom);
+    let tile_size = viewport.scale() / count;
+    let (left, top, right, bottom) = viewport.bounds();
+    let range = |from: f64, to: f64| {
+        let first = (from * count).floor().max(0.0) as u64;
+        let last = ((to * count).floor().min(count - 1.0)).max(0.0) as u64;
+
+        first..=last
+    };
+    let content_type = if template.ends_with(".jpg") || template.ends_with(".jpeg") {
+        "image/jpeg"
+    } else {
+        "image/png"
+    };
+    let mut result = Vec::new();
+
+    for tile_x in range(left, right) {
+        for tile_y in range(top, bottom) {
+            if result.len() >= MAX_TILES {
+                warn!("Too many base map tiles, only {} rendered", MAX_TILES);
+
+                return result;
+            }
+
+            let path = template
+                .replace("{z}", &format!("{}", zoom as u32))
+                .replace("{x}", &tile_x.to_string())
+                .replace("{y}", &tile_y.to_string());
+            let content = match fs::read(&path) {
+                Ok(content) => content,
+                Err(_) => continue,
+            };
+            let (x, y) = viewport.world_to_pixel(tile_x as f64 / count, tile_y as f64 / count);
+
+            result.push(TileImage {
+                x,
+                y,
+                size: tile_size,
+                content_type,
+                content,
+            });
+        }
+    }
+
+    result
+}
@@ -0,0 +1,107 @@
+use crate::database::Point;
+use std::f64::consts::PI;
+
+const TILE_SIZE: f64 = 256.0;
+const MAX_ZOOM: f64 = 19.0;
+// Zoom used when all objects are single point
+const POINT_ZOOM: f64 = 17.0;
+const MAX_LATITUDE: f64 = 85.051_128_78;
+
+/// Projection of points to image pixels. Points projected to WEB Mercator with the world
+/// square of size 1.0, then scaled and shifted to fit the image.
+#[derive(Debug, Clone, Copy)]
+pub struct Viewport {
+    width: u32,
+    height: u32,
+    // Pixels per world unit
+    scale: f64,
+    // World coordinates of the top left image corner
+    left: f64,
+    top: f64,
+}
+
+impl Viewport {
+    pub fn fit<'a>(
+        points: impl Iterator<Item = &'a Point>,
+        width: u32,
+        height: u32,
+        padding: f64,
+    ) -> Viewport {
+        let mut bounds: Option<(f64, f64, f64, f64)> = None;
+
+        for point in points {
+            let (x, y) = world(point);
+
+            bounds = Some(match bounds {
+                Some((min_x, min_y, max_x, max_y)) => {
+                    (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+                }
+                None => (x, y, x, y),
+            });
+        }
+
+        let (min_x, min_y, max_x, max_y) = bounds.unwrap_or((0.0, 0.0, 1.0, 1.0));
+        let inner_width = (f64::from(width) - 2.0 * padding).max(1.0);
+        let inner_height = (f64::from(height) - 2.0 * padding).max(1.0);
+        let max_scale = TILE_SIZE * 2f64.powf(MAX_ZOOM);
+        let scale = if max_x > min_x || max_y > min_y {
+            (inner_width / (max_x - min_x)).min(inner_height / (max_y - min_y))
+        } else {
+            TILE_SIZE * 2f64.powf(POINT_ZOOM)
+        }
+        .min(max_scale);
+        let center_x = (min_x + max_x) / 2.0;
+        let center_y = (min_y + max_y) / 2.0;
+
+        Viewport {
+            width,
+            height,
+            scale,
+            left: center_x - f64::from(width) / 2.0 / scale,
+            top: center_y - f64::from(height) / 2.0 / scale,
+        }
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn zoom(&self) -> f64 {
+        (self.scale / TILE_SIZE).log2()
+    }
+
+    pub fn scale(&self) -> f64 {
+        self.scale
+    }
+
+    pub fn bounds(&self) -> (f64, f64, f64, f64) {
+        (
+            self.left,
+            self.top,
+            self.left + f64::from(self.width) / self.scale,
+            self.top + f64::from(self.height) / self.scale,
+        )
+    }
+
+    pub fn project(&self, point: &Point) -> (f64, f64) {
+        let (x, y) = world(point);
+
+        self.world_to_pixel(x, y)
+    }
+
+    pub fn world_to_pixel(&self, x: f64, y: f64) -> (f64, f64) {
+        ((x - self.left) * self.scale, (y - self.top) * self.scale)
+    }
+}
+
+fn world(point: &Point) -> (f64, f64) {
+    let lat = point.lat().clamp(-MAX_LATITUDE, MAX_LATITUDE).to_radians();
+    let x = (point.lon() + 180.0) / 360.0;
+    let y = (1.0 - (lat.tan() + 1.0 / lat.cos()).ln() / PI) / 2.0;
+
+    (x, y)
+}
@@ -1,11 +1,51 @@
+use crate::export::ExportFormat;
+use crate::render::RenderFormat;
 use argparse::ArgumentParser;
+use argparse::Collect;
+use argparse::List;
+use argparse::Store;
+use argparse::StoreFalse;
 use argparse::StoreOption;
+use std::io::stderr;
+use std::io::stdout;
+use std::process::exit;
 
 #[derive(Debug, Clone)]
 pub struct Settings {
     bind_address: String,
     bind_port: u16,
     config_path: String,
+    command: Command,
+}
+
+#[derive(Debug, Clone)]
+pub enum Command {
+    Serve,
+    Query(QuerySettings),
+    Render(QuerySettings),
+    Introspect(IntrospectSettings),
+}
+
+#[derive(Debug, Clone)]
+pub struct QuerySettings {
+    connection: String,
+    format: String,
+    ids: String,
+    output: ExportFormat,
+    role: Option<String>,
+    parameters: Vec<String>,
+    variables: Vec<String>,
+    image: RenderFormat,
+    width: Option<u32>,
+    height: Option<u32>,
+    base_map: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct IntrospectSettings {
+    connection: String,
+    role: Option<String>,
+    tables: Vec<String>,
 }
 
 impl Settings {
@@ -15,6 +55,8 @@ impl Settings {
         let mut bind_address = None;
         let mut bind_port = None;
         let mut config_path = None;
+        let mut command: Option<String> = None;
+        let mut arguments: Vec<String> = Vec::new();
 
         {
             let mut ap = ArgumentParser::new();
@@ -35,6 +77,14 @@ impl Settings {
                 StoreOption,
                 "Path to configuration file (default: config.yaml)",
             );
+            ap.refer(&mut command).add_argument(
+                "command",
+                StoreOption,
+                "Command to run: serve (default), query, render or introspect",
+            );
+            ap.refer(&mut arguments)
+                .add_argument("arguments", List, "Arguments of command");
+            ap.stop_on_first_argument(true);
             ap.parse_args_or_exit();
         }
 
@@ -52,9 +102,233 @@ impl Settings {
             config.config_path = config_path;
         }
 
+        match command.as_deref() {
+            None => {}
+            Some("serve") => Self::serve_from_args(arguments, &mut config),
+            Some("query") => {
+                config.command = Command::Query(Self::query_from_args(
+                    "query",
+                    arguments,
+                    &mut config.config_path,
+                ))
+            }
+            Some("render") => {
+                config.command = Command::Render(Self::query_from_args(
+                    "render",
+                    arguments,
+                    &mut config.config_path,
+                ))
+            }
+            Some("introspect") => {
+                config.command = Command::Introspect(Self::introspect_from_args(
+                    arguments,
+                    &mut config.config_path,
+                ))
+            }
+            _ => {
+                eprintln!(
+                    "Unexpected arguments, available commands: serve (default), query, render, \
+                     introspect"
+                );
+                exit(2);
+            }
+        }
+
         config
     }
 
+    // Options of server may be given after command name too
+    fn serve_from_args(arguments: Vec<String>, config: &mut Settings) {
+        let mut ap = ArgumentParser::new();
+
+        ap.set_description("Start WEB server.");
+        ap.refer(&mut config.bind_address).add_option(
+            &["-b", "--bind"],
+            Store,
+            "Address to bind on (default: localhost)",
+        );
+        ap.refer(&mut config.bind_port).add_option(
+            &["-p", "--port"],
+            Store,
+            "Port to listen on (default: 8080)",
+        );
+        ap.refer(&mut config.config_path).add_option(
+            &["-c", "--config"],
+            Store,
+            "Path to configuration file (default: config.yaml)",
+        );
+
+        let mut arguments = arguments;
+        arguments.insert(0, "show-on-map serve".into());
+
+        if let Err(code) = ap.parse(arguments, &mut stdout(), &mut stderr()) {
+            exit(code);
+        }
+    }
+
+    // Arguments of query and render commands, configuration path may be given before or after
+    // command name.
+    fn query_from_args(
+        command: &str,
+        arguments: Vec<String>,
+        config_path: &mut String,
+    ) -> QuerySettings {
+        let mut connection = String::new();
+        let mut format = String::new();
+        let mut ids = String::new();
+        let mut output = ExportFormat::GeoJson;
+        let mut role = None;
+        let mut parameters = Vec::new();
+        let mut variables = Vec::new();
+        let mut image = RenderFormat::Svg;
+        let mut width = None;
+        let mut height = None;
+        let mut base_map = true;
+
+        {
+            let mut ap = ArgumentParser::new();
+
+            if command == "render" {
+                ap.set_description("Render objects to image and write it to standard output.");
+            } else {
+                ap.set_description("Query objects and write them to standard output.");
+            }
+
+            ap.refer(config_path).add_option(
+                &["-c", "--config"],
+                Store,
+                "Path to configuration file (default: config.yaml)",
+            );
+            ap.refer(&mut connection).required().add_option(
+                &["--connection"],
+                Store,
+                "Name of connection from configuration file",
+            );
+            ap.refer(&mut format)
+                .required()
+                .add_option(&["--format"], Store, "Name of format");
+            ap.refer(&mut ids).required().add_option(
+                &["--ids"],
+                Store,
+                "Object identifiers separated with commas or whitespace, - to read from stdin",
+            );
+
+            if command == "render" {
+                ap.refer(&mut image).add_option(
+                    &["--image"],
+                    Store,
+                    "Image format: svg (default) or png",
+                );
+                ap.refer(&mut width)
+                    .add_option(&["--width"], StoreOption, "Image width in pixels");
+                ap.refer(&mut height).add_option(
+                    &["--height"],
+                    StoreOption,
+                    "Image height in pixels",
+                );
+                ap.refer(&mut base_map).add_option(
+                    &["--no-base-map"],
+                    StoreFalse,
+                    "Do not render base map tiles",
+                );
+            } else {
+                ap.refer(&mut output).add_option(
+                    &["--output"],
+                    Store,
+                    "Output format: geojson (default), wkt, csv, kml or gpx",
+                );
+            }
+
+            ap.refer(&mut role).add_option(
+                &["--role"],
+                StoreOption,
+                "Database role, overrides role of connection",
+            );
+            ap.refer(&mut parameters).add_option(
+                &["--parameter"],
+                Collect,
+                "Format parameter as name=value, may be repeated",
+            );
+            ap.refer(&mut variables).add_option(
+                &["--variable"],
+                Collect,
+                "Template variable as name=value, may be repeated",
+            );
+
+            let mut arguments = arguments;
+            arguments.insert(0, format!("show-on-map {}", command));
+
+            if let Err(code) = ap.parse(arguments, &mut stdout(), &mut stderr()) {
+                exit(code);
+            }
+        }
+
+        QuerySettings {
+            connection,
+            format,
+            ids,
+            output,
+            role,
+            parameters,
+            variables,
+            image,
+            width,
+            height,
+            base_map,
+        }
+    }
+
+    fn introspect_from_args(
+        arguments: Vec<String>,
+        config_path: &mut String,
+    ) -> IntrospectSettings {
+        let mut connection = String::new();
+        let mut role = None;
+        let mut tables = Vec::new();
+
+        {
+            let mut ap = ArgumentParser::new();
+
+            ap.set_description(
+                "List PostGIS geometry columns or write formats proposed for them to standard \
+                 output.",
+            );
+            ap.refer(config_path).add_option(
+                &["-c", "--config"],
+                Store,
+                "Path to configuration file (default: config.yaml)",
+            );
+            ap.refer(&mut connection).required().add_option(
+                &["--connection"],
+                Store,
+                "Name of connection from configuration file",
+            );
+            ap.refer(&mut role).add_option(
+                &["--role"],
+                StoreOption,
+                "Database role, overrides role of connection",
+            );
+            ap.refer(&mut tables).add_option(
+                &["--table"],
+                Collect,
+                "Table as schema.table.column, schema.table or table, may be repeated",
+            );
+
+            let mut arguments = arguments;
+            arguments.insert(0, "show-on-map introspect".into());
+
+            if let Err(code) = ap.parse(arguments, &mut stdout(), &mut stderr()) {
+                exit(code);
+            }
+        }
+
+        IntrospectSettings {
+            connection,
+            role,
+            tables,
+        }
+    }
+
     pub fn bind_address(&self) -> &str {
         &self.bind_address
     }
@@ -66,6 +340,10 @@ impl Settings {
     pub fn config_path(&self) -> &str {
         &self.config_path
     }
+
+    pub fn command(&self) -> &Command {
+        &self.command
+    }
 }
 
 impl Default for Settings {
@@ -74,6 +352,67 @@ impl Default for Settings {
             bind_address: "localhost".into(),
             bind_port: 8080,
             config_path: "config.yaml".into(),
+            command: Command::Serve,
         }
     }
 }
+
+impl QuerySettings {
+    pub fn connection(&self) -> &str {
+        &self.connection
+    }
+
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+
+    pub fn ids(&self) -> &str {
+        &self.ids
+    }
+
+    pub fn output(&self) -> ExportFormat {
+        self.output
+    }
+
+    pub fn role(&self) -> Option<&str> {
+        self.role.as_deref()
+    }
+
+    pub fn parameters(&self) -> &[String] {
+        &self.parameters
+    }
+
+    pub fn variables(&self) -> &[String] {
+        &self.variables
+    }
+
+    pub fn image(&self) -> RenderFormat {
+        self.image
+    }
+
+    pub fn width(&self) -> Option<u32> {
+        self.width
+    }
+
+    pub fn height(&self) -> Option<u32> {
+        self.height
+    }
+
+    pub fn base_map(&self) -> bool {
+        self.base_map
+    }
+}
+
+impl IntrospectSettings {
+    pub fn connection(&self) -> &str {
+        &self.connection
+    }
+
+    pub fn role(&self) -> Option<&str> {
+        self.role.as_deref()
+    }
+
+    pub fn tables(&self) -> &[String] {
+        &self.tables
+    }
+}
@@ -0,0 +1,158 @@
+mod state;
+
+pub use self::state::SharedState;
+
+use crate::config::ShareConfig;
+use sha2::Digest;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::ffi::OsStr;
+use std::fs;
+use std::fs::File;
+use std::io::ErrorKind;
+use std::io::Result as IoResult;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+pub type ShareRef = Arc<ShareStore>;
+
+// Number of digest bytes in identifier, encoded as 12 base64 characters
+const ID_BYTES: usize = 9;
+
+#[derive(Default)]
+struct MemoryStore {
+    states: HashMap<String, SharedState>,
+    // Identifiers in order of insertion, first one removed when store is full
+    order: VecDeque<String>,
+}
+
+/// Store of shared map states. States kept in memory or in files of configured directory,
+/// identifier derived from state content so the same state always gets the same identifier.
+pub struct ShareStore {
+    max_entries: usize,
+    path: Option<PathBuf>,
+    memory: Mutex<MemoryStore>,
+    // Writes and removals of files done one at a time
+    disk: Mutex<()>,
+}
+
+impl ShareStore {
+    pub fn new(config: &ShareConfig) -> ShareStore {
+        ShareStore {
+            max_entries: config.max_entries(),
+            path: config.path().map(PathBuf::from),
+            memory: Mutex::new(MemoryStore::default()),
+            disk: Mutex::new(()),
+        }
+    }
+
+    pub fn put(&self, state: &SharedState) -> IoResult<String> {
+        let content = serde_json::to_vec(state)?;
+        let id = base64::encode_config(
+            &Sha256::digest(&content)[..ID_BYTES],
+            base64::URL_SAFE_NO_PAD,
+        );
+
+        match &self.path {
+            Some(path) => {
+                let _lock = self.disk.lock().unwrap();
+                fs::create_dir_all(path)?;
+
+                let file_path = path.join(format!("{}.json", id));
+                let temp_path = file_path.with_extension("tmp");
+                fs::write(&temp_path, &content)?;
+                fs::rename(temp_path, file_path)?;
+
+                self.remove_oldest_files(path)?;
+            }
+            None => {
+                let mut memory = self.memory.lock().unwrap();
+
+                if memory.states.insert(id.clone(), state.clone()).is_none() {
+                    memory.order.push_back(id.clone());
+                }
+
+                while memory.states.len() > self.max_entries {
+                    match memory.order.pop_front() {
+                        Some(oldest) => memory.states.remove(&oldest),
+                        None => break,
+                    };
+                }
+            }
+        }
+
+        Ok(id)
+    }
+
+    pub fn get(&self, id: &str) -> Option<SharedState> {
+        // Identifier used as file name, so anything except base64 characters rejected
+        let valid = id.len() == ID_BYTES / 3 * 4
+            && id
+                .chars()
+                .all(|symbol| symbol.is_ascii_alphanumeric() || symbol == '-' || symbol == '_');
+
+        if !valid {
+            return None;
+        }
+
+        match &self.path {
+            Some(path) => read_state(&path.join(format!("{}.json", id))),
+            None => self.memory.lock().unwrap().states.get(id).cloned(),
+        }
+    }
+
+    // Files ordered by modification time, state shared again becomes the newest one
+    fn remove_oldest_files(&self, path: &Path) -> IoResult<()> {
+        let mut files = Vec::new();
+
+        for entry in fs::read_dir(path)? {
+            let entry = entry?;
+            let file_path = entry.path();
+
+            if file_path.extension().and_then(OsStr::to_str) == Some("json") {
+                files.push((entry.metadata()?.modified()?, file_path));
+            }
+        }
+
+        if files.len() <= self.max_entries {
+            return Ok(());
+        }
+
+        files.sort();
+
+        for (_, file_path) in &files[..files.len() - self.max_entries] {
+            fs::remove_file(file_path)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn read_state(path: &Path) -> Option<SharedState> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(error) => {
+            if error.kind() != ErrorKind::NotFound {
+                warn!("Failed to read shared state {} - {}", path.display(), error);
+            }
+
+            return None;
+        }
+    };
+
+    match serde_json::from_reader(file) {
+        Ok(state) => Some(state),
+        Err(error) => {
+            warn!(
+                "Failed to parse shared state {} - {}",
+                path.display(),
+                error
+            );
+
+            None
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use crate::config::ParameterValue;
+use std::collections::BTreeMap;
+
+/// Map state restored by permalink. Connection password is never stored, unknown fields of
+/// request (including `password`) are dropped.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedState {
+    host: String,
+    port: i16,
+    database: String,
+    role: Option<String>,
+    objects: Vec<SharedObjects>,
+    view: Option<MapView>,
+}
+
+impl SharedState {
+    pub fn objects(&self) -> &[SharedObjects] {
+        &self.objects
+    }
+
+    pub fn ids_count(&self) -> usize {
+        self.objects.iter().map(|objects| objects.ids.len()).sum()
+    }
+}
+
+/// Identifiers of objects shown with one format, parameter and variable values.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedObjects {
+    format: String,
+    ids: Vec<i64>,
+    #[serde(default)]
+    parameters: BTreeMap<String, ParameterValue>,
+    #[serde(default)]
+    variables: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MapView {
+    lat: f64,
+    lon: f64,
+    zoom: f64,
+}
@@ -0,0 +1,37 @@
+use rusqlite::Error as SqliteError;
+use std::error::Error;
+use std::fmt::Display;
+use std::fmt::Formatter;
+use std::fmt::Result as FmtResult;
+use std::io::Error as IoError;
+
+pub type TileResult<T> = Result<T, TileError>;
+
+#[derive(Debug)]
+pub struct TileError {
+    message: String,
+}
+
+impl From<IoError> for TileError {
+    fn from(error: IoError) -> TileError {
+        TileError {
+            message: format!("{}", error),
+        }
+    }
+}
+
+impl From<SqliteError> for TileError {
+    fn from(error: SqliteError) -> TileError {
+        TileError {
+            message: format!("{}", error),
+        }
+    }
+}
+
+impl Error for TileError {}
+
+impl Display for TileError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}", self.message)
+    }
+}
@@ -0,0 +1,50 @@
+use super::TileResult;
+use rusqlite::Connection;
+use rusqlite::OpenFlags;
+use rusqlite::OptionalExtension;
+use std::sync::Mutex;
+
+/// Tiles from MBTiles (SQLite) file. Rows of MBTiles use TMS scheme, so `y` is flipped.
+pub struct MbTiles {
+    connection: Mutex<Connection>,
+    format: String,
+}
+
+impl MbTiles {
+    pub fn open(path: &str) -> TileResult<MbTiles> {
+        let connection = Connection::open_with_flags(
+            path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        let format: Option<String> = connection
+            .query_row(
+                "select value from metadata where name = 'format'",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(MbTiles {
+            connection: Mutex::new(connection),
+            format: format.unwrap_or_else(|| "png".into()),
+        })
+    }
+
+    pub fn format(&self) -> &str {
+        &self.format
+    }
+
+    pub fn get(&self, z: u32, x: u32, y: u32) -> TileResult<Option<Vec<u8>>> {
+        let row = (1u32 << z) - 1 - y;
+        let connection = self.connection.lock().unwrap();
+        let tile = connection
+            .query_row(
+                "select tile_data from tiles where zoom_level = ?1 and tile_column = ?2 and tile_row = ?3",
+                [z, x, row],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        Ok(tile)
+    }
+}
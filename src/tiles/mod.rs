@@ -0,0 +1,122 @@
+mod error;
+mod mbtiles;
+
+pub use self::error::TileResult;
+
+use self::mbtiles::MbTiles;
+use crate::config::Config;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+pub type TileStoreRef = Arc<TileStore>;
+
+const MAX_ZOOM: u32 = 24;
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+pub struct Tile {
+    content_type: &'static str,
+    content: Vec<u8>,
+}
+
+impl Tile {
+    pub fn content_type(&self) -> &str {
+        self.content_type
+    }
+
+    pub fn is_gzipped(&self) -> bool {
+        self.content.starts_with(&GZIP_MAGIC)
+    }
+
+    pub fn into_content(self) -> Vec<u8> {
+        self.content
+    }
+}
+
+enum TileSource {
+    MbTiles(MbTiles),
+    // Directory with `{z}/{x}/{y}.{extension}` files
+    Directory { path: PathBuf, extension: String },
+}
+
+/// Base map layers from configuration. MBTiles files opened once, layer which can not be
+/// opened is skipped with error in log.
+pub struct TileStore {
+    layers: BTreeMap<String, TileSource>,
+}
+
+impl TileStore {
+    pub fn new(config: &Config) -> TileStore {
+        let mut layers = BTreeMap::new();
+
+        for (name, layer) in config.layers() {
+            let source = if layer.path().ends_with(".mbtiles") {
+                match MbTiles::open(layer.path()) {
+                    Ok(mbtiles) => TileSource::MbTiles(mbtiles),
+                    Err(error) => {
+                        error!("Failed to open layer {} - {}", name, error);
+
+                        continue;
+                    }
+                }
+            } else {
+                TileSource::Directory {
+                    path: layer.path().into(),
+                    extension: layer.extension().into(),
+                }
+            };
+
+            layers.insert(name.clone(), source);
+        }
+
+        TileStore { layers }
+    }
+
+    pub fn contains(&self, layer: &str) -> bool {
+        self.layers.contains_key(layer)
+    }
+
+    pub fn get(&self, layer: &str, z: u32, x: u32, y: u32) -> TileResult<Option<Tile>> {
+        let source = match self.layers.get(layer) {
+            Some(source) => source,
+            None => return Ok(None),
+        };
+
+        if z > MAX_ZOOM || x >= 1 << z || y >= 1 << z {
+            return Ok(None);
+        }
+
+        match source {
+            TileSource::MbTiles(mbtiles) => Ok(mbtiles.get(z, x, y)?.map(|content| Tile {
+                content_type: content_type(mbtiles.format()),
+                content,
+            })),
+            TileSource::Directory { path, extension } => {
+                let file_path = path
+                    .join(z.to_string())
+                    .join(x.to_string())
+                    .join(format!("{}.{}", y, extension));
+
+                match fs::read(file_path) {
+                    Ok(content) => Ok(Some(Tile {
+                        content_type: content_type(extension),
+                        content,
+                    })),
+                    Err(error) if error.kind() == ErrorKind::NotFound => Ok(None),
+                    Err(error) => Err(error.into()),
+                }
+            }
+        }
+    }
+}
+
+fn content_type(format: &str) -> &'static str {
+    match format {
+        "jpg" | "jpeg" => "image/jpeg",
+        "webp" => "image/webp",
+        "pbf" | "mvt" => "application/vnd.mapbox-vector-tile",
+        _ => "image/png",
+    }
+}
@@ -0,0 +1,38 @@
+use std::fmt::Arguments;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum IssueKind {
+    TooFewPoints,
+    UnclosedRing,
+    SelfIntersection,
+    DuplicatePoint,
+    OutOfRange,
+    Disconnected,
+    CheckSkipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Issue {
+    kind: IssueKind,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    part: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    point: Option<usize>,
+    message: String,
+}
+
+impl Issue {
+    pub fn new(
+        kind: IssueKind,
+        part: Option<usize>,
+        point: Option<usize>,
+        message: Arguments,
+    ) -> Issue {
+        Issue {
+            kind,
+            part,
+            point,
+            message: format!("{}", message),
+        }
+    }
+}
@@ -0,0 +1,333 @@
+mod issue;
+
+pub use self::issue::Issue;
+pub use self::issue::IssueKind;
+
+use crate::database::Geometry;
+use crate::database::Point;
+use crate::database::EPSILON;
+
+const MAX_ISSUES: usize = 100;
+// Segment pairs of a part are checked for intersection, longer parts are not checked
+const MAX_INTERSECTION_SEGMENTS: usize = 2000;
+const MIN_LINE_POINTS: usize = 2;
+const MIN_RING_POINTS: usize = 4;
+
+pub fn validate(geometry: &Geometry) -> Vec<Issue> {
+    let mut result = Vec::new();
+
+    match geometry {
+        Geometry::MultiLine(multi_line) => {
+            let lines: Vec<&[Point]> = multi_line
+                .lines()
+                .iter()
+                .map(|line| line.points())
+                .collect();
+
+            for (part, points) in lines.iter().enumerate() {
+                check_point_count(&mut result, part, points, MIN_LINE_POINTS);
+                check_ranges(&mut result, part, points);
+                check_duplicates(&mut result, part, points);
+                // Loop roads and roundabouts start and end at the same point
+                check_self_intersections(&mut result, part, points, is_closed(points));
+            }
+
+            check_connectivity(&mut result, &lines);
+        }
+        Geometry::MultiPolygon(multi_polygon) => {
+            for (part, polygon) in multi_polygon.polygons().iter().enumerate() {
+                let points = polygon.points();
+                let closed = is_closed(points);
+
+                if !closed {
+                    result.push(Issue::new(
+                        IssueKind::UnclosedRing,
+                        Some(part),
+                        None,
+                        format_args!("Ring {} is not closed", part),
+                    ));
+                }
+
+                check_point_count(&mut result, part, points, MIN_RING_POINTS);
+                check_ranges(&mut result, part, points);
+                check_duplicates(&mut result, part, points);
+                check_self_intersections(&mut result, part, points, closed);
+            }
+        }
+    }
+
+    result.truncate(MAX_ISSUES);
+    result
+}
+
+fn check_point_count(result: &mut Vec<Issue>, part: usize, points: &[Point], minimum: usize) {
+    if points.len() < minimum {
+        result.push(Issue::new(
+            IssueKind::TooFewPoints,
+            Some(part),
+            None,
+            format_args!(
+                "Part {} has {} points, at least {} required",
+                part,
+                points.len(),
+                minimum
+            ),
+        ));
+    }
+}
+
+fn check_ranges(result: &mut Vec<Issue>, part: usize, points: &[Point]) {
+    for (index, point) in points.iter().enumerate() {
+        let lat = point.lat();
+        let lon = point.lon();
+
+        if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+            result.push(Issue::new(
+                IssueKind::OutOfRange,
+                Some(part),
+                Some(index),
+                format_args!(
+                    "Point {} of part {} is out of range: {}, {}",
+                    index, part, lat, lon
+                ),
+            ));
+        }
+    }
+}
+
+fn check_duplicates(result: &mut Vec<Issue>, part: usize, points: &[Point]) {
+    for index in 1..points.len() {
+        if is_same(&points[index - 1], &points[index]) {
+            result.push(Issue::new(
+                IssueKind::DuplicatePoint,
+                Some(part),
+                Some(index),
+                format_args!("Point {} of part {} duplicates previous point", index, part),
+            ));
+        }
+    }
+}
+
+fn check_self_intersections(result: &mut Vec<Issue>, part: usize, points: &[Point], closed: bool) {
+    let segments: Vec<(usize, &Point, &Point)> = (1..points.len())
+        .filter(|&index| !is_same(&points[index - 1], &points[index]))
+        .map(|index| (index - 1, &points[index - 1], &points[index]))
+        .collect();
+    let n_segments = segments.len();
+
+    if n_segments > MAX_INTERSECTION_SEGMENTS {
+        result.push(Issue::new(
+            IssueKind::CheckSkipped,
+            Some(part),
+            None,
+            format_args!(
+                "Self-intersections of part {} not checked, {} segments exceed limit of {}",
+                part, n_segments, MAX_INTERSECTION_SEGMENTS
+            ),
+        ));
+
+        return;
+    }
+
+    for first in 0..n_segments {
+        for second in first + 2..n_segments {
+            if closed && first == 0 && second == n_segments - 1 {
+                continue;
+            }
+
+            let (index, a, b) = segments[first];
+            let (other_index, c, d) = segments[second];
+
+            if is_intersects(a, b, c, d) {
+                result.push(Issue::new(
+                    IssueKind::SelfIntersection,
+                    Some(part),
+                    Some(index),
+                    format_args!(
+                        "Segment {} of part {} intersects segment {}",
+                        index, part, other_index
+                    ),
+                ));
+
+                if result.len() >= MAX_ISSUES {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+// Lines are connected when they share end points, all lines of an object must form single connected component.
+fn check_connectivity(result: &mut Vec<Issue>, lines: &[&[Point]]) {
+    let lines: Vec<(usize, &[Point])> = lines
+        .iter()
+        .cloned()
+        .enumerate()
+        .filter(|(_, points)| !points.is_empty())
+        .collect();
+    let mut components: Vec<usize> = (0..lines.len()).collect();
+
+    for first in 0..lines.len() {
+        for second in first + 1..lines.len() {
+            if is_touches(lines[first].1, lines[second].1) {
+                let from = find_component(&components, second);
+                let to = find_component(&components, first);
+
+                components[from] = to;
+            }
+        }
+    }
+
+    for (index, (part, _)) in lines.iter().enumerate().skip(1) {
+        if find_component(&components, index) != find_component(&components, 0) {
+            result.push(Issue::new(
+                IssueKind::Disconnected,
+                Some(*part),
+                None,
+                format_args!("Line {} is not connected to line {}", part, lines[0].0),
+            ));
+        }
+    }
+}
+
+fn find_component(components: &[usize], mut index: usize) -> usize {
+    while components[index] != index {
+        index = components[index];
+    }
+
+    index
+}
+
+fn is_touches(first: &[Point], second: &[Point]) -> bool {
+    let first_ends = [&first[0], &first[first.len() - 1]];
+    let second_ends = [&second[0], &second[second.len() - 1]];
+
+    first_ends
+        .iter()
+        .any(|a| second_ends.iter().any(|b| is_same(a, b)))
+}
+
+fn is_closed(points: &[Point]) -> bool {
+    match (points.first(), points.last()) {
+        (Some(first), Some(last)) => points.len() > 1 && is_same(first, last),
+        _ => false,
+    }
+}
+
+fn is_same(a: &Point, b: &Point) -> bool {
+    let delta = (a.lat() - b.lat()).abs() + (a.lon() - b.lon()).abs();
+
+    delta <= EPSILON
+}
+
+fn is_intersects(a: &Point, b: &Point, c: &Point, d: &Point) -> bool {
+    let d1 = orientation(c, d, a);
+    let d2 = orientation(c, d, b);
+    let d3 = orientation(a, b, c);
+    let d4 = orientation(a, b, d);
+
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+    {
+        return true;
+    }
+
+    (d1 == 0.0 && is_on_segment(c, d, a))
+        || (d2 == 0.0 && is_on_segment(c, d, b))
+        || (d3 == 0.0 && is_on_segment(a, b, c))
+        || (d4 == 0.0 && is_on_segment(a, b, d))
+}
+
+fn orientation(a: &Point, b: &Point, c: &Point) -> f64 {
+    let (ax, ay) = (a.lon(), a.lat());
+    let (bx, by) = (b.lon(), b.lat());
+    let (cx, cy) = (c.lon(), c.lat());
+
+    (bx - ax) * (cy - ay) - (by - ay) * (cx - ax)
+}
+
+fn is_on_segment(a: &Point, b: &Point, c: &Point) -> bool {
+    let (min_lat, max_lat) = min_max(a.lat(), b.lat());
+    let (min_lon, max_lon) = min_max(a.lon(), b.lon());
+
+    (min_lat..=max_lat).contains(&c.lat()) && (min_lon..=max_lon).contains(&c.lon())
+}
+
+fn min_max(a: f64, b: f64) -> (f64, f64) {
+    if a < b {
+        (a, b)
+    } else {
+        (b, a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::validate;
+    use super::MAX_INTERSECTION_SEGMENTS;
+    use crate::database::Geometry;
+    use crate::database::Point;
+
+    fn line(coordinates: &[(f64, f64)]) -> Vec<Point> {
+        coordinates
+            .iter()
+            .map(|&(lat, lon)| Point::new(lat, lon))
+            .collect()
+    }
+
+    fn kinds(geometry: &Geometry) -> Vec<String> {
+        validate(geometry)
+            .iter()
+            .map(|issue| serde_json::to_value(issue).unwrap()["kind"].to_string())
+            .collect()
+    }
+
+    #[test]
+    fn accepts_closed_line() {
+        let square = line(&[(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0), (0.0, 0.0)]);
+
+        assert!(kinds(&Geometry::MultiLine(vec![square].into())).is_empty());
+    }
+
+    #[test]
+    fn accepts_closed_ring() {
+        let square = line(&[(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0), (0.0, 0.0)]);
+
+        assert!(kinds(&Geometry::MultiPolygon(vec![square].into())).is_empty());
+    }
+
+    #[test]
+    fn reports_crossing_segments() {
+        let figure_eight = line(&[(0.0, 0.0), (1.0, 1.0), (1.0, 0.0), (0.0, 1.0)]);
+
+        assert_eq!(
+            kinds(&Geometry::MultiLine(vec![figure_eight].into())),
+            vec!["\"SelfIntersection\""]
+        );
+    }
+
+    #[test]
+    fn reports_touching_segments() {
+        // Fourth point lies on the first segment
+        let touching = line(&[(0.0, 0.0), (0.0, 2.0), (1.0, 2.0), (0.0, 1.0)]);
+
+        assert_eq!(
+            kinds(&Geometry::MultiLine(vec![touching].into())),
+            vec!["\"SelfIntersection\""]
+        );
+    }
+
+    #[test]
+    fn skips_check_of_long_parts() {
+        let points: Vec<(f64, f64)> = (0..MAX_INTERSECTION_SEGMENTS + 2)
+            .map(|index| (0.0, index as f64 * 0.001))
+            .collect();
+        let allowed: Vec<(f64, f64)> = points[..MAX_INTERSECTION_SEGMENTS + 1].to_vec();
+
+        assert_eq!(
+            kinds(&Geometry::MultiLine(vec![line(&points)].into())),
+            vec!["\"CheckSkipped\""]
+        );
+        assert!(kinds(&Geometry::MultiLine(vec![line(&allowed)].into())).is_empty());
+    }
+}